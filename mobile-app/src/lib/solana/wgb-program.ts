@@ -3,6 +3,7 @@ import {
   Connection,
   PublicKey,
   SystemProgram,
+  SYSVAR_INSTRUCTIONS_PUBKEY,
   SYSVAR_RENT_PUBKEY,
   TransactionInstruction,
 } from '@solana/web3.js';
@@ -14,8 +15,8 @@ export const PROTOCOL_STATE_PDA = new PublicKey(env.wgbProtocolState);
 export const TOKEN_2022_PROGRAM_ID = new PublicKey('TokenzQdBNbLqP5VEhdkAS6EPFLC1PHnBqCXEpPxuEb');
 export const ASSOCIATED_TOKEN_PROGRAM_ID = new PublicKey('ATokenGPvbdGVxr1b2hvZbsiqW5xWH25efTNsLJA8knL');
 
-const BUY_WGB_DISCRIMINATOR = new Uint8Array([32, 152, 242, 112, 159, 221, 39, 173]);
-const BURN_WGB_DISCRIMINATOR = new Uint8Array([207, 123, 197, 201, 16, 132, 251, 254]);
+const BUY_WGB_DISCRIMINATOR = new Uint8Array([126, 70, 178, 202, 207, 87, 78, 215]);
+const BURN_WGB_DISCRIMINATOR = new Uint8Array([157, 117, 126, 72, 224, 52, 4, 173]);
 const INIT_USER_PROFILE_DISCRIMINATOR = new Uint8Array([148, 35, 126, 247, 28, 169, 135, 175]);
 
 function writeU64LE(value: bigint, buffer: Uint8Array, offset: number): void {
@@ -58,6 +59,14 @@ export function getUserProfilePDA(userPubkey: PublicKey): [PublicKey, number] {
   );
 }
 
+function getSingletonPDA(seed: string): PublicKey {
+  return PublicKey.findProgramAddressSync([Buffer.from(seed)], PROGRAM_ID)[0];
+}
+
+function getUserScopedPDA(seed: string, userPubkey: PublicKey): PublicKey {
+  return PublicKey.findProgramAddressSync([Buffer.from(seed), userPubkey.toBuffer()], PROGRAM_ID)[0];
+}
+
 export function getRedemptionRequestPDA(userPubkey: PublicKey, requestId: bigint): [PublicKey, number] {
   const idBuffer = Buffer.alloc(8);
   writeU64LE(requestId, idBuffer, 0);
@@ -93,11 +102,18 @@ export async function maybeCreateInitUserProfileInstruction(
   return createInitUserProfileInstruction(user);
 }
 
+// Optional buy_wgb accounts; omitted ones are sent as the program ID, which Anchor reads as None.
+export interface BuyWgbOptionalAccounts {
+  treasurySigner?: PublicKey;
+  useQuoteLock?: boolean;
+}
+
 export function createBuyWgbInstruction(
   buyer: PublicKey,
   buyerTokenAccount: PublicKey,
   solReceiver: PublicKey,
-  amount: bigint
+  amount: bigint,
+  optional: BuyWgbOptionalAccounts = {}
 ): TransactionInstruction {
   const data = new Uint8Array(16);
   data.set(BUY_WGB_DISCRIMINATOR, 0);
@@ -111,10 +127,24 @@ export function createBuyWgbInstruction(
       { pubkey: buyer, isSigner: true, isWritable: true },
       { pubkey: buyerTokenAccount, isSigner: false, isWritable: true },
       { pubkey: TREASURY, isSigner: false, isWritable: true },
+      optional.treasurySigner
+        ? { pubkey: optional.treasurySigner, isSigner: true, isWritable: false }
+        : { pubkey: PROGRAM_ID, isSigner: false, isWritable: false },
       { pubkey: solReceiver, isSigner: false, isWritable: true },
+      { pubkey: WGB_MINT, isSigner: false, isWritable: true },
       { pubkey: SystemProgram.programId, isSigner: false, isWritable: false },
       { pubkey: TOKEN_2022_PROGRAM_ID, isSigner: false, isWritable: false },
       { pubkey: userProfilePda, isSigner: false, isWritable: true },
+      { pubkey: getSingletonPDA('tier_config'), isSigner: false, isWritable: false },
+      { pubkey: getSingletonPDA('points_config'), isSigner: false, isWritable: false },
+      { pubkey: getUserScopedPDA('kyc_attestation', buyer), isSigner: false, isWritable: false },
+      { pubkey: getSingletonPDA('sol_split'), isSigner: false, isWritable: false },
+      { pubkey: getSingletonPDA('insurance_fund'), isSigner: false, isWritable: true },
+      { pubkey: SYSVAR_INSTRUCTIONS_PUBKEY, isSigner: false, isWritable: false },
+      { pubkey: getSingletonPDA('cpi_allowlist'), isSigner: false, isWritable: false },
+      optional.useQuoteLock
+        ? { pubkey: getUserScopedPDA('quote_lock', buyer), isSigner: false, isWritable: true }
+        : { pubkey: PROGRAM_ID, isSigner: false, isWritable: false },
     ],
     data: Buffer.from(data),
   });
@@ -144,15 +174,19 @@ export function createBurnWgbInstruction(
   user: PublicKey,
   userTokenAccount: PublicKey,
   amount: bigint,
-  requestId: bigint
+  requestId: bigint,
+  regionCode = 0,
+  priorityFeeLamports = BigInt(0)
 ): TransactionInstruction {
   const [redemptionPda] = getRedemptionRequestPDA(user, requestId);
   const [userProfilePda] = getUserProfilePDA(user);
 
-  const data = new Uint8Array(24);
+  const data = new Uint8Array(34);
   data.set(BURN_WGB_DISCRIMINATOR, 0);
   writeU64LE(amount, data, 8);
   writeU64LE(requestId, data, 16);
+  new DataView(data.buffer).setUint16(24, regionCode, true);
+  writeU64LE(priorityFeeLamports, data, 26);
 
   return new TransactionInstruction({
     programId: PROGRAM_ID,
@@ -165,6 +199,9 @@ export function createBurnWgbInstruction(
       { pubkey: SystemProgram.programId, isSigner: false, isWritable: false },
       { pubkey: TOKEN_2022_PROGRAM_ID, isSigner: false, isWritable: false },
       { pubkey: userProfilePda, isSigner: false, isWritable: true },
+      { pubkey: getSingletonPDA('points_config'), isSigner: false, isWritable: false },
+      { pubkey: getUserScopedPDA('redemption_block', user), isSigner: false, isWritable: false },
+      { pubkey: getSingletonPDA('redemption_stats'), isSigner: false, isWritable: true },
     ],
     data: Buffer.from(data),
   });
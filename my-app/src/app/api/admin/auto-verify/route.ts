@@ -87,7 +87,10 @@ const WGB_IDL = {
       discriminator: [54, 241, 46, 84, 4, 212, 46, 94],
       accounts: [
         { name: "protocol_state", writable: true },
-        { name: "operator", signer: true },
+        // Writable so the operator rebate can be paid
+        { name: "operator", writable: true, signer: true },
+        { name: "proof_history", writable: true },
+        { name: "withdrawal_throttle", writable: true },
       ],
       args: [
         { name: "proof_hash", type: "bytes" },
@@ -378,12 +381,25 @@ export async function POST(request: Request) {
       .update(Buffer.from(String(serials.length)))
       .digest();
 
+    // ProofHistory PDA (seeds [b"proof_history"]) — the ring buffer submit_proof appends to
+    const [proofHistoryPda] = PublicKey.findProgramAddressSync(
+      [Buffer.from("proof_history")],
+      programId
+    );
+    // WithdrawalThrottle PDA — the operator rebate counts against it
+    const [withdrawalThrottlePda] = PublicKey.findProgramAddressSync(
+      [Buffer.from("withdrawal_throttle")],
+      programId
+    );
+
     log("Submitting submit_proof to Solana...");
     const proofTx = await (program.methods as any)
       .submitProof(proofHash, new BN(serials.length))
       .accountsPartial({
         protocolState: protocolStatePda,
         operator: authority.publicKey,
+        proofHistory: proofHistoryPda,
+        withdrawalThrottle: withdrawalThrottlePda,
       })
       .rpc();
 
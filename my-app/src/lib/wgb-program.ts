@@ -8,6 +8,7 @@ import {
   PublicKey,
   TransactionInstruction,
   SystemProgram,
+  SYSVAR_INSTRUCTIONS_PUBKEY,
   LAMPORTS_PER_SOL,
 } from '@solana/web3.js';
 import {
@@ -89,6 +90,34 @@ function writeU64LE(value: bigint, buffer: Uint8Array, offset: number): void {
   }
 }
 
+/**
+ * Derive a singleton protocol PDA (points_config, tier_config, sol_split, ...)
+ */
+function getSingletonPDA(seed: string): PublicKey {
+  return PublicKey.findProgramAddressSync([Buffer.from(seed)], PROGRAM_ID())[0];
+}
+
+/**
+ * Derive a per-user protocol PDA (kyc_attestation, quote_lock, redemption_block, ...)
+ */
+function getUserScopedPDA(seed: string, userPubkey: PublicKey): PublicKey {
+  return PublicKey.findProgramAddressSync(
+    [Buffer.from(seed), userPubkey.toBuffer()],
+    PROGRAM_ID()
+  )[0];
+}
+
+/**
+ * Optional accounts of buy_wgb. Anything left out is passed as the program ID,
+ * which Anchor reads as `None`.
+ */
+export interface BuyWgbOptionalAccounts {
+  /** Treasury owner co-signer, required under TREASURY_OWNER_AUTHORITY */
+  treasurySigner?: PublicKey;
+  /** Pass true to settle against the buyer's QuoteLock from lock_quote */
+  useQuoteLock?: boolean;
+}
+
 /**
  * Create a buy_wgb instruction
  * 
@@ -96,12 +125,14 @@ function writeU64LE(value: bigint, buffer: Uint8Array, offset: number): void {
  * @param buyerTokenAccount - The buyer's WGB token account
  * @param solReceiver - The SOL receiver address
  * @param amount - Amount of WGB tokens to buy (in base units, 0 decimals)
+ * @param optional - Optional accounts; omitted ones are sent as program-ID placeholders
  */
 export function createBuyWgbInstruction(
   buyer: PublicKey,
   buyerTokenAccount: PublicKey,
   solReceiver: PublicKey,
-  amount: bigint
+  amount: bigint,
+  optional: BuyWgbOptionalAccounts = {}
 ): TransactionInstruction {
   // Serialize instruction data: discriminator + amount (u64)
   const data = new Uint8Array(16);
@@ -112,17 +143,30 @@ export function createBuyWgbInstruction(
 
   const [userProfilePda] = getUserProfilePDA(buyer);
 
-  // Account metas for buy_wgb instruction (must match on-chain BuyWGB struct order)
+  // Account metas for buy_wgb instruction (must match the IDL / on-chain BuyWGB struct order)
   const keys = [
     { pubkey: PROTOCOL_STATE_PDA(), isSigner: false, isWritable: true },
     { pubkey: buyer, isSigner: true, isWritable: true },
     { pubkey: buyerTokenAccount, isSigner: false, isWritable: true },
     { pubkey: TREASURY(), isSigner: false, isWritable: true },
+    optional.treasurySigner
+      ? { pubkey: optional.treasurySigner, isSigner: true, isWritable: false }
+      : { pubkey: PROGRAM_ID(), isSigner: false, isWritable: false },
     { pubkey: solReceiver, isSigner: false, isWritable: true },
     { pubkey: WGB_MINT(), isSigner: false, isWritable: true },
     { pubkey: SystemProgram.programId, isSigner: false, isWritable: false },
     { pubkey: TOKEN_2022_PROGRAM_ID, isSigner: false, isWritable: false },
     { pubkey: userProfilePda, isSigner: false, isWritable: true },
+    { pubkey: getSingletonPDA('tier_config'), isSigner: false, isWritable: false },
+    { pubkey: getSingletonPDA('points_config'), isSigner: false, isWritable: false },
+    { pubkey: getUserScopedPDA('kyc_attestation', buyer), isSigner: false, isWritable: false },
+    { pubkey: getSingletonPDA('sol_split'), isSigner: false, isWritable: false },
+    { pubkey: getSingletonPDA('insurance_fund'), isSigner: false, isWritable: true },
+    { pubkey: SYSVAR_INSTRUCTIONS_PUBKEY, isSigner: false, isWritable: false },
+    { pubkey: getSingletonPDA('cpi_allowlist'), isSigner: false, isWritable: false },
+    optional.useQuoteLock
+      ? { pubkey: getUserScopedPDA('quote_lock', buyer), isSigner: false, isWritable: true }
+      : { pubkey: PROGRAM_ID(), isSigner: false, isWritable: false },
   ];
 
   return new TransactionInstruction({
@@ -227,23 +271,29 @@ export async function maybeCreateInitUserProfileInstruction(
  * @param userTokenAccount - The user's WGB token account
  * @param amount - Amount of WGB tokens to burn
  * @param requestId - Sequential redemption request ID
+ * @param regionCode - Coarse delivery region bucket for fulfillers (0 = unspecified)
+ * @param priorityFeeLamports - Optional SOL bid escrowed for whoever fulfils the order
  */
 export function createBurnWgbInstruction(
   user: PublicKey,
   userTokenAccount: PublicKey,
   amount: bigint,
-  requestId: bigint
+  requestId: bigint,
+  regionCode = 0,
+  priorityFeeLamports = BigInt(0)
 ): TransactionInstruction {
   const [redemptionPda] = getRedemptionRequestPDA(user, requestId);
   const [userProfilePda] = getUserProfilePDA(user);
 
-  // Serialize instruction data: discriminator + amount (u64) + request_id (u64)
-  const data = new Uint8Array(24);
+  // Serialize instruction data: discriminator + amount (u64) + request_id (u64) + region_code (u16) + priority fee (u64)
+  const data = new Uint8Array(34);
   data.set(BURN_WGB_DISCRIMINATOR, 0);
   writeU64LE(amount, data, 8);
   writeU64LE(requestId, data, 16);
+  new DataView(data.buffer).setUint16(24, regionCode, true);
+  writeU64LE(priorityFeeLamports, data, 26);
 
-  // Account metas for burn_wgb instruction
+  // Account metas for burn_wgb instruction (must match the IDL / on-chain BurnWGB struct order)
   const keys = [
     { pubkey: PROTOCOL_STATE_PDA(), isSigner: false, isWritable: true },
     { pubkey: user, isSigner: true, isWritable: true },
@@ -253,6 +303,9 @@ export function createBurnWgbInstruction(
     { pubkey: SystemProgram.programId, isSigner: false, isWritable: false },
     { pubkey: TOKEN_2022_PROGRAM_ID, isSigner: false, isWritable: false },
     { pubkey: userProfilePda, isSigner: false, isWritable: true },
+    { pubkey: getSingletonPDA('points_config'), isSigner: false, isWritable: false },
+    { pubkey: getUserScopedPDA('redemption_block', user), isSigner: false, isWritable: false },
+    { pubkey: getSingletonPDA('redemption_stats'), isSigner: false, isWritable: true },
   ];
 
   return new TransactionInstruction({
@@ -1,14 +1,158 @@
 use anchor_lang::prelude::*;
 use anchor_lang::system_program;
+use anchor_lang::solana_program::instruction::{get_stack_height, TRANSACTION_LEVEL_STACK_HEIGHT};
 use anchor_lang::solana_program::program::invoke;
 use anchor_lang::solana_program::program::invoke_signed;
 use anchor_lang::solana_program::rent::Rent;
-use anchor_spl::token_2022::{self, MintTo, Transfer, TransferChecked, Burn, Token2022};
-use anchor_spl::token_interface::{Mint, TokenAccount};
+use anchor_spl::token_2022::{self, MintToChecked, TransferChecked, Burn, Token2022};
+use anchor_spl::token_interface::{
+    self as token_interface, Mint, TokenAccount, TokenInterface, TokenMetadataUpdateField,
+    token_metadata_update_field,
+};
 use anchor_spl::token_2022::spl_token_2022::extension::transfer_fee::instruction::set_transfer_fee;
+use solana_sha256_hasher::hashv;
+use solana_instructions_sysvar::{load_current_index_checked, load_instruction_at_checked};
+use solana_sdk_ids::ed25519_program;
+use spl_token_metadata_interface::state::Field;
 
 declare_id!("9xZaf2jccNqsfStFKqcXS9ubKfcZcqNbCmgPuHDLLtd6");
 
+/// Legacy (pre-V2) deployment of the protocol — see programs/w3b_protocol/src/lib.rs
+pub const LEGACY_PROGRAM_ID: Pubkey = pubkey!("Fg6PaFpoGXkYsidMpWTK6W2BeZ7FEfcYkg476zPFsLnS");
+
+/// Total bytes (discriminator included) of a ProtocolState after migrate_v2
+pub const PROTOCOL_STATE_V2_SIZE: usize = 512;
+
+/// Current ProtocolState allocation; fields after `_reserved` live in the V3 region beyond 512 bytes.
+/// Existing deployments grow to this size with `resize_protocol_state`.
+pub const PROTOCOL_STATE_SIZE: usize = 1024;
+
+/// Interpretation of `ProtocolState._reserved` understood by this build (see RESERVED_SLOTS)
+pub const RESERVED_SCHEMA_VERSION: u8 = 1;
+
+/// Layout of ProtocolSnapshot.state emitted by this build — bump whenever ProtocolState gains fields
+pub const SNAPSHOT_SCHEMA_VERSION: u8 = 1;
+
+/// Decimals the WGB mint must have. Caps, min buys, bar units and reserve counts are all
+/// whole tokens (one token = one serial), so initialize_v2 rejects any other mint.
+pub const WGB_DECIMALS: u8 = 0;
+
+/// Most tokens one buy (or quote lock) may cover — whole tokens, given WGB_DECIMALS
+pub const MAX_TOKENS_PER_BUY: u64 = 1000;
+
+/// Upper bound on loyalty tiers — fixes the TierConfig account size
+pub const MAX_TIERS: usize = 8;
+
+/// Reserve proofs older than this block supply increases unless max_proof_age_secs overrides it
+pub const DEFAULT_MAX_PROOF_AGE_SECS: i64 = 48 * 3600;
+
+/// Opt-in deployment guardrail: build with `WGB_ALLOWED_MINT=<mint>` and initialize_v2
+/// refuses any other mint. Left unset, any Token-2022 mint is accepted.
+pub const ALLOWED_MINT: Option<Pubkey> = match option_env!("WGB_ALLOWED_MINT") {
+    Some(mint) => Some(Pubkey::from_str_const(mint)),
+    None => None,
+};
+
+/// How reserves back the supply (ProtocolState.reserve_unit) — see backing_reserves
+pub const RESERVE_UNIT_SERIALS: u8 = 0;
+pub const RESERVE_UNIT_WEIGHT: u8 = 1;
+
+/// How burn_wgb handles amounts that aren't whole bars (ProtocolState.redemption_rounding)
+/// Only applies once redemption_bar_unit is set.
+pub const REDEMPTION_ROUND_FLOOR: u8 = 0;
+pub const REDEMPTION_ROUND_NEAREST: u8 = 1;
+pub const REDEMPTION_ROUND_REJECT: u8 = 2;
+
+/// Who owns the treasury token account (ProtocolState.treasury_owner_mode). Under
+/// AUTHORITY, buys must be co-signed by the authority as treasury_signer.
+pub const TREASURY_OWNER_PDA: u8 = 0;
+pub const TREASURY_OWNER_AUTHORITY: u8 = 1;
+
+/// What tier thresholds are measured against (ProtocolState.tier_basis)
+pub const TIER_BASIS_POINTS: u8 = 0;
+pub const TIER_BASIS_VOLUME: u8 = 1;
+
+/// set_wgb_price swing allowed when a role's band is unset (20%)
+pub const DEFAULT_PRICE_BAND_BPS: u16 = 2000;
+
+/// Reserve coverage yield needs when yield_min_coverage_bps is unset (1:1)
+pub const DEFAULT_YIELD_MIN_COVERAGE_BPS: u16 = 10_000;
+
+/// Ceiling for operator_rebate_lamports so a bad setting can't drain the rebate pool in a few proofs
+pub const MAX_OPERATOR_REBATE_LAMPORTS: u64 = 1_000_000;
+
+/// Compute units a batch instruction gets without a compute-budget request
+pub const BATCH_COMPUTE_BUDGET: usize = 200_000;
+/// Rough fixed cost of a batch instruction (account checks, stats, event)
+pub const BATCH_BASE_COMPUTE_UNITS: usize = 40_000;
+/// Rough cost per item (deserialize, PDA re-derivation, profile update, serialize)
+pub const BATCH_ITEM_COMPUTE_UNITS: usize = 16_000;
+/// Most items any remaining_accounts batch instruction takes in one transaction
+pub const MAX_BATCH: usize = (BATCH_COMPUTE_BUDGET - BATCH_BASE_COMPUTE_UNITS) / BATCH_ITEM_COMPUTE_UNITS;
+
+/// Upper bound on AdminMultisig signers — fixes the account size
+pub const MAX_MULTISIG_SIGNERS: usize = 5;
+
+/// Most SOL receivers a buy splits proceeds across (one system transfer each)
+pub const MAX_SOL_RECEIVERS: usize = 5;
+
+/// Most roots verify_root_lineage will replay in one call (compute budget)
+pub const MAX_LINEAGE_PROOF: usize = 64;
+
+/// Longest raw serial verify_serial_proof accepts. Internal reserve-tree nodes hash exactly
+/// 64 bytes, so a shorter preimage can only ever be a leaf.
+pub const MAX_SERIAL_LEN: usize = 63;
+
+/// How long a lock_quote price holds when quote_lock_secs is unset
+pub const DEFAULT_QUOTE_LOCK_SECS: i64 = 60;
+
+/// Longest price hold set_time_windows accepts — quotes must track the live price
+pub const MAX_QUOTE_LOCK_SECS: i64 = 600;
+
+/// Longest redemption deadline or reward holdback set_time_windows accepts (180 days)
+pub const MAX_TIME_WINDOW_SECS: i64 = 180 * 86_400;
+
+/// Most distinct fulfillers one FulfillerContributions account can list — fixes its size
+pub const MAX_ORDER_FULFILLERS: usize = 4;
+
+/// Streak lengths with their own bonus in ProtocolState.streak_bonus_points; longer
+/// streaks keep earning the last entry
+pub const STREAK_BONUS_DAYS: usize = 7;
+
+/// Who may reach the buy path through CPI (ProtocolState.cpi_gate) — see ensure_cpi_allowed
+pub const CPI_GATE_OPEN: u8 = 0;
+pub const CPI_GATE_BLOCK_ALL: u8 = 1;
+pub const CPI_GATE_ALLOWLIST: u8 = 2;
+
+/// Upper bound on CpiAllowlist entries — fixes the account size
+pub const MAX_CPI_ALLOWLIST: usize = 8;
+
+/// Proof attestations kept in the ProofHistory ring buffer — fixes the account size
+pub const PROOF_HISTORY_LEN: usize = 16;
+
+/// Event verbosity levels (ProtocolState.event_verbosity) — see set_event_verbosity
+pub const EVENTS_FULL: u8 = 0;
+pub const EVENTS_REDUCED: u8 = 1;
+pub const EVENTS_MINIMAL: u8 = 2;
+
+/// Built-in thresholds (Bronze, Silver, Gold, Platinum) used until a TierConfig is created
+pub const DEFAULT_TIER_THRESHOLDS: [u64; 4] = [0, 100, 500, 2000];
+
+/// Log the compute units left at `label`. Only compiled in with the `compute-debug` feature,
+/// so production builds carry no extra logging. To profile under the TS tests:
+/// `anchor build -- --features compute-debug && anchor test --skip-build`, then read the
+/// "CU ..." lines from the transaction logs.
+macro_rules! log_compute_units {
+    ($label:expr) => {
+        #[cfg(feature = "compute-debug")]
+        msg!(
+            "CU {}: {} remaining",
+            $label,
+            ::solana_program::compute_units::sol_remaining_compute_units()
+        );
+    };
+}
+
 #[program]
 pub mod wgb_protocol {
     use super::*;
@@ -16,7 +160,18 @@ pub mod wgb_protocol {
     // ==================== ADMIN / MIGRATION ====================
 
     /// Initialize the protocol V2 (New Deployment)
-    pub fn initialize_v2(ctx: Context<InitializeV2>) -> Result<()> {
+    /// `expected_decimals` must match the mint and equal WGB_DECIMALS — caps and pricing
+    /// assume whole-token units.
+    /// Builds pinned with WGB_ALLOWED_MINT only accept that mint.
+    pub fn initialize_v2(ctx: Context<InitializeV2>, expected_decimals: u8) -> Result<()> {
+        require!(
+            expected_decimals == WGB_DECIMALS && ctx.accounts.wgb_mint.decimals == expected_decimals,
+            WGBError::DecimalsMismatch
+        );
+        if let Some(allowed_mint) = ALLOWED_MINT {
+            require_keys_eq!(ctx.accounts.wgb_mint.key(), allowed_mint, WGBError::MintNotAllowed);
+        }
+
         let state = &mut ctx.accounts.protocol_state;
         state.authority = ctx.accounts.authority.key();
         state.operator = ctx.accounts.authority.key(); // Default operator = admin
@@ -34,59 +189,129 @@ pub mod wgb_protocol {
         
         state.is_paused = false;
         state.bump = ctx.bumps.protocol_state;
+        update_coverage(state);
 
         msg!("WGB Protocol V2 Initialized");
         Ok(())
     }
 
-    /// Set the Operator key (Admin only)
-    pub fn set_operator(ctx: Context<AdminOnly>, new_operator: Pubkey) -> Result<()> {
-        ctx.accounts.protocol_state.operator = new_operator;
+    /// Pre-flight for deployment scripts (Public, read-only): fails with AlreadyInitialized,
+    /// logging the existing authority, when the ProtocolState PDA is already set up.
+    /// Reads only the V2 prefix so un-resized V2 accounts are recognised too.
+    pub fn ensure_initialized(ctx: Context<EnsureInitialized>) -> Result<()> {
+        let info = &ctx.accounts.protocol_state;
+        if info.owner == &crate::ID && !info.data_is_empty() {
+            let data = info.try_borrow_data()?;
+            if data.len() >= 8 + 32 && data[..8] == *ProtocolState::DISCRIMINATOR {
+                let authority = Pubkey::try_from(&data[8..40]).map_err(|_| WGBError::InvalidConfig)?;
+                msg!("Protocol already initialized; authority {}", authority);
+                return err!(WGBError::AlreadyInitialized);
+            }
+        }
+        msg!("Protocol state not initialized");
+        Ok(())
+    }
+
+    /// Set a backup operator that may sign every operator-gated instruction (Admin only)
+    /// Pubkey::default() removes it.
+    pub fn set_backup_operator(ctx: Context<AdminOnly>, backup_operator: Pubkey) -> Result<()> {
+        let previous = ctx.accounts.protocol_state.backup_operator;
+        ctx.accounts.protocol_state.backup_operator = backup_operator;
+        emit_role_key_updated("backup_operator", previous, backup_operator, ctx.accounts.authority.key())?;
+        msg!("Backup operator set to {}", backup_operator);
+        Ok(())
+    }
+
+    /// Swap the backup operator into the primary slot (Admin only) — e.g. after the hot key is lost
+    pub fn promote_backup_operator(ctx: Context<AdminOnly>) -> Result<()> {
+        let state = &mut ctx.accounts.protocol_state;
+        require!(state.backup_operator != Pubkey::default(), WGBError::InvalidConfig);
+
+        let previous_operator = state.operator;
+        state.operator = state.backup_operator;
+        state.backup_operator = Pubkey::default();
+        state.last_operator_change = Clock::get()?.unix_timestamp;
+
+        emit!(BackupOperatorPromoted {
+            previous_operator,
+            new_operator: state.operator,
+            actor: ctx.accounts.authority.key(),
+            timestamp: Clock::get()?.unix_timestamp,
+        });
+
+        msg!("Backup operator {} promoted to operator", state.operator);
+        Ok(())
+    }
+
+    /// Set the Operator key (Admin + multisig)
+    pub fn set_operator(ctx: Context<MultisigAdminOnly>, new_operator: Pubkey) -> Result<()> {
+        require_multisig(&ctx.accounts.admin_multisig, &ctx.accounts.authority, ctx.remaining_accounts)?;
+        let now = Clock::get()?.unix_timestamp;
+        let state = &mut ctx.accounts.protocol_state;
+        let previous_operator = state.operator;
+        state.operator = new_operator;
+        state.last_operator_change = now;
+
+        emit!(OperatorChanged {
+            previous_operator,
+            new_operator,
+            actor: ctx.accounts.authority.key(),
+            timestamp: now,
+        });
+
         msg!("Operator updated to {}", new_operator);
         Ok(())
     }
 
+    /// Hand the operator role to a new key (current Operator) — no admin signature needed
+    /// Limited to one rotation per operator_rotation_cooldown_secs, counted from the last
+    /// operator change of any kind; the admin paths are not held by the cooldown.
+    pub fn operator_self_rotate(ctx: Context<OperatorSelfRotate>, new_operator: Pubkey) -> Result<()> {
+        let now = Clock::get()?.unix_timestamp;
+        let state = &mut ctx.accounts.protocol_state;
+        require!(
+            new_operator != Pubkey::default() && new_operator != state.operator,
+            WGBError::InvalidConfig
+        );
+        require!(
+            now.saturating_sub(state.last_operator_change) >= state.operator_rotation_cooldown_secs,
+            WGBError::OperatorRotationCooldown
+        );
+
+        let previous_operator = state.operator;
+        state.operator = new_operator;
+        state.last_operator_change = now;
+
+        emit!(OperatorChanged {
+            previous_operator,
+            new_operator,
+            actor: ctx.accounts.operator.key(),
+            timestamp: now,
+        });
+
+        msg!("Operator rotated itself to {}", new_operator);
+        Ok(())
+    }
+
+    /// Minimum seconds between operator self-rotations; 0 = no cooldown (Admin only)
+    pub fn set_operator_rotation_cooldown(ctx: Context<AdminOnly>, cooldown_secs: i64) -> Result<()> {
+        require!(cooldown_secs >= 0, WGBError::InvalidConfig);
+        ctx.accounts.protocol_state.operator_rotation_cooldown_secs = cooldown_secs;
+        emit_config_updated("operator_rotation_cooldown", ctx.accounts.authority.key())?;
+        msg!("Operator rotation cooldown set to {}s", cooldown_secs);
+        Ok(())
+    }
+
     /// Migration: Upgrade V1 State to V2 (Admin only)
     pub fn migrate_v2(ctx: Context<MigrateV2>) -> Result<()> {
-        let protocol_state = &ctx.accounts.protocol_state;
-        let authority = &ctx.accounts.authority;
-
         // 0. Validate authority by reading raw bytes (authority = first Pubkey after 8-byte discriminator)
-        {
-            let data = protocol_state.try_borrow_data()?;
-            require!(data.len() >= 40, WGBError::Unauthorized);
-            let stored_authority = Pubkey::try_from(&data[8..40])
-                .map_err(|_| error!(WGBError::Unauthorized))?;
-            require!(stored_authority == authority.key(), WGBError::Unauthorized);
-        }
+        check_raw_authority(&ctx.accounts.protocol_state, &ctx.accounts.authority)?;
 
         // 1. Resize account
         // V1 size: 218 bytes (approx) -> V2 size: ~400 bytes
         // We reserve extra space (512 bytes total) to avoid future resizing
-        let new_size = 512;
-        
-        let rent = Rent::get()?;
-        let current_lamports = protocol_state.lamports();
-        let new_min_rent = rent.minimum_balance(new_size);
-
-        if current_lamports < new_min_rent {
-            let diff = new_min_rent - current_lamports;
-            let transfer_ix = anchor_lang::solana_program::system_instruction::transfer(
-                authority.key,
-                protocol_state.key,
-                diff,
-            );
-            invoke(
-                &transfer_ix,
-                &[
-                    authority.to_account_info(),
-                    protocol_state.to_account_info(),
-                    ctx.accounts.system_program.to_account_info(),
-                ],
-            )?;
-        }
-
-        protocol_state.realloc(new_size, false)?;
+        let new_size = PROTOCOL_STATE_V2_SIZE;
+        grow_protocol_state(&ctx, new_size)?;
 
         // 2. Initialize new fields manually (unsafe byte manipulation required for migration)
         // Note: In a real migration we'd deserialize, modify, serialize. 
@@ -100,6 +325,36 @@ pub mod wgb_protocol {
         Ok(())
     }
 
+    /// Grow ProtocolState to PROTOCOL_STATE_SIZE (Admin only)
+    /// Run once after upgrading to a build whose state extends past the current allocation;
+    /// the new tail is zero-filled, which every appended field treats as its default.
+    pub fn resize_protocol_state(ctx: Context<MigrateV2>) -> Result<()> {
+        check_raw_authority(&ctx.accounts.protocol_state, &ctx.accounts.authority)?;
+
+        let current_size = ctx.accounts.protocol_state.data_len();
+        require!(current_size < PROTOCOL_STATE_SIZE, WGBError::InvalidConfig);
+
+        grow_protocol_state(&ctx, PROTOCOL_STATE_SIZE)?;
+
+        msg!("Protocol state resized {} -> {} bytes", current_size, PROTOCOL_STATE_SIZE);
+        Ok(())
+    }
+
+    /// Fix Bump: Rewrite ProtocolState.bump with the canonical bump (Admin only)
+    /// Recovery path if the stored bump was corrupted (e.g. by a raw-byte migration),
+    /// which would otherwise fail every `bump = protocol_state.bump` constraint.
+    pub fn fix_bump(ctx: Context<FixBump>) -> Result<()> {
+        let (pda, bump) = Pubkey::find_program_address(&[b"protocol_state"], ctx.program_id);
+        require_keys_eq!(pda, ctx.accounts.protocol_state.key(), WGBError::InvalidConfig);
+
+        let state = &mut ctx.accounts.protocol_state;
+        let old_bump = state.bump;
+        state.bump = bump;
+
+        msg!("Protocol state bump fixed {} -> {}", old_bump, bump);
+        Ok(())
+    }
+
     /// Fix V2 Layout: Remap V1 field offsets to V2 positions (Admin only, one-time)
     /// V1 inserted `operator` between authority and wgb_mint, shifting all offsets.
     /// This reads V1 data and writes it to V2 positions in the same buffer.
@@ -200,31 +455,90 @@ pub mod wgb_protocol {
             data[266] = is_paused;
             // [267]      bump
             data[267] = bump;
-            // [268..332] _reserved = 0 (already zeroed)
+            // [268..512] post-V2 fields + _reserved = 0 (already zeroed)
         }
 
         msg!("V2 layout fix applied: data remapped from V1 offsets to V2");
         Ok(())
     }
 
+    /// Import career stats from the legacy program's ProtocolState (Admin only, one-time)
+    /// Legacy layout: [8..40] authority, [40..72] mint, [72..80] total_minted, [80..88] total_burned
+    pub fn import_legacy_stats(ctx: Context<ImportLegacy>) -> Result<()> {
+        require!(!ctx.accounts.protocol_state.legacy_imported, WGBError::LegacyAlreadyImported);
+
+        let total_minted;
+        let total_burned;
+        {
+            let data = ctx.accounts.legacy_state.try_borrow_data()?;
+            require!(data.len() >= 89, WGBError::InvalidLegacyState);
+            // Both programs name the account `ProtocolState`, so the discriminators match
+            require!(
+                data[..8] == *ProtocolState::DISCRIMINATOR,
+                WGBError::InvalidLegacyState
+            );
+
+            let legacy_authority = Pubkey::try_from(&data[8..40])
+                .map_err(|_| error!(WGBError::InvalidLegacyState))?;
+            require_keys_eq!(
+                legacy_authority,
+                ctx.accounts.authority.key(),
+                WGBError::Unauthorized
+            );
+
+            let mut buf8 = [0u8; 8];
+            buf8.copy_from_slice(&data[72..80]);
+            total_minted = u64::from_le_bytes(buf8);
+            buf8.copy_from_slice(&data[80..88]);
+            total_burned = u64::from_le_bytes(buf8);
+        }
+
+        let state = &mut ctx.accounts.protocol_state;
+        state.total_supply = total_minted
+            .checked_sub(total_burned)
+            .ok_or(WGBError::MathOverflow)?;
+        state.total_burned = total_burned;
+        state.legacy_imported = true;
+        update_coverage(state);
+
+        emit!(LegacyStatsImported {
+            legacy_state: ctx.accounts.legacy_state.key(),
+            total_minted,
+            total_burned,
+            actor: ctx.accounts.authority.key(),
+            timestamp: Clock::get()?.unix_timestamp,
+        });
+
+        msg!("Legacy stats imported: minted {}, burned {}", total_minted, total_burned);
+        Ok(())
+    }
+
     // ==================== OPERATOR OPS (TIER 1 HARDENING) ====================
 
     /// Update Merkle Root (Operator)
+    /// Refused while a ProofChallenge is open, so the challenged root can't be swapped out
+    /// from under it before resolve_challenge.
     pub fn update_merkle_root(
         ctx: Context<OperatorOnly>,
         new_root: [u8; 32],
         total_serials: u64,
     ) -> Result<()> {
+        note_backup_operator(&ctx.accounts.protocol_state, &ctx.accounts.operator.key());
         require!(!ctx.accounts.protocol_state.is_paused, WGBError::ProtocolPaused);
+        require!(!ctx.accounts.protocol_state.proof_challenged, WGBError::ProofUnderChallenge);
 
         let state = &mut ctx.accounts.protocol_state;
         state.current_merkle_root = new_root;
+        extend_lineage(state, new_root);
         state.proven_reserves = total_serials;
         state.last_root_update = Clock::get()?.unix_timestamp;
+        update_coverage(state);
 
         emit!(MerkleRootUpdated {
             root: new_root,
             total_serials,
+            lineage_hash: state.lineage_hash,
+            actor: ctx.accounts.operator.key(),
             timestamp: state.last_root_update,
         });
 
@@ -233,11 +547,13 @@ pub mod wgb_protocol {
 
     /// Submit Proof (Operator) - Now Validates Logic!
     pub fn submit_proof(
-        ctx: Context<OperatorOnly>,
+        ctx: Context<SubmitProof>,
         proof_hash: Vec<u8>,
         claimed_reserves: u64,
     ) -> Result<()> {
+        note_backup_operator(&ctx.accounts.protocol_state, &ctx.accounts.operator.key());
         let state = &mut ctx.accounts.protocol_state;
+        require!(!state.proof_challenged, WGBError::ProofUnderChallenge);
         
         // CRitICAL CHECK: Claim must match what we already know from the Merkle update
         require!(
@@ -245,828 +561,7529 @@ pub mod wgb_protocol {
             WGBError::ReserveCountMismatch
         );
 
+        // Persist the latest attestation so clients don't need log history to read it.
+        // A 32-byte payload is already a digest and is stored as-is; any other length is
+        // stored as sha256(payload), so every payload the instruction accepted still lands.
+        state.last_proof_hash = match <[u8; 32]>::try_from(proof_hash.as_slice()) {
+            Ok(digest) => digest,
+            Err(_) => hashv(&[&proof_hash]).to_bytes(),
+        };
+        state.last_claimed_reserves = claimed_reserves;
         state.last_proof_timestamp = Clock::get()?.unix_timestamp;
+        record_proof(&ctx.accounts.proof_history, state.last_proof_hash, state.last_proof_timestamp)?;
+
+        let rebate_lamports = pay_operator_rebate(
+            &mut ctx.accounts.protocol_state,
+            &ctx.accounts.operator,
+            &ctx.accounts.withdrawal_throttle,
+        )?;
+        let state = &ctx.accounts.protocol_state;
 
         emit!(ProofSubmitted {
             merkle_root: state.current_merkle_root,
             claimed_reserves,
             proof_hash,
+            actor: ctx.accounts.operator.key(),
             timestamp: state.last_proof_timestamp,
+            rebate_lamports,
         });
 
         Ok(())
     }
 
-    /// Set Price with Bounds (Operator)
-    pub fn set_wgb_price(ctx: Context<OperatorOnly>, price_lamports: u64) -> Result<()> {
-        require!(price_lamports > 0, WGBError::InvalidPrice);
-        
+    /// Attest Reserves (Operator) - Root, reserves and proof in one atomic update
+    /// Avoids the window where update_merkle_root has landed but submit_proof hasn't.
+    pub fn attest_reserves(
+        ctx: Context<SubmitProof>,
+        new_root: [u8; 32],
+        total_serials: u64,
+        proof_hash: [u8; 32],
+    ) -> Result<()> {
+        note_backup_operator(&ctx.accounts.protocol_state, &ctx.accounts.operator.key());
+        require!(!ctx.accounts.protocol_state.is_paused, WGBError::ProtocolPaused);
+        require!(!ctx.accounts.protocol_state.proof_challenged, WGBError::ProofUnderChallenge);
+
+        let now = Clock::get()?.unix_timestamp;
         let state = &mut ctx.accounts.protocol_state;
-        let current = state.wgb_price_lamports;
+        state.current_merkle_root = new_root;
+        extend_lineage(state, new_root);
+        state.proven_reserves = total_serials;
+        state.last_root_update = now;
+        update_coverage(state);
+        state.last_proof_hash = proof_hash;
+        state.last_claimed_reserves = total_serials;
+        state.last_proof_timestamp = now;
+        record_proof(&ctx.accounts.proof_history, proof_hash, now)?;
+
+        let rebate_lamports = pay_operator_rebate(
+            &mut ctx.accounts.protocol_state,
+            &ctx.accounts.operator,
+            &ctx.accounts.withdrawal_throttle,
+        )?;
 
-        // Bounds Check: Max 20% swing allowed automatically
-        if current > 0 {
-            let max_change = current / 5; // 20%
-            let diff = if price_lamports > current {
-                price_lamports - current
-            } else {
-                current - price_lamports
-            };
-            require!(diff <= max_change, WGBError::PriceChangeExceedsLimit);
+        emit!(MerkleRootUpdated {
+            root: new_root,
+            total_serials,
+            lineage_hash: ctx.accounts.protocol_state.lineage_hash,
+            actor: ctx.accounts.operator.key(),
+            timestamp: now,
+        });
+        emit!(ProofSubmitted {
+            merkle_root: new_root,
+            claimed_reserves: total_serials,
+            proof_hash: proof_hash.to_vec(),
+            actor: ctx.accounts.operator.key(),
+            timestamp: now,
+            rebate_lamports,
+        });
+
+        Ok(())
+    }
+
+    /// Attest the total reserve weight in 1/10_000 g (Operator) — the backing under RESERVE_UNIT_WEIGHT
+    pub fn attest_reserve_weight(ctx: Context<OperatorOnly>, reserve_weight: u64) -> Result<()> {
+        note_backup_operator(&ctx.accounts.protocol_state, &ctx.accounts.operator.key());
+        let state = &mut ctx.accounts.protocol_state;
+        state.reserve_weight = reserve_weight;
+        update_coverage(state);
+
+        emit!(ReserveWeightAttested {
+            reserve_weight,
+            backing_reserves: backing_reserves(state),
+            actor: ctx.accounts.operator.key(),
+            timestamp: Clock::get()?.unix_timestamp,
+        });
+
+        msg!("Reserve weight attested: {}", reserve_weight);
+        Ok(())
+    }
+
+    /// Apply a signed change to proven_reserves between full root updates (Operator)
+    /// The result is clamped at zero and, unless allow_reserve_shrink is set, a decrease never
+    /// takes reserves below total_supply. Counts as a fresh attestation for proof-age checks;
+    /// `reason_hash` commits to the off-chain record of why reserves moved.
+    pub fn adjust_reserves(ctx: Context<OperatorOnly>, delta: i64, reason_hash: [u8; 32]) -> Result<()> {
+        note_backup_operator(&ctx.accounts.protocol_state, &ctx.accounts.operator.key());
+        let state = &mut ctx.accounts.protocol_state;
+        require!(!state.is_paused, WGBError::ProtocolPaused);
+        require!(delta != 0, WGBError::ZeroAmount);
+        require!(!state.proof_challenged, WGBError::ProofUnderChallenge);
+
+        let old_reserves = state.proven_reserves;
+        let mut new_reserves = if delta > 0 {
+            old_reserves.checked_add(delta as u64).ok_or(WGBError::MathOverflow)?
+        } else {
+            old_reserves.saturating_sub(delta.unsigned_abs())
+        };
+        if delta < 0 && !state.allow_reserve_shrink {
+            new_reserves = new_reserves.max(state.total_supply.min(old_reserves));
         }
 
-        state.wgb_price_lamports = price_lamports;
-        msg!("Price set to {} (Operator)", price_lamports);
+        let now = Clock::get()?.unix_timestamp;
+        state.proven_reserves = new_reserves;
+        state.last_claimed_reserves = new_reserves;
+        state.last_proof_timestamp = now;
+        update_coverage(state);
+
+        emit!(ReservesAdjusted {
+            delta,
+            old_reserves,
+            new_reserves,
+            reason_hash,
+            actor: ctx.accounts.operator.key(),
+            timestamp: now,
+        });
+
+        msg!("Reserves adjusted {} -> {}", old_reserves, new_reserves);
         Ok(())
     }
 
-    /// Mint W3B (Operator) - Typed Accounts
-    pub fn mint_wgb(ctx: Context<MintWGB>, amount: u64) -> Result<()> {
+    /// Let adjust_reserves take reserves below total_supply (Admin only)
+    pub fn set_allow_reserve_shrink(ctx: Context<AdminOnly>, allowed: bool) -> Result<()> {
+        ctx.accounts.protocol_state.allow_reserve_shrink = allowed;
+        emit_config_updated("allow_reserve_shrink", ctx.accounts.authority.key())?;
+        msg!("Reserve shrink below supply {}", if allowed { "allowed" } else { "blocked" });
+        Ok(())
+    }
+
+    /// Submit Signed Proof (Permissionless relay) - Root and reserves attested by the registered oracle
+    /// The transaction must carry an ed25519 program instruction, immediately before this one,
+    /// verifying `signature` by `oracle_pubkey` over merkle_root || reserves (LE) || timestamp (LE).
+    /// Recorded as a proof landing now, with sha256 of that message as the proof hash; the
+    /// oracle's attested time is kept in OracleProofSubmitted.
+    pub fn submit_signed_proof(
+        ctx: Context<SubmitSignedProof>,
+        merkle_root: [u8; 32],
+        reserves: u64,
+        timestamp: i64,
+        oracle_pubkey: Pubkey,
+        signature: [u8; 64],
+    ) -> Result<()> {
         let state = &ctx.accounts.protocol_state;
         require!(!state.is_paused, WGBError::ProtocolPaused);
-        
-        // 1. Staleness Check
+        require!(!state.proof_challenged, WGBError::ProofUnderChallenge);
+        require!(
+            state.oracle != Pubkey::default() && oracle_pubkey == state.oracle,
+            WGBError::InvalidOracleSignature
+        );
+
+        // Attestations must move forward in time and can't be post-dated
         let now = Clock::get()?.unix_timestamp;
         require!(
-            now - state.last_proof_timestamp < 48 * 3600,
+            timestamp > state.last_proof_timestamp && timestamp <= now,
             WGBError::StaleMerkleRoot
         );
 
-        // 2. Reserve Check
-        let new_supply = state.total_supply.checked_add(amount).ok_or(WGBError::MathOverflow)?;
-        require!(new_supply <= state.proven_reserves, WGBError::InsufficientReserves);
+        let mut message = [0u8; 48];
+        message[..32].copy_from_slice(&merkle_root);
+        message[32..40].copy_from_slice(&reserves.to_le_bytes());
+        message[40..].copy_from_slice(&timestamp.to_le_bytes());
+        verify_ed25519_ix(
+            &ctx.accounts.instructions_sysvar,
+            &oracle_pubkey,
+            &signature,
+            &message,
+        )?;
 
-        // 3. CPI Mint
-        let seeds = &[b"protocol_state".as_ref(), &[state.bump]];
-        let signer = &[&seeds[..]];
+        let state = &mut ctx.accounts.protocol_state;
+        state.current_merkle_root = merkle_root;
+        extend_lineage(state, merkle_root);
+        state.proven_reserves = reserves;
+        state.last_root_update = now;
+        update_coverage(state);
+        state.last_proof_hash = hashv(&[&message]).to_bytes();
+        state.last_claimed_reserves = reserves;
+        state.last_proof_timestamp = now;
+        record_proof(&ctx.accounts.proof_history, state.last_proof_hash, now)?;
 
-        token_2022::mint_to(
-            CpiContext::new_with_signer(
-                ctx.accounts.token_program.to_account_info(),
-                MintTo {
-                    mint: ctx.accounts.wgb_mint.to_account_info(),
-                    to: ctx.accounts.treasury.to_account_info(),
-                    authority: ctx.accounts.protocol_state.to_account_info(),
-                },
-                signer,
-            ),
-            amount,
-        )?;
+        emit!(MerkleRootUpdated {
+            root: merkle_root,
+            total_serials: reserves,
+            lineage_hash: state.lineage_hash,
+            actor: ctx.accounts.submitter.key(),
+            timestamp: now,
+        });
+        emit!(OracleProofSubmitted {
+            oracle: oracle_pubkey,
+            merkle_root,
+            reserves,
+            attested_at: timestamp,
+            timestamp: now,
+        });
 
-        // 4. Update State
-        let state_mut = &mut ctx.accounts.protocol_state;
-        state_mut.total_supply = new_supply;
-        
-        emit!(TokensMinted { amount, new_total_supply: new_supply, timestamp: now });
         Ok(())
     }
 
-    // ==================== PUBLIC OPS (POINTS + REDEMPTION) ====================
+    /// Checkpoint the current root lineage for auditors (Operator) — emits CommitmentAnchored
+    /// The lineage hash chains every root ever set (see extend_lineage), so an anchored value
+    /// can later be tied to the live root with verify_root_lineage.
+    pub fn anchor_commitment(ctx: Context<OperatorOnly>) -> Result<()> {
+        note_backup_operator(&ctx.accounts.protocol_state, &ctx.accounts.operator.key());
+        let state = &ctx.accounts.protocol_state;
 
-    /// Initialize User Profile (Public)
-    pub fn init_user_profile(ctx: Context<InitUserProfile>) -> Result<()> {
-        let profile = &mut ctx.accounts.user_profile;
-        profile.user = ctx.accounts.user.key();
-        profile.points = 0;
-        profile.tier = 0; // Bronze
-        profile.total_volume = 0;
-        profile.total_redeemed = 0;
-        profile.bump = ctx.bumps.user_profile;
+        emit!(CommitmentAnchored {
+            lineage_hash: state.lineage_hash,
+            merkle_root: state.current_merkle_root,
+            proven_reserves: state.proven_reserves,
+            actor: ctx.accounts.operator.key(),
+            timestamp: Clock::get()?.unix_timestamp,
+        });
         Ok(())
     }
 
-    /// Buy W3B (Public) - Awards Points!
-    pub fn buy_wgb(ctx: Context<BuyWGB>, amount: u64) -> Result<()> {
+    /// Prove the live root descends from a past lineage commitment (Public, read-only)
+    /// `proof` is every root set since the commitment, oldest first; replaying them must
+    /// reproduce the current lineage hash and end at current_merkle_root.
+    pub fn verify_root_lineage(
+        ctx: Context<VerifyRootLineage>,
+        historical_commitment: [u8; 32],
+        proof: Vec<[u8; 32]>,
+    ) -> Result<()> {
+        require!(
+            !proof.is_empty() && proof.len() <= MAX_LINEAGE_PROOF,
+            WGBError::InvalidBatchSize
+        );
         let state = &ctx.accounts.protocol_state;
-        require!(!state.is_paused, WGBError::ProtocolPaused);
-        require!(state.wgb_price_lamports > 0, WGBError::PriceNotSet);
+        let lineage = proof
+            .iter()
+            .fold(historical_commitment, |acc, root| hashv(&[&acc, root]).to_bytes());
+        require!(
+            lineage == state.lineage_hash && proof.last() == Some(&state.current_merkle_root),
+            WGBError::LineageMismatch
+        );
+
+        emit!(LineageVerified {
+            historical_commitment,
+            lineage_hash: state.lineage_hash,
+            merkle_root: state.current_merkle_root,
+            roots_replayed: proof.len() as u32,
+            timestamp: Clock::get()?.unix_timestamp,
+        });
+        Ok(())
+    }
 
-        // Rate limiting: max 1000 W3B per transaction
-        require!(amount <= 1000, WGBError::ExceedsTransactionCap);
+    /// Create the ProofHistory ring buffer (Admin only) — proofs are recorded from here on
+    pub fn init_proof_history(ctx: Context<InitProofHistory>) -> Result<()> {
+        ctx.accounts.proof_history.bump = ctx.bumps.proof_history;
+        msg!("Proof history initialized ({} entries)", PROOF_HISTORY_LEN);
+        Ok(())
+    }
 
-        validate_optional_user_profile(&ctx.accounts.user_profile, &ctx.accounts.buyer.key())?;
+    /// Emit the recorded proof hashes, oldest first (Public, read-only)
+    pub fn emit_proof_history(ctx: Context<GetProofHistory>) -> Result<()> {
+        emit!(ProofHistoryView {
+            entries: ctx.accounts.proof_history.ordered(),
+            total_recorded: ctx.accounts.proof_history.total_recorded,
+            timestamp: Clock::get()?.unix_timestamp,
+        });
+        Ok(())
+    }
 
-        let cost = state.wgb_price_lamports.checked_mul(amount).ok_or(WGBError::MathOverflow)?;
+    /// Challenge the current reserve attestation (Public) by posting a SOL bond
+    /// Minting halts until the admin resolves it. One open challenge at a time.
+    pub fn challenge_proof(ctx: Context<ChallengeProof>, bond: u64) -> Result<()> {
+        let state = &mut ctx.accounts.protocol_state;
+        require!(!state.proof_challenged, WGBError::ProofUnderChallenge);
+        require!(
+            bond > 0 && bond >= state.min_challenge_bond,
+            WGBError::ChallengeBondTooLow
+        );
 
-        // 1. Transfer SOL
         system_program::transfer(
             CpiContext::new(
                 ctx.accounts.system_program.to_account_info(),
                 system_program::Transfer {
-                    from: ctx.accounts.buyer.to_account_info(),
-                    to: ctx.accounts.sol_receiver.to_account_info(),
+                    from: ctx.accounts.challenger.to_account_info(),
+                    to: ctx.accounts.proof_challenge.to_account_info(),
                 },
             ),
-            cost,
+            bond,
         )?;
 
-        // 2. Transfer WGB (must use transfer_checked for Token-2022 Transfer Fee Extension)
-        let seeds = &[b"protocol_state".as_ref(), &[state.bump]];
-        let signer = &[&seeds[..]];
+        state.proof_challenged = true;
+
+        let challenge = &mut ctx.accounts.proof_challenge;
+        challenge.challenger = ctx.accounts.challenger.key();
+        challenge.merkle_root = state.current_merkle_root;
+        challenge.proof_timestamp = state.last_proof_timestamp;
+        challenge.bond = bond;
+        challenge.created_at = Clock::get()?.unix_timestamp;
+        challenge.bump = ctx.bumps.proof_challenge;
+
+        emit!(ProofChallenged {
+            challenger: challenge.challenger,
+            merkle_root: challenge.merkle_root,
+            bond,
+            timestamp: challenge.created_at,
+        });
 
-        token_2022::transfer_checked(
-            CpiContext::new_with_signer(
-                ctx.accounts.token_program.to_account_info(),
-                TransferChecked {
-                    from: ctx.accounts.treasury.to_account_info(),
-                    to: ctx.accounts.buyer_token_account.to_account_info(),
-                    mint: ctx.accounts.wgb_mint.to_account_info(),
-                    authority: ctx.accounts.protocol_state.to_account_info(),
-                },
-                signer,
-            ),
-            amount,
-            0, // WGB has 0 decimals
-        )?;
+        msg!("Proof challenged by {} with {} lamports", challenge.challenger, bond);
+        Ok(())
+    }
+
+    /// Resolve the open proof challenge (Admin only)
+    /// Upheld: bond returned, the attestation is voided (the operator must resubmit) and a
+    /// penalty is recorded against the operator. Rejected: the bond goes to sol_receiver.
+    /// Either way the challenge account is closed and minting resumes.
+    pub fn resolve_challenge(ctx: Context<ResolveChallenge>, upheld: bool) -> Result<()> {
+        let state = &mut ctx.accounts.protocol_state;
+        state.proof_challenged = false;
 
-        // 3. Award Points (Check if profile exists)
-        if let Some(profile) = &mut ctx.accounts.user_profile {
-            profile.points = profile.points.saturating_add(amount); // 1 pt per W3B
-            profile.total_volume = profile.total_volume.saturating_add(amount);
-            
-            // Tier Logic? (Simple version)
-            if profile.points > 2000 { profile.tier = 3; } // Platinum
-            else if profile.points > 500 { profile.tier = 2; } // Gold
-            else if profile.points > 100 { profile.tier = 1; } // Silver
+        if upheld {
+            if state.current_merkle_root == ctx.accounts.proof_challenge.merkle_root {
+                state.last_proof_timestamp = 0; // Stale from now on — mint_wgb needs a new proof
+            }
+            state.operator_penalties = state.operator_penalties.checked_add(1).ok_or(WGBError::MathOverflow)?;
         }
 
-        emit!(TokensPurchased {
-            buyer: ctx.accounts.buyer.key(),
-            amount,
-            lamports_paid: cost,
+        let challenge = &ctx.accounts.proof_challenge;
+        let (challenger, merkle_root, bond) = (challenge.challenger, challenge.merkle_root, challenge.bond);
+        // The bond (and the account's rent) leave the PDA either way
+        consume_withdrawal(
+            &ctx.accounts.withdrawal_throttle,
+            state.is_paused,
+            challenge.to_account_info().lamports(),
+            0,
+        )?;
+        let destination = if upheld {
+            ctx.accounts.challenger.to_account_info()
+        } else {
+            ctx.accounts.sol_receiver.to_account_info()
+        };
+        ctx.accounts.proof_challenge.close(destination)?;
+
+        emit!(ChallengeResolved {
+            challenger,
+            merkle_root,
+            bond,
+            upheld,
+            operator_penalties: state.operator_penalties,
+            actor: ctx.accounts.authority.key(),
             timestamp: Clock::get()?.unix_timestamp,
         });
 
+        msg!("Proof challenge by {} {}", challenger, if upheld { "upheld" } else { "rejected" });
         Ok(())
     }
 
-    /// Burn to Redeem (Public) - Starts Redemption Flow
-    pub fn burn_wgb(ctx: Context<BurnWGB>, amount: u64, request_id: u64) -> Result<()> {
-        let state = &mut ctx.accounts.protocol_state;
-        require!(!state.is_paused, WGBError::ProtocolPaused);
-
-        validate_optional_user_profile(&ctx.accounts.user_profile, &ctx.accounts.user.key())?;
+    /// Minimum bond for challenge_proof (Admin only)
+    pub fn set_min_challenge_bond(ctx: Context<AdminOnly>, lamports: u64) -> Result<()> {
+        ctx.accounts.protocol_state.min_challenge_bond = lamports;
+        emit_config_updated("min_challenge_bond", ctx.accounts.authority.key())?;
+        msg!("Min challenge bond set to {} lamports", lamports);
+        Ok(())
+    }
 
-        // 1. Burn Tokens
-        token_2022::burn(
+    /// Set Price with Bounds (Operator or Authority, each within its own band)
+    pub fn set_wgb_price(ctx: Context<OperatorOnly>, price_lamports: u64) -> Result<()> {
+        note_backup_operator(&ctx.accounts.protocol_state, &ctx.accounts.operator.key());
+        require!(price_lamports > 0, WGBError::InvalidPrice);
+        
+        let state = &mut ctx.accounts.protocol_state;
+        let current = state.wgb_price_lamports;
+
+        // Bounds Check: the hot operator key gets a tighter band than the authority
+        let is_authority = ctx.accounts.operator.key() == state.authority;
+        let band_bps = if is_authority {
+            state.authority_price_band_bps
+        } else {
+            state.operator_price_band_bps
+        };
+        let band_bps = if band_bps == 0 { DEFAULT_PRICE_BAND_BPS } else { band_bps };
+        if current > 0 {
+            let max_change = (current as u128 * band_bps as u128 / 10_000) as u64;
+            let diff = price_lamports.abs_diff(current);
+            require!(diff <= max_change, WGBError::PriceChangeExceedsLimit);
+        }
+
+        state.wgb_price_lamports = price_lamports;
+        apply_spread(state);
+
+        emit!(PriceUpdated {
+            buy_price_lamports: state.wgb_price_lamports,
+            sell_price_lamports: sell_price(state),
+            actor: ctx.accounts.operator.key(),
+            timestamp: Clock::get()?.unix_timestamp,
+        });
+
+        msg!(
+            "Price set to {} ({})",
+            price_lamports,
+            if is_authority { "Authority" } else { "Operator" }
+        );
+        Ok(())
+    }
+
+    /// Set the price trigger_buyback pays per token (Operator or Authority)
+    /// 0 = pay the buy price. Can't exceed the buy price, and is managed automatically
+    /// while maintain_spread is on.
+    pub fn set_sell_price(ctx: Context<OperatorOnly>, price_lamports: u64) -> Result<()> {
+        note_backup_operator(&ctx.accounts.protocol_state, &ctx.accounts.operator.key());
+        let state = &mut ctx.accounts.protocol_state;
+        require!(!state.maintain_spread, WGBError::InvalidConfig);
+        require!(price_lamports <= state.wgb_price_lamports, WGBError::InvalidPrice);
+        state.wgb_sell_price_lamports = price_lamports;
+
+        emit!(PriceUpdated {
+            buy_price_lamports: state.wgb_price_lamports,
+            sell_price_lamports: sell_price(state),
+            actor: ctx.accounts.operator.key(),
+            timestamp: Clock::get()?.unix_timestamp,
+        });
+
+        msg!("Sell price set to {}", price_lamports);
+        Ok(())
+    }
+
+    /// Keep the sell price at buy_price * (10_000 - spread_bps) / 10_000 (Admin only)
+    /// While on, every buy-price update re-derives the sell price, so the two sides can't
+    /// drift apart or invert. Turning it off keeps the last derived sell price.
+    pub fn set_spread(ctx: Context<AdminOnly>, maintain_spread: bool, spread_bps: u16) -> Result<()> {
+        require!(spread_bps <= 10_000, WGBError::InvalidConfig);
+        let state = &mut ctx.accounts.protocol_state;
+        state.maintain_spread = maintain_spread;
+        state.spread_bps = spread_bps;
+        apply_spread(state);
+        emit_config_updated("spread", ctx.accounts.authority.key())?;
+        msg!("Spread {} bps (maintained: {})", spread_bps, maintain_spread);
+        Ok(())
+    }
+
+    /// Mint W3B (Operator) - Typed Accounts
+    pub fn mint_wgb(ctx: Context<MintWGB>, amount: u64) -> Result<()> {
+        process_mint(ctx.accounts, amount)
+    }
+
+    /// Mint straight into a sale (Operator + buyer) — mint_wgb then buy_wgb in one transaction
+    /// Every mint guard and every buy guard applies; the buyer co-signs to pay for the tokens,
+    /// so no one else can take the freshly minted supply in between.
+    pub fn mint_and_sell<'info>(
+        ctx: Context<'_, '_, '_, 'info, MintAndSell<'info>>,
+        amount: u64,
+        buyer: Pubkey,
+    ) -> Result<()> {
+        let accounts = &mut *ctx.accounts;
+        require_keys_eq!(buyer, accounts.buyer.key(), WGBError::Unauthorized);
+
+        let mut mint = MintWGB {
+            protocol_state: accounts.protocol_state.clone(),
+            wgb_mint: accounts.wgb_mint.clone(),
+            treasury: accounts.treasury.clone(),
+            token_program: accounts.token_program.clone(),
+            operator: accounts.operator.clone(),
+        };
+        process_mint(&mut mint, amount)?;
+        accounts.protocol_state.set_inner(mint.protocol_state.into_inner());
+        accounts.treasury.reload()?;
+
+        let mut buy = BuyWGB {
+            protocol_state: accounts.protocol_state.clone(),
+            buyer: accounts.buyer.clone(),
+            buyer_token_account: accounts.buyer_token_account.clone(),
+            treasury: accounts.treasury.clone(),
+            sol_receiver: accounts.sol_receiver.clone(),
+            wgb_mint: accounts.wgb_mint.clone(),
+            system_program: accounts.system_program.clone(),
+            token_program: accounts.token_program.clone(),
+            user_profile: accounts.user_profile.clone(),
+            tier_config: accounts.tier_config.clone(),
+            points_config: accounts.points_config.clone(),
+            kyc_attestation: accounts.kyc_attestation.clone(),
+            sol_split: accounts.sol_split.clone(),
+            insurance_fund: accounts.insurance_fund.clone(),
+            instructions_sysvar: accounts.instructions_sysvar.clone(),
+            cpi_allowlist: accounts.cpi_allowlist.clone(),
+            quote_lock: None,
+            treasury_signer: accounts.treasury_signer.clone(),
+        };
+        process_buy(&mut buy, amount, ctx.remaining_accounts)?;
+
+        // Carry the written accounts back so they are persisted
+        accounts.protocol_state.set_inner(buy.protocol_state.into_inner());
+        if let (Some(profile), Some(updated)) = (accounts.user_profile.as_mut(), buy.user_profile) {
+            profile.set_inner(updated.into_inner());
+        }
+
+        msg!("Minted and sold {} WGB to {}", amount, buyer);
+        Ok(())
+    }
+
+    // ==================== PUBLIC OPS (POINTS + REDEMPTION) ====================
+
+    /// Initialize User Profile (Public)
+    pub fn init_user_profile(ctx: Context<InitUserProfile>) -> Result<()> {
+        let profile = &mut ctx.accounts.user_profile;
+        profile.user = ctx.accounts.user.key();
+        profile.points = 0;
+        profile.tier = 0; // Bronze
+        profile.total_volume = 0;
+        profile.total_redeemed = 0;
+        profile.bump = ctx.bumps.user_profile;
+
+        emit!(UserProfileCreated {
+            user: profile.user,
+            timestamp: Clock::get()?.unix_timestamp,
+        });
+        Ok(())
+    }
+
+    /// Create the buyer's UserProfile and buy in one transaction (Public)
+    /// Same checks, pricing and events as buy_wgb, so the first purchase always earns points.
+    pub fn onboard_and_buy<'info>(
+        ctx: Context<'_, '_, '_, 'info, OnboardAndBuy<'info>>,
+        amount: u64,
+    ) -> Result<()> {
+        let accounts = &mut *ctx.accounts;
+        let profile = &mut accounts.user_profile;
+        profile.user = accounts.buyer.key();
+        profile.bump = ctx.bumps.user_profile;
+
+        emit!(UserProfileCreated {
+            user: profile.user,
+            timestamp: Clock::get()?.unix_timestamp,
+        });
+
+        let mut buy = BuyWGB {
+            protocol_state: accounts.protocol_state.clone(),
+            buyer: accounts.buyer.clone(),
+            buyer_token_account: accounts.buyer_token_account.clone(),
+            treasury: accounts.treasury.clone(),
+            sol_receiver: accounts.sol_receiver.clone(),
+            wgb_mint: accounts.wgb_mint.clone(),
+            system_program: accounts.system_program.clone(),
+            token_program: accounts.token_program.clone(),
+            user_profile: Some(accounts.user_profile.clone()),
+            tier_config: accounts.tier_config.clone(),
+            points_config: accounts.points_config.clone(),
+            kyc_attestation: accounts.kyc_attestation.clone(),
+            sol_split: accounts.sol_split.clone(),
+            insurance_fund: accounts.insurance_fund.clone(),
+            instructions_sysvar: accounts.instructions_sysvar.clone(),
+            cpi_allowlist: accounts.cpi_allowlist.clone(),
+            quote_lock: None,
+            treasury_signer: accounts.treasury_signer.clone(),
+        };
+        process_buy(&mut buy, amount, ctx.remaining_accounts)?;
+
+        // Carry the written accounts back so they are persisted
+        accounts.protocol_state.set_inner(buy.protocol_state.into_inner());
+        if let Some(updated) = buy.user_profile {
+            accounts.user_profile.set_inner(updated.into_inner());
+        }
+        Ok(())
+    }
+
+    /// Buy W3B (Public) - Awards Points!
+    pub fn buy_wgb<'info>(ctx: Context<'_, '_, '_, 'info, BuyWGB<'info>>, amount: u64) -> Result<()> {
+        process_buy(ctx.accounts, amount, ctx.remaining_accounts)
+    }
+
+    /// Lock the current price for a buy of exactly `amount` (Public)
+    /// Holds for quote_lock_secs (0 = DEFAULT_QUOTE_LOCK_SECS); pass the QuoteLock to buy_wgb to
+    /// use it. Locking again replaces any earlier quote.
+    pub fn lock_quote(ctx: Context<LockQuote>, amount: u64) -> Result<()> {
+        let state = &ctx.accounts.protocol_state;
+        require!(!state.is_paused, WGBError::ProtocolPaused);
+        require!(state.wgb_price_lamports > 0, WGBError::PriceNotSet);
+        require!(amount > 0, WGBError::ZeroAmount);
+        require!(amount >= state.min_buy_amount, WGBError::BelowMinBuy);
+        require!(amount <= MAX_TOKENS_PER_BUY, WGBError::ExceedsTransactionCap);
+
+        let lock_secs = match state.quote_lock_secs {
+            0 => DEFAULT_QUOTE_LOCK_SECS,
+            secs => secs,
+        };
+        let now = Clock::get()?.unix_timestamp;
+
+        let lock = &mut ctx.accounts.quote_lock;
+        lock.buyer = ctx.accounts.buyer.key();
+        lock.amount = amount;
+        lock.price_lamports = state.wgb_price_lamports;
+        lock.expires_at = now + lock_secs;
+        lock.bump = ctx.bumps.quote_lock;
+
+        emit!(QuoteLocked {
+            buyer: lock.buyer,
+            amount,
+            price_lamports: lock.price_lamports,
+            expires_at: lock.expires_at,
+            timestamp: now,
+        });
+        Ok(())
+    }
+
+    /// Burn to Redeem (Public) - Starts Redemption Flow
+    pub fn burn_wgb(
+        ctx: Context<BurnWGB>,
+        amount: u64,
+        request_id: u64,
+        region_code: u16,
+        priority_fee_lamports: u64,
+    ) -> Result<()> {
+        let request_bump = ctx.bumps.redemption_request;
+        process_burn(ctx.accounts, request_bump, amount, request_id, region_code, priority_fee_lamports)
+    }
+
+    /// Burn to redeem one specific bar (Public)
+    /// `serial_leaf` is sha256(serial); the raw `serial` is proven against current_merkle_root
+    /// by verify_serial_proof. `amount` must be exactly one bar (redemption_bar_unit, or 1 token
+    /// while unset). The RedeemedSerial marker is created here and bound to the new order, so
+    /// no other order can claim the bar.
+    pub fn burn_with_serial(
+        ctx: Context<BurnWithSerial>,
+        amount: u64,
+        request_id: u64,
+        serial_leaf: [u8; 32],
+        serial: String,
+        proof: Vec<[u8; 32]>,
+    ) -> Result<()> {
+        let accounts = &mut *ctx.accounts;
+        require!(
+            amount == accounts.protocol_state.redemption_bar_unit.max(1),
+            WGBError::NonWholeRedemption
+        );
+        let root = accounts.protocol_state.current_merkle_root;
+        verify_serial_proof(&serial, serial_leaf, &proof, root)?;
+        require!(
+            accounts.redeemed_serial.user == Pubkey::default(),
+            WGBError::SerialAlreadyAssigned
+        );
+
+        let mut burn = BurnWGB {
+            protocol_state: accounts.protocol_state.clone(),
+            user: accounts.user.clone(),
+            user_token_account: accounts.user_token_account.clone(),
+            wgb_mint: accounts.wgb_mint.clone(),
+            redemption_request: accounts.redemption_request.clone(),
+            system_program: accounts.system_program.clone(),
+            token_program: accounts.token_program.clone(),
+            user_profile: accounts.user_profile.clone(),
+            points_config: accounts.points_config.clone(),
+            redemption_block: accounts.redemption_block.clone(),
+            redemption_stats: accounts.redemption_stats.clone(),
+        };
+        process_burn(&mut burn, ctx.bumps.redemption_request, amount, request_id, 0, 0)?;
+
+        // Carry the written accounts back so they are persisted
+        accounts.protocol_state.set_inner(burn.protocol_state.into_inner());
+        if let (Some(profile), Some(updated)) = (&mut accounts.user_profile, burn.user_profile) {
+            profile.set_inner(updated.into_inner());
+        }
+        let mut req = burn.redemption_request.into_inner();
+        req.serials_recorded = 1;
+        let (amount, created_at) = (req.amount, req.created_at);
+        accounts.redemption_request.set_inner(req);
+
+        let marker = &mut accounts.redeemed_serial;
+        marker.serial_leaf = serial_leaf;
+        marker.user = accounts.user.key();
+        marker.request_id = request_id;
+        marker.redeemed_at = created_at;
+        marker.bump = ctx.bumps.redeemed_serial;
+
+        emit!(SerialRedemptionRequested {
+            serial_leaf,
+            user: marker.user,
+            request_id,
+            amount,
+            timestamp: created_at,
+        });
+
+        msg!("Redemption #{} bound to a proven serial", request_id);
+        Ok(())
+    }
+
+    /// Free the serial a cancelled burn_with_serial order was bound to (Admin or Operator)
+    /// Closes its RedeemedSerial marker, refunding the rent to the redeemer.
+    pub fn release_serial(ctx: Context<ReleaseSerial>) -> Result<()> {
+        note_backup_operator(&ctx.accounts.protocol_state, &ctx.accounts.signer.key());
+        let marker = &ctx.accounts.redeemed_serial;
+
+        emit!(SerialReleased {
+            serial_leaf: marker.serial_leaf,
+            user: marker.user,
+            request_id: marker.request_id,
+            actor: ctx.accounts.signer.key(),
+            timestamp: Clock::get()?.unix_timestamp,
+        });
+        Ok(())
+    }
+
+    /// Add to the priority fee escrowed in your Pending or Claimed redemption (Redeemer)
+    /// The whole fee goes to the fulfiller on confirmation and back to the redeemer if the
+    /// order is cancelled or expires.
+    pub fn raise_priority_fee(ctx: Context<RaisePriorityFee>, additional: u64) -> Result<()> {
+        require!(!ctx.accounts.protocol_state.is_paused, WGBError::ProtocolPaused);
+        require!(additional > 0, WGBError::ZeroAmount);
+        escrow_priority_fee(
+            &ctx.accounts.system_program,
+            &ctx.accounts.user,
+            &ctx.accounts.redemption_request,
+            additional,
+        )
+    }
+
+    /// Buy back and burn W3B while under-collateralized (Operator + seller)
+    /// The seller co-signs and is paid the sell price * amount (rounded down) from the buyback_vault
+    /// PDA, which is funded by plain SOL transfers. Spending is capped by
+    /// buyback_budget_lamports and by the shortfall, so supply never drops below reserves.
+    pub fn trigger_buyback(ctx: Context<TriggerBuyback>, amount: u64) -> Result<()> {
+        note_backup_operator(&ctx.accounts.protocol_state, &ctx.accounts.operator.key());
+        let state = &ctx.accounts.protocol_state;
+        require!(!state.is_paused, WGBError::ProtocolPaused);
+        require!(state.wgb_price_lamports > 0, WGBError::PriceNotSet);
+
+        require!(state.coverage_bps < 10_000, WGBError::NotUnderCollateralized);
+        let shortfall = state.total_supply.saturating_sub(backing_reserves(state));
+        require!(amount > 0 && amount <= shortfall, WGBError::InvalidConfig);
+
+        let cost = compute_cost(
+            sell_price(state),
+            amount,
+            ctx.accounts.wgb_mint.decimals,
+            0,
+            false,
+        )?;
+        require!(cost <= state.buyback_budget_lamports, WGBError::BuybackBudgetExceeded);
+
+        // The vault is a system account; keep it rent-exempt
+        let vault = &ctx.accounts.buyback_vault;
+        let min_balance = Rent::get()?.minimum_balance(0);
+        require!(
+            vault.lamports().saturating_sub(cost) >= min_balance,
+            WGBError::BuybackBudgetExceeded
+        );
+
+        // 1. Burn the seller's tokens
+        token_2022::burn(
             CpiContext::new(
                 ctx.accounts.token_program.to_account_info(),
                 Burn {
                     mint: ctx.accounts.wgb_mint.to_account_info(),
-                    from: ctx.accounts.user_token_account.to_account_info(),
-                    authority: ctx.accounts.user.to_account_info(),
+                    from: ctx.accounts.seller_token_account.to_account_info(),
+                    authority: ctx.accounts.seller.to_account_info(),
                 },
             ),
             amount,
         )?;
 
-        // 2. Update Protocol Stats
+        // 2. Pay the seller from the vault
+        consume_withdrawal(&ctx.accounts.withdrawal_throttle, ctx.accounts.protocol_state.is_paused, cost, 0)?;
+        let vault_bump = ctx.bumps.buyback_vault;
+        let seeds = &[b"buyback_vault".as_ref(), &[vault_bump]];
+        let signer = &[&seeds[..]];
+        system_program::transfer(
+            CpiContext::new_with_signer(
+                ctx.accounts.system_program.to_account_info(),
+                system_program::Transfer {
+                    from: ctx.accounts.buyback_vault.to_account_info(),
+                    to: ctx.accounts.seller.to_account_info(),
+                },
+                signer,
+            ),
+            cost,
+        )?;
+
+        // 3. Update State
+        let state = &mut ctx.accounts.protocol_state;
         state.total_supply = state.total_supply.checked_sub(amount).ok_or(WGBError::MathOverflow)?;
         state.total_burned = state.total_burned.checked_add(amount).ok_or(WGBError::MathOverflow)?;
+        update_coverage(state);
+        state.buyback_budget_lamports -= cost;
 
-        // 3. Create Redemption Request
-        let req = &mut ctx.accounts.redemption_request;
-        req.user = ctx.accounts.user.key();
-        req.request_id = request_id;
-        req.amount = amount;
-        req.status = 0; // Pending
-        req.created_at = Clock::get()?.unix_timestamp;
-        req.bump = ctx.bumps.redemption_request;
-
-        // 4. Points & Profile
-        if let Some(profile) = &mut ctx.accounts.user_profile {
-            // Double points for redemption!
-            let points = amount.checked_mul(2).unwrap_or(amount);
-            profile.points = profile.points.saturating_add(points);
-            profile.total_redeemed = profile.total_redeemed.saturating_add(amount);
-        }
-
-        emit!(TokensBurned {
-            user: ctx.accounts.user.key(),
+        emit!(BuybackExecuted {
+            seller: ctx.accounts.seller.key(),
             amount,
-            request_id,
-            timestamp: req.created_at,
+            lamports_paid: cost,
+            remaining_budget: state.buyback_budget_lamports,
+            new_total_supply: state.total_supply,
+            actor: ctx.accounts.operator.key(),
+            timestamp: Clock::get()?.unix_timestamp,
         });
-
-        msg!("Redemption Request #{} created for {} WGB", request_id, amount);
         Ok(())
     }
 
     /// Award Points Manually (Operator) - For off-chain purchases (e.g. Shop)
-    pub fn award_points(ctx: Context<AwardPoints>, amount: u64) -> Result<()> {
+    /// `nonce` must increase strictly per profile, so a retried transaction cannot double-credit.
+    pub fn award_points(ctx: Context<AwardPoints>, amount: u64, nonce: u64) -> Result<()> {
+        note_backup_operator(&ctx.accounts.protocol_state, &ctx.accounts.operator.key());
+        require!(amount > 0, WGBError::ZeroAmount);
+        // Operators can't credit themselves
+        require_keys_neq!(ctx.accounts.user.key(), ctx.accounts.operator.key(), WGBError::SelfTarget);
         let profile = &mut ctx.accounts.user_profile;
-        profile.points = profile.points.saturating_add(amount);
-        msg!("Awarded {} points to {}", amount, profile.user);
+        require!(nonce > profile.last_award_nonce, WGBError::NonceReused);
+
+        profile.last_award_nonce = nonce;
+        let granted = credit_points(profile, amount, ctx.accounts.protocol_state.max_user_points)?;
+        msg!("Awarded {} points to {} (nonce {})", granted, profile.user, nonce);
         Ok(())
     }
 
-    // ==================== P2P FULFILLMENT ====================
+    /// Gift points to another user (Sender) — only while points_transfers_enabled
+    /// The recipient is subject to max_user_points; the sender is only debited what lands.
+    pub fn transfer_points(ctx: Context<TransferPoints>, amount: u64) -> Result<()> {
+        require!(amount > 0, WGBError::ZeroAmount);
+        let state = &ctx.accounts.protocol_state;
+        require!(state.points_transfers_enabled, WGBError::PointsTransfersDisabled);
 
-    /// Claim a pending redemption order (Public — race-to-accept)
-    pub fn claim_redemption(ctx: Context<ClaimRedemption>) -> Result<()> {
-        let req = &mut ctx.accounts.redemption_request;
+        let sender = ctx.accounts.sender.key();
+        let recipient = ctx.accounts.recipient.key();
+        require_keys_neq!(sender, recipient, WGBError::SelfTransfer);
+        require!(amount <= ctx.accounts.sender_profile.points, WGBError::InsufficientPoints);
 
-        // Only pending orders can be claimed
-        require!(req.status == 0, WGBError::InvalidRedemptionStatus);
+        let granted = credit_points(&mut ctx.accounts.recipient_profile, amount, state.max_user_points)?;
+        let sender_profile = &mut ctx.accounts.sender_profile;
+        sender_profile.points -= granted;
 
-        req.status = 1; // Claimed
-        req.fulfiller = ctx.accounts.fulfiller.key();
-        req.claimed_at = Clock::get()?.unix_timestamp;
+        emit!(PointsTransferred {
+            from: sender,
+            to: recipient,
+            amount: granted,
+            timestamp: Clock::get()?.unix_timestamp,
+        });
 
-        emit!(RedemptionClaimed {
-            request_id: req.request_id,
-            fulfiller: ctx.accounts.fulfiller.key(),
-            timestamp: req.claimed_at,
+        msg!("{} points transferred from {} to {}", granted, sender, recipient);
+        Ok(())
+    }
+
+    /// Move the signer's profile to a new wallet and close the old one (Profile owner + new wallet)
+    /// Points, tier, volume and history carry over; the old profile's rent goes back to the signer.
+    pub fn migrate_profile(ctx: Context<MigrateProfile>) -> Result<()> {
+        let old_user = ctx.accounts.user.key();
+        let new_user = ctx.accounts.new_user.key();
+        require_keys_neq!(old_user, new_user, WGBError::SelfTransfer);
+        let old = &ctx.accounts.old_profile;
+        require!(old.active_claims == 0, WGBError::ProfileHasActiveClaims);
+
+        let new = &mut ctx.accounts.new_profile;
+        require!(!new.is_populated(), WGBError::TargetProfileExists);
+        new.user = new_user;
+        new.total_volume = old.total_volume;
+        new.points = old.points;
+        new.tier = old.tier;
+        new.total_redeemed = old.total_redeemed;
+        new.total_fulfilled = old.total_fulfilled;
+        new.fulfiller_rewards = old.fulfiller_rewards;
+        new.bump = ctx.bumps.new_profile;
+        new.last_award_nonce = old.last_award_nonce;
+        new.failed_fulfillments = old.failed_fulfillments;
+        new.compliance_locked = old.compliance_locked;
+        new.points_dust = old.points_dust;
+        new.streak_days = old.streak_days;
+        new.last_streak_day = old.last_streak_day;
+        new.last_buy_at = old.last_buy_at;
+        new.welcome_tier_floor = old.welcome_tier_floor;
+
+        emit!(ProfileMigrated {
+            old_user,
+            new_user,
+            points: new.points,
+            tier: new.tier,
+            timestamp: Clock::get()?.unix_timestamp,
         });
 
-        msg!(
-            "Redemption #{} claimed by {}",
-            req.request_id,
-            ctx.accounts.fulfiller.key()
-        );
+        msg!("Profile migrated from {} to {}", old_user, new_user);
         Ok(())
     }
 
-    /// Confirm delivery of a claimed redemption (Admin/Operator)
-    pub fn confirm_delivery(ctx: Context<ConfirmDelivery>) -> Result<()> {
-        let req = &mut ctx.accounts.redemption_request;
+    /// Claim airdropped points (Public) — leaf = sha256(user || amount_le), one claim per root
+    pub fn claim_airdrop(ctx: Context<ClaimAirdrop>, amount: u64, proof: Vec<[u8; 32]>) -> Result<()> {
+        let root = ctx.accounts.protocol_state.airdrop_root;
+        require!(root != [0u8; 32], WGBError::AirdropNotActive);
 
-        // Only claimed orders can be confirmed
-        require!(req.status == 1, WGBError::InvalidRedemptionStatus);
+        let user = ctx.accounts.user.key();
+        let leaf = hashv(&[user.as_ref(), &amount.to_le_bytes()]).to_bytes();
+        require!(verify_merkle_proof(leaf, &proof, root), WGBError::InvalidMerkleProof);
 
-        req.status = 3; // Confirmed
-        req.confirmed_at = Clock::get()?.unix_timestamp;
+        let profile = &mut ctx.accounts.user_profile;
+        credit_points(profile, amount, ctx.accounts.protocol_state.max_user_points)?;
+
+        let claim = &mut ctx.accounts.airdrop_claim;
+        claim.user = user;
+        claim.root = root;
+        claim.amount = amount;
+        claim.claimed_at = Clock::get()?.unix_timestamp;
+        claim.bump = ctx.bumps.airdrop_claim;
+
+        emit!(AirdropClaimed {
+            user,
+            root,
+            amount,
+            timestamp: claim.claimed_at,
+        });
 
-        // Reward the fulfiller — 5 points per order fulfilled + update stats
-        if let Some(fulfiller_profile) = &mut ctx.accounts.fulfiller_profile {
-            fulfiller_profile.points = fulfiller_profile.points.saturating_add(5);
-            fulfiller_profile.total_fulfilled = fulfiller_profile.total_fulfilled.saturating_add(1);
-        }
+        msg!("Airdrop of {} points claimed by {}", amount, user);
+        Ok(())
+    }
 
-        emit!(RedemptionConfirmed {
-            request_id: req.request_id,
-            fulfiller: req.fulfiller,
-            timestamp: req.confirmed_at,
+    /// Record who referred the signer (Public, once per user)
+    /// The referrer earns PointsConfig.referral_bonus only at or above min_referrer_tier;
+    /// below it the relationship is still recorded but nothing is paid. The signer must
+    /// already have bought (total_volume > 0), so fresh wallets can't mint referral points.
+    pub fn register_referral(ctx: Context<RegisterReferral>, referrer: Pubkey) -> Result<()> {
+        let user = ctx.accounts.user.key();
+        require_keys_neq!(referrer, user, WGBError::SelfReferral);
+        require!(ctx.accounts.user_profile.total_volume > 0, WGBError::ReferralNotQualified);
+
+        let now = Clock::get()?.unix_timestamp;
+        let referrer_profile = &mut ctx.accounts.referrer_profile;
+        let min_tier = ctx.accounts.protocol_state.min_referrer_tier;
+        let max_user_points = ctx.accounts.protocol_state.max_user_points;
+
+        let bonus = if referrer_profile.tier >= min_tier {
+            let points_config = load_points_config(&ctx.accounts.points_config)?;
+            credit_points(referrer_profile, points_config.referral_bonus, max_user_points)?
+        } else {
+            emit!(ReferralIneligible {
+                referrer,
+                referee: user,
+                referrer_tier: referrer_profile.tier,
+                min_referrer_tier: min_tier,
+                timestamp: now,
+            });
+            0
+        };
+
+        let referral = &mut ctx.accounts.referral;
+        referral.referee = user;
+        referral.referrer = referrer;
+        referral.bonus_paid = bonus;
+        referral.created_at = now;
+        referral.bump = ctx.bumps.referral;
+
+        emit!(ReferralRegistered {
+            referrer,
+            referee: user,
+            bonus_paid: bonus,
+            timestamp: now,
         });
 
-        msg!("Redemption #{} confirmed — delivery complete", req.request_id);
+        msg!("{} referred by {} ({} points)", user, referrer, bonus);
         Ok(())
     }
 
-    /// Cancel a redemption order (Admin only)
-    pub fn cancel_redemption(ctx: Context<CancelRedemption>) -> Result<()> {
-        let req = &mut ctx.accounts.redemption_request;
+    /// Smallest amount buy_wgb accepts (Admin only) — 0 only rejects zero-amount buys
+    pub fn set_min_buy_amount(ctx: Context<AdminOnly>, min_amount: u64) -> Result<()> {
+        require!(min_amount <= MAX_TOKENS_PER_BUY, WGBError::InvalidConfig); // Above the per-tx cap nothing could be bought
+        ctx.accounts.protocol_state.min_buy_amount = min_amount;
+        emit_config_updated("min_buy_amount", ctx.accounts.authority.key())?;
+        msg!("Min buy amount set to {}", min_amount);
+        Ok(())
+    }
+
+    /// Minimum gap between one buyer's purchases (Admin only) — 0 disables
+    /// While set, buys must include the buyer's UserProfile so last_buy_at can be checked.
+    pub fn set_buy_cooldown_secs(ctx: Context<AdminOnly>, secs: i64) -> Result<()> {
+        require!(secs >= 0, WGBError::InvalidConfig);
+        ctx.accounts.protocol_state.buy_cooldown_secs = secs;
+        emit_config_updated("buy_cooldown_secs", ctx.accounts.authority.key())?;
+        msg!("Buy cooldown set to {}s", secs);
+        Ok(())
+    }
+
+    /// Require a KycAttestation of at least `min_level` to buy (Admin only)
+    pub fn set_require_kyc(ctx: Context<AdminOnly>, required: bool, min_level: u8) -> Result<()> {
+        require!(!required || min_level > 0, WGBError::InvalidConfig); // Level 0 = no attestation
+        let state = &mut ctx.accounts.protocol_state;
+        state.require_kyc = required;
+        state.min_kyc_level = min_level;
+        emit_config_updated("require_kyc", ctx.accounts.authority.key())?;
+        msg!("Require KYC: {} (min level {})", required, min_level);
+        Ok(())
+    }
+
+    /// Fractional buy-points rate in bps of a point per token (Admin only)
+    /// 0 falls back to PointsConfig.buy_points_per_token.
+    pub fn set_buy_points_bps(ctx: Context<AdminOnly>, bps: u64) -> Result<()> {
+        ctx.accounts.protocol_state.buy_points_bps = bps;
+        emit_config_updated("buy_points_bps", ctx.accounts.authority.key())?;
+        msg!("Buy points rate set to {} bps", bps);
+        Ok(())
+    }
+
+    /// Measure tiers by points (0) or lifetime purchase volume (1) (Admin only)
+    /// Volume-based tiers don't drop when points are spent or gifted.
+    pub fn set_tier_basis(ctx: Context<AdminOnly>, basis: u8) -> Result<()> {
+        require!(basis <= TIER_BASIS_VOLUME, WGBError::InvalidConfig);
+        ctx.accounts.protocol_state.tier_basis = basis;
+        emit_config_updated("tier_basis", ctx.accounts.authority.key())?;
+        msg!("Tier basis set to {}", basis);
+        Ok(())
+    }
+
+    /// Turn user-to-user point gifting on or off (Admin only) — off by default
+    pub fn set_points_transfers_enabled(ctx: Context<AdminOnly>, enabled: bool) -> Result<()> {
+        ctx.accounts.protocol_state.points_transfers_enabled = enabled;
+        emit_config_updated("points_transfers_enabled", ctx.accounts.authority.key())?;
+        msg!("Points transfers enabled: {}", enabled);
+        Ok(())
+    }
 
-        // Can only cancel Pending (0) or Claimed (1) orders
+    /// Cap on the points any one profile can hold (Admin only) — 0 or u64::MAX = unlimited
+    pub fn set_max_user_points(ctx: Context<AdminOnly>, max_points: u64) -> Result<()> {
+        ctx.accounts.protocol_state.max_user_points = max_points;
+        emit_config_updated("max_user_points", ctx.accounts.authority.key())?;
+        msg!("Max user points set to {}", max_points);
+        Ok(())
+    }
+
+    /// Per-role set_wgb_price bands in bps (Admin only) — 0 keeps the 20% default
+    /// The operator's band can't be wider than the authority's.
+    pub fn set_price_bands(
+        ctx: Context<AdminOnly>,
+        operator_band_bps: u16,
+        authority_band_bps: u16,
+    ) -> Result<()> {
+        let effective = |bps: u16| if bps == 0 { DEFAULT_PRICE_BAND_BPS } else { bps };
         require!(
-            req.status == 0 || req.status == 1,
-            WGBError::InvalidRedemptionStatus
+            operator_band_bps <= 10_000
+                && authority_band_bps <= 10_000
+                && effective(operator_band_bps) <= effective(authority_band_bps),
+            WGBError::InvalidConfig
         );
 
-        req.status = 4; // Cancelled
+        let state = &mut ctx.accounts.protocol_state;
+        state.operator_price_band_bps = operator_band_bps;
+        state.authority_price_band_bps = authority_band_bps;
+        emit_config_updated("price_bands", ctx.accounts.authority.key())?;
+        msg!(
+            "Price bands set: operator {} bps, authority {} bps",
+            operator_band_bps,
+            authority_band_bps
+        );
+        Ok(())
+    }
 
-        emit!(RedemptionCancelled {
-            request_id: req.request_id,
-            timestamp: Clock::get()?.unix_timestamp,
-        });
+    /// Per-proof SOL rebate to the operator (Admin only), capped at MAX_OPERATOR_REBATE_LAMPORTS
+    pub fn set_operator_rebate(ctx: Context<AdminOnly>, rebate_lamports: u64) -> Result<()> {
+        require!(rebate_lamports <= MAX_OPERATOR_REBATE_LAMPORTS, WGBError::InvalidConfig);
+        ctx.accounts.protocol_state.operator_rebate_lamports = rebate_lamports;
+        emit_config_updated("operator_rebate", ctx.accounts.authority.key())?;
+        msg!("Operator rebate set to {} lamports", rebate_lamports);
+        Ok(())
+    }
 
-        msg!("Redemption #{} cancelled", req.request_id);
+    /// Redemption priority per tier, index = tier id (Admin only) — all zeros keeps FIFO ordering
+    pub fn set_tier_priority(ctx: Context<AdminOnly>, priorities: [u8; MAX_TIERS]) -> Result<()> {
+        ctx.accounts.protocol_state.tier_priority = priorities;
+        emit_config_updated("tier_priority", ctx.accounts.authority.key())?;
+        msg!("Tier priority set to {:?}", priorities);
         Ok(())
     }
 
-    // ==================== ADMIN OPS ====================
+    /// Fulfiller reward multiplier per tier in bps, by the fulfiller's profile tier (Admin only)
+    /// 0 = 1x, so the all-zero default pays the flat PointsConfig.fulfiller_reward.
+    pub fn set_tier_fulfiller_multipliers(
+        ctx: Context<AdminOnly>,
+        multipliers_bps: [u16; MAX_TIERS],
+    ) -> Result<()> {
+        ctx.accounts.protocol_state.tier_fulfiller_multiplier_bps = multipliers_bps;
+        emit_config_updated("tier_fulfiller_multipliers", ctx.accounts.authority.key())?;
+        msg!("Tier fulfiller multipliers set to {:?}", multipliers_bps);
+        Ok(())
+    }
 
-    /// Close ProtocolState PDA (Admin only) — enables clean-slate reinit
-    pub fn close_protocol_state(_ctx: Context<CloseProtocolState>) -> Result<()> {
-        msg!("Protocol state closed — ready for fresh initialization");
+    /// Orders of at least `threshold` tokens need `min_fulfillers` distinct contributors before
+    /// they can be confirmed (Admin only) — threshold 0 turns it off
+    pub fn set_multi_fulfiller(ctx: Context<AdminOnly>, threshold: u64, min_fulfillers: u8) -> Result<()> {
+        require!(
+            threshold == 0 || (2..=MAX_ORDER_FULFILLERS as u8).contains(&min_fulfillers),
+            WGBError::InvalidConfig
+        );
+        let state = &mut ctx.accounts.protocol_state;
+        state.multi_fulfiller_threshold = threshold;
+        state.min_distinct_fulfillers = min_fulfillers;
+        emit_config_updated("multi_fulfiller", ctx.accounts.authority.key())?;
+        msg!("Orders of {}+ need {} distinct fulfillers", threshold, min_fulfillers);
         Ok(())
     }
 
-    pub fn set_paused(ctx: Context<AdminOnly>, paused: bool) -> Result<()> {
-        ctx.accounts.protocol_state.is_paused = paused;
+    /// Cap on orders a single fulfiller may hold claimed at once (Admin only) — 0 = unlimited
+    pub fn set_max_active_claims(ctx: Context<AdminOnly>, max_claims: u32) -> Result<()> {
+        ctx.accounts.protocol_state.max_active_claims_per_fulfiller = max_claims;
+        emit_config_updated("max_active_claims", ctx.accounts.authority.key())?;
+        msg!("Max active claims per fulfiller set to {}", max_claims);
         Ok(())
     }
 
-    pub fn set_sol_receiver(ctx: Context<AdminOnly>, receiver: Pubkey) -> Result<()> {
-        ctx.accounts.protocol_state.sol_receiver = receiver;
+    /// Lamports trigger_buyback may still spend (Admin only) — 0 disables buybacks
+    pub fn set_buyback_budget(ctx: Context<AdminOnly>, budget_lamports: u64) -> Result<()> {
+        ctx.accounts.protocol_state.buyback_budget_lamports = budget_lamports;
+        emit_config_updated("buyback_budget", ctx.accounts.authority.key())?;
+        msg!("Buyback budget set to {} lamports", budget_lamports);
         Ok(())
     }
 
-    pub fn set_treasury(ctx: Context<AdminOnly>, treasury: Pubkey) -> Result<()> {
-        ctx.accounts.protocol_state.treasury = treasury;
+    /// Trade per-transaction events for log cost (Admin only)
+    /// EVENTS_FULL (0): everything.
+    /// EVENTS_REDUCED (1): drops TokensPurchased and WelcomeTierGranted from buy_wgb.
+    /// EVENTS_MINIMAL (2): also drops TokensBurned, RedemptionClaimed and BatchClaimed, leaving
+    /// admin, accounting and view events (emit_accounting etc.) as the aggregate record.
+    pub fn set_event_verbosity(ctx: Context<AdminOnly>, level: u8) -> Result<()> {
+        require!(level <= EVENTS_MINIMAL, WGBError::InvalidConfig);
+        ctx.accounts.protocol_state.event_verbosity = level;
+        emit_config_updated("event_verbosity", ctx.accounts.authority.key())?;
+        msg!("Event verbosity set to {}", level);
         Ok(())
     }
-    
-    pub fn set_wgb_price_admin(ctx: Context<AdminOnly>, price: u64) -> Result<()> {
-        ctx.accounts.protocol_state.wgb_price_lamports = price; // Unbounded override
+
+    /// Tier granted on a first purchase that would otherwise land on Bronze (Admin only)
+    pub fn set_welcome_tier(ctx: Context<AdminOnly>, tier: u8) -> Result<()> {
+        require!((tier as usize) < MAX_TIERS, WGBError::InvalidConfig);
+        ctx.accounts.protocol_state.welcome_tier = tier;
+        emit_config_updated("welcome_tier", ctx.accounts.authority.key())?;
+        msg!("Welcome tier set to {}", tier);
         Ok(())
     }
 
-    // ==================== YIELD OPS ====================
+    /// Minimum tier a referrer needs to earn referral points (Admin only) — 0 (Bronze) pays everyone
+    pub fn set_min_referrer_tier(ctx: Context<AdminOnly>, min_tier: u8) -> Result<()> {
+        require!((min_tier as usize) < MAX_TIERS, WGBError::InvalidConfig);
+        ctx.accounts.protocol_state.min_referrer_tier = min_tier;
+        emit_config_updated("min_referrer_tier", ctx.accounts.authority.key())?;
+        msg!("Min referrer tier set to {}", min_tier);
+        Ok(())
+    }
 
-    /// Set yield APY rate in basis points (Admin only)
-    pub fn set_yield_rate(ctx: Context<AdminOnly>, apy_bps: u16) -> Result<()> {
-        ctx.accounts.protocol_state.yield_apy_bps = apy_bps;
+    /// Place or lift a compliance hold on a user's buys and burns (Admin only)
+    /// Tokens stay freely transferable — only protocol operations are blocked.
+    pub fn set_compliance_lock(ctx: Context<SetComplianceLock>, user: Pubkey, locked: bool) -> Result<()> {
+        ctx.accounts.user_profile.compliance_locked = locked;
 
-        emit!(YieldRateUpdated {
-            apy_bps,
+        emit!(ComplianceLockChanged {
+            user,
+            locked,
+            actor: ctx.accounts.authority.key(),
             timestamp: Clock::get()?.unix_timestamp,
         });
 
-        msg!("Yield rate set to {} bps", apy_bps);
+        msg!("Compliance lock for {} set to {}", user, locked);
         Ok(())
     }
 
-    /// Record that yield was distributed off-chain (Operator)
-    pub fn record_yield_distribution(ctx: Context<OperatorOnly>, amount: u64) -> Result<()> {
-        let state = &mut ctx.accounts.protocol_state;
+    /// Block a user's redemptions only (Admin only) — buys and transfers are unaffected
+    pub fn block_redemptions(ctx: Context<BlockRedemptions>, user: Pubkey) -> Result<()> {
+        let block = &mut ctx.accounts.redemption_block;
+        block.user = user;
+        block.blocked_at = Clock::get()?.unix_timestamp;
+        block.bump = ctx.bumps.redemption_block;
+
+        emit!(RedemptionBlockChanged {
+            user,
+            blocked: true,
+            actor: ctx.accounts.authority.key(),
+            timestamp: block.blocked_at,
+        });
 
-        state.total_yield_distributed = state
-            .total_yield_distributed
-            .checked_add(amount)
-            .ok_or(WGBError::MathOverflow)?;
-        state.last_yield_distribution = Clock::get()?.unix_timestamp;
+        msg!("Redemptions blocked for {}", user);
+        Ok(())
+    }
 
-        emit!(YieldDistributed {
-            amount,
-            new_total: state.total_yield_distributed,
-            timestamp: state.last_yield_distribution,
+    /// Lift a redemption block (Admin only) — closing the PDA refunds its rent to the authority
+    pub fn unblock_redemptions(ctx: Context<UnblockRedemptions>, user: Pubkey) -> Result<()> {
+        emit!(RedemptionBlockChanged {
+            user,
+            blocked: false,
+            actor: ctx.accounts.authority.key(),
+            timestamp: Clock::get()?.unix_timestamp,
         });
 
-        msg!("Yield distribution recorded: {} WGB", amount);
+        msg!("Redemptions unblocked for {}", user);
         Ok(())
     }
 
-    /// Update the Transfer Fee Extension config on the WGB mint (Admin only)
-    pub fn update_transfer_fee(
-        ctx: Context<UpdateTransferFee>,
-        new_fee_bps: u16,
-        new_max_fee: u64,
+    /// Record a user's KYC level (Operator or Admin) — no personal data is stored
+    /// `expires_at` 0 = never lapses; level 0 revokes the attestation.
+    pub fn attest_kyc(ctx: Context<AttestKyc>, user: Pubkey, level: u8, expires_at: i64) -> Result<()> {
+        let now = Clock::get()?.unix_timestamp;
+        require!(expires_at == 0 || expires_at > now, WGBError::InvalidConfig);
+        require_keys_neq!(user, ctx.accounts.attester.key(), WGBError::SelfTarget);
+        note_backup_operator(&ctx.accounts.protocol_state, &ctx.accounts.attester.key());
+
+        let attestation = &mut ctx.accounts.kyc_attestation;
+        attestation.user = user;
+        attestation.level = level;
+        attestation.expires_at = expires_at;
+        attestation.attested_at = now;
+        attestation.bump = ctx.bumps.kyc_attestation;
+
+        emit!(KycAttested {
+            user,
+            level,
+            expires_at,
+            attested_by: ctx.accounts.attester.key(),
+            timestamp: now,
+        });
+
+        msg!("KYC level {} attested for {}", level, user);
+        Ok(())
+    }
+
+    // ==================== P2P FULFILLMENT ====================
+
+    /// Claim a pending redemption order (Public — race-to-accept)
+    pub fn claim_redemption(ctx: Context<ClaimRedemption>) -> Result<()> {
+        let req = &mut ctx.accounts.redemption_request;
+
+        // Only pending orders can be claimed
+        require!(req.status == 0, WGBError::InvalidRedemptionStatus);
+        take_claim_slot(&ctx.accounts.protocol_state, &ctx.accounts.fulfiller_profile)?;
+
+        req.status = 1; // Claimed
+        req.fulfiller = ctx.accounts.fulfiller.key();
+        req.claimed_at = Clock::get()?.unix_timestamp;
+        bump_redemption_stat(&ctx.accounts.redemption_stats, |s| &mut s.claimed, 1)?;
+
+        if event_enabled(&ctx.accounts.protocol_state, EVENTS_MINIMAL) {
+            emit!(RedemptionClaimed {
+                request_id: req.request_id,
+                fulfiller: ctx.accounts.fulfiller.key(),
+                timestamp: req.claimed_at,
+            });
+        }
+
+        msg!(
+            "Redemption #{} claimed by {}",
+            req.request_id,
+            ctx.accounts.fulfiller.key()
+        );
+        Ok(())
+    }
+
+    /// Claim several pending redemptions at once (Public) — PDAs passed as writable remaining_accounts
+    /// Orders that are no longer Pending are skipped rather than failing the whole batch.
+    pub fn claim_redemption_batch(ctx: Context<ClaimRedemptionBatch>) -> Result<()> {
+        let requests = ctx.remaining_accounts;
+        require!(requests.len() <= MAX_BATCH, WGBError::BatchTooLarge);
+        require!(!requests.is_empty(), WGBError::InvalidBatchSize);
+
+        let fulfiller = ctx.accounts.fulfiller.key();
+        let now = Clock::get()?.unix_timestamp;
+        let mut claimed = Vec::with_capacity(requests.len());
+
+        for info in requests {
+            require!(
+                info.owner == &crate::ID && info.is_writable,
+                WGBError::InvalidRedemptionAccount
+            );
+            let mut data = info.try_borrow_mut_data()?;
+            let mut req = RedemptionRequest::try_deserialize(&mut &data[..])?;
+
+            let pda = Pubkey::create_program_address(
+                &[
+                    b"redemption",
+                    req.user.as_ref(),
+                    req.request_id.to_le_bytes().as_ref(),
+                    &[req.bump],
+                ],
+                &crate::ID,
+            )
+            .map_err(|_| error!(WGBError::InvalidRedemptionAccount))?;
+            require_keys_eq!(pda, info.key(), WGBError::InvalidRedemptionAccount);
+
+            if req.status != 0 {
+                continue; // Already claimed (or otherwise moved on)
+            }
+            take_claim_slot(&ctx.accounts.protocol_state, &ctx.accounts.fulfiller_profile)?;
+
+            req.status = 1; // Claimed
+            req.fulfiller = fulfiller;
+            req.claimed_at = now;
+            req.try_serialize(&mut &mut data[..])?;
+            claimed.push(req.request_id);
+        }
+
+        bump_redemption_stat(&ctx.accounts.redemption_stats, |s| &mut s.claimed, claimed.len() as u64)?;
+
+        msg!("{} of {} redemptions claimed by {}", claimed.len(), requests.len(), fulfiller);
+        if event_enabled(&ctx.accounts.protocol_state, EVENTS_MINIMAL) {
+            emit!(BatchClaimed {
+                fulfiller,
+                request_ids: claimed,
+                timestamp: now,
+            });
+        }
+        Ok(())
+    }
+
+    /// Mark a claimed redemption as shipped (Fulfiller) — starts the auto-confirm window
+    /// `shipment_proof_hash` commits to the courier hand-off receipt; all zeroes = none.
+    pub fn mark_shipped(ctx: Context<MarkShipped>, shipment_proof_hash: [u8; 32]) -> Result<()> {
+        let req = &mut ctx.accounts.redemption_request;
+
+        req.status = 2; // Shipped
+        req.shipped_at = Clock::get()?.unix_timestamp;
+        bump_redemption_stat(&ctx.accounts.redemption_stats, |s| &mut s.shipped, 1)?;
+
+        let proof = &mut ctx.accounts.delivery_proof;
+        proof.redemption_request = req.key();
+        proof.shipment_proof_hash = shipment_proof_hash;
+        proof.updated_at = req.shipped_at;
+        proof.bump = ctx.bumps.delivery_proof;
+
+        emit!(RedemptionShipped {
+            request_id: req.request_id,
+            fulfiller: req.fulfiller,
+            proof_hash: shipment_proof_hash,
+            timestamp: req.shipped_at,
+        });
+
+        msg!("Redemption #{} shipped", req.request_id);
+        Ok(())
+    }
+
+    /// Dispute a shipped redemption (Redeemer) — blocks auto-confirm until admin resolution
+    /// A confirmed order can still be disputed while its fulfiller reward is held back
+    /// (reward_holdback_secs); the held reward is forfeited and the order reopens as Disputed.
+    pub fn dispute_delivery(ctx: Context<DisputeDelivery>) -> Result<()> {
+        let state = &mut ctx.accounts.protocol_state;
+        let req = &mut ctx.accounts.redemption_request;
+        let now = Clock::get()?.unix_timestamp;
+
+        if req.status == 3 {
+            require!(
+                req.held_reward > 0 && now - req.confirmed_at < state.reward_holdback_secs,
+                WGBError::InvalidRedemptionStatus
+            );
+            let forfeited = take_held_reward(req, &ctx.accounts.held_reward)?;
+            state.pending_fulfiller_rewards = state.pending_fulfiller_rewards.saturating_sub(forfeited);
+            // Disputed orders hold a claim slot again until resolved
+            with_profile_mut(&ctx.accounts.fulfiller_profile, |profile| {
+                profile.active_claims = profile.active_claims.checked_add(1).ok_or(WGBError::MathOverflow)?;
+                Ok(())
+            })?;
+        }
+
+        req.status = 5; // Disputed
+        bump_redemption_stat(&ctx.accounts.redemption_stats, |s| &mut s.disputed, 1)?;
+
+        emit!(DeliveryDisputed {
+            request_id: req.request_id,
+            user: req.user,
+            timestamp: now,
+        });
+
+        msg!("Redemption #{} disputed by {}", req.request_id, req.user);
+        Ok(())
+    }
+
+    /// Set or correct the shipping-address commitment (Redeemer) — only while still Pending
+    /// The commitment (a hash of the off-chain address) lives in its own PDA so
+    /// RedemptionRequest keeps its layout.
+    pub fn amend_redemption(
+        ctx: Context<AmendRedemption>,
+        new_address_commitment: [u8; 32],
+    ) -> Result<()> {
+        let req = &ctx.accounts.redemption_request;
+        require!(req.status == 0, WGBError::CannotAmendActive);
+
+        let address = &mut ctx.accounts.redemption_address;
+        let previous = address.address_commitment;
+        address.redemption_request = req.key();
+        address.address_commitment = new_address_commitment;
+        address.updated_at = Clock::get()?.unix_timestamp;
+        address.bump = ctx.bumps.redemption_address;
+
+        emit!(RedemptionAmended {
+            request_id: req.request_id,
+            user: req.user,
+            previous_commitment: previous,
+            address_commitment: new_address_commitment,
+            timestamp: address.updated_at,
+        });
+
+        msg!("Redemption #{} address amended", req.request_id);
+        Ok(())
+    }
+
+    /// Confirm delivery of a redemption
+    /// Admin/Operator: Claimed, Shipped or Disputed orders. Redeemer: only their own Shipped order.
+    /// `delivery_proof_hash` commits to a signed delivery receipt (e.g. courier POD) so a
+    /// later dispute can reference it without putting PII on-chain; all zeroes = none.
+    pub fn confirm_delivery(ctx: Context<ConfirmDelivery>, delivery_proof_hash: [u8; 32]) -> Result<()> {
+        let state = &mut ctx.accounts.protocol_state;
+        let req = &mut ctx.accounts.redemption_request;
+        let signer = ctx.accounts.signer.key();
+
+        if signer == state.authority || state.is_operator(&signer) {
+            note_backup_operator(state, &signer);
+            require!(
+                req.status == 1 || req.status == 2 || req.status == 5,
+                WGBError::InvalidRedemptionStatus
+            );
+        } else {
+            require_keys_eq!(signer, req.user, WGBError::Unauthorized);
+            require!(req.status == 2, WGBError::InvalidRedemptionStatus);
+        }
+        ensure_multi_fulfilled(state, req, &ctx.accounts.fulfiller_contributions)?;
+
+        req.status = 3; // Confirmed
+        req.confirmed_at = Clock::get()?.unix_timestamp;
+        bump_redemption_stat(&ctx.accounts.redemption_stats, |s| &mut s.confirmed, 1)?;
+
+        let proof = &mut ctx.accounts.delivery_proof;
+        proof.redemption_request = req.key();
+        proof.delivery_proof_hash = delivery_proof_hash;
+        proof.updated_at = req.confirmed_at;
+        proof.bump = ctx.bumps.delivery_proof;
+
+        let held = &mut ctx.accounts.held_reward;
+        held.redemption_request = req.key();
+        held.bump = ctx.bumps.held_reward;
+
+        // Profile is loaded by address, so the reward and the claim slot can't be skipped by omitting it
+        let points_config = load_points_config(&ctx.accounts.points_config)?;
+        let mut reward_multiplier_bps = 0;
+        with_profile_mut(&ctx.accounts.fulfiller_profile, |fulfiller_profile| {
+            let (reward, multiplier_bps) =
+                tiered_fulfiller_reward(state, fulfiller_profile.tier, points_config.fulfiller_reward)?;
+            reward_multiplier_bps = multiplier_bps;
+            settle_fulfiller_reward(state, req, held, fulfiller_profile, reward)
+        })?;
+        release_claim_slot(&ctx.accounts.fulfiller_profile)?;
+
+        emit!(RedemptionConfirmed {
+            request_id: req.request_id,
+            fulfiller: req.fulfiller,
+            proof_hash: delivery_proof_hash,
+            reward_multiplier_bps,
+            timestamp: req.confirmed_at,
+        });
+
+        msg!("Redemption #{} confirmed — delivery complete", req.request_id);
+        settle_priority_fee(&ctx.accounts.redemption_request, &ctx.accounts.fulfiller, false)?;
+        Ok(())
+    }
+
+    /// Pay a held-back fulfiller reward once its dispute window has passed (Public)
+    pub fn release_fulfiller_rewards(ctx: Context<ReleaseFulfillerRewards>) -> Result<()> {
+        let state = &mut ctx.accounts.protocol_state;
+        let req = &mut ctx.accounts.redemption_request;
+        let now = Clock::get()?.unix_timestamp;
+        require!(
+            now - req.confirmed_at >= state.reward_holdback_secs,
+            WGBError::RewardHoldbackActive
+        );
+
+        let held = take_held_reward(req, &ctx.accounts.held_reward)?;
+        state.pending_fulfiller_rewards = state.pending_fulfiller_rewards.saturating_sub(held);
+        let granted = credit_points(&mut ctx.accounts.fulfiller_profile, held, state.max_user_points)?;
+
+        emit!(FulfillerRewardsReleased {
+            request_id: req.request_id,
+            fulfiller: req.fulfiller,
+            points: granted,
+            timestamp: now,
+        });
+
+        msg!("Released {} reward points for redemption #{}", granted, req.request_id);
+        Ok(())
+    }
+
+    /// Record a fulfiller's share of a claimed or shipped order (Fulfiller + approver)
+    /// Orders of at least multi_fulfiller_threshold need min_distinct_fulfillers listed here
+    /// before they can be confirmed. Repeat calls by the same fulfiller add to their share.
+    /// Contributors need a UserProfile, and each share is co-signed by the claiming fulfiller,
+    /// an operator or the admin — only an operator or the admin for orders over the threshold,
+    /// so one fulfiller can't fill the quorum with keys of their own.
+    pub fn record_contribution(ctx: Context<RecordContribution>, amount: u64) -> Result<()> {
+        require!(amount > 0, WGBError::ZeroAmount);
+        let state = &ctx.accounts.protocol_state;
+        let req = &ctx.accounts.redemption_request;
+        let fulfiller = ctx.accounts.fulfiller.key();
+
+        let approver = ctx.accounts.approver.key();
+        let privileged = approver == state.authority || state.is_operator(&approver);
+        let multi_fulfiller =
+            state.multi_fulfiller_threshold > 0 && req.amount >= state.multi_fulfiller_threshold;
+        require!(
+            privileged || (!multi_fulfiller && approver == req.fulfiller),
+            WGBError::Unauthorized
+        );
+        note_backup_operator(state, &approver);
+
+        let contributions = &mut ctx.accounts.fulfiller_contributions;
+        contributions.redemption_request = req.key();
+        contributions.bump = ctx.bumps.fulfiller_contributions;
+        match contributions.contributors.iter_mut().find(|c| c.fulfiller == fulfiller) {
+            Some(existing) => {
+                existing.amount = existing.amount.checked_add(amount).ok_or(WGBError::MathOverflow)?;
+            }
+            None => {
+                require!(
+                    contributions.contributors.len() < MAX_ORDER_FULFILLERS,
+                    WGBError::InvalidConfig
+                );
+                contributions.contributors.push(Contribution { fulfiller, amount });
+            }
+        }
+        let total = contributions
+            .contributors
+            .iter()
+            .try_fold(0u64, |sum, c| sum.checked_add(c.amount))
+            .ok_or(WGBError::MathOverflow)?;
+        require!(total <= req.amount, WGBError::InvalidConfig);
+
+        emit!(ContributionRecorded {
+            request_id: req.request_id,
+            fulfiller,
+            amount,
+            distinct_fulfillers: contributions.contributors.len() as u8,
+            timestamp: Clock::get()?.unix_timestamp,
+        });
+        Ok(())
+    }
+
+    /// Auto-confirm a shipped redemption the redeemer never confirmed (Public)
+    pub fn auto_confirm(ctx: Context<AutoConfirm>) -> Result<()> {
+        let state = &mut ctx.accounts.protocol_state;
+        let req = &mut ctx.accounts.redemption_request;
+        let now = Clock::get()?.unix_timestamp;
+        let timeouts = state.redemption_timeouts();
+
+        // Disputed orders leave the Shipped status, so the account constraint already excludes them
+        require!(
+            timeouts.ship_to_confirm_secs > 0 && now - req.shipped_at > timeouts.ship_to_confirm_secs,
+            WGBError::ConfirmWindowOpen
+        );
+        ensure_multi_fulfilled(state, req, &ctx.accounts.fulfiller_contributions)?;
+
+        req.status = 3; // Confirmed
+        req.confirmed_at = now;
+        bump_redemption_stat(&ctx.accounts.redemption_stats, |s| &mut s.confirmed, 1)?;
+
+        let held = &mut ctx.accounts.held_reward;
+        held.redemption_request = req.key();
+        held.bump = ctx.bumps.held_reward;
+
+        // Profile is loaded by address, so the reward and the claim slot can't be skipped by omitting it
+        let points_config = load_points_config(&ctx.accounts.points_config)?;
+        let mut reward_multiplier_bps = 0;
+        with_profile_mut(&ctx.accounts.fulfiller_profile, |fulfiller_profile| {
+            let (reward, multiplier_bps) =
+                tiered_fulfiller_reward(state, fulfiller_profile.tier, points_config.fulfiller_reward)?;
+            reward_multiplier_bps = multiplier_bps;
+            settle_fulfiller_reward(state, req, held, fulfiller_profile, reward)
+        })?;
+        release_claim_slot(&ctx.accounts.fulfiller_profile)?;
+
+        emit!(AutoConfirmed {
+            request_id: req.request_id,
+            fulfiller: req.fulfiller,
+            shipped_at: req.shipped_at,
+            reward_multiplier_bps,
+            timestamp: now,
+        });
+
+        msg!("Redemption #{} auto-confirmed", req.request_id);
+        settle_priority_fee(&ctx.accounts.redemption_request, &ctx.accounts.fulfiller, false)?;
+        Ok(())
+    }
+
+    /// Record the physical serial delivered for a confirmed redemption (Admin or Operator)
+    /// `serial_leaf` is sha256(serial) as in services/api merkle.ts; the raw `serial` is passed
+    /// too so the leaf is derived on-chain and proven against the current reserve root (see
+    /// verify_serial_proof). The RedeemedSerial marker can only be created once, so a bar can't
+    /// be redeemed twice, and an order records at most one serial per bar it redeemed
+    /// (`amount / redemption_bar_unit`).
+    pub fn record_serial_redemption(
+        ctx: Context<RecordSerialRedemption>,
+        serial_leaf: [u8; 32],
+        serial: String,
+        proof: Vec<[u8; 32]>,
     ) -> Result<()> {
+        note_backup_operator(&ctx.accounts.protocol_state, &ctx.accounts.signer.key());
+        let root = ctx.accounts.protocol_state.current_merkle_root;
+        verify_serial_proof(&serial, serial_leaf, &proof, root)?;
+
+        let bars = ctx.accounts.redemption_request.amount / ctx.accounts.protocol_state.redemption_bar_unit.max(1);
+        let req = &mut ctx.accounts.redemption_request;
+        require!(req.serials_recorded < bars, WGBError::InvalidRedemptionStatus);
+        req.serials_recorded += 1;
+
+        let marker = &mut ctx.accounts.redeemed_serial;
+        marker.serial_leaf = serial_leaf;
+        marker.user = req.user;
+        marker.request_id = req.request_id;
+        marker.redeemed_at = Clock::get()?.unix_timestamp;
+        marker.bump = ctx.bumps.redeemed_serial;
+
+        emit!(SerialRedeemed {
+            serial_leaf,
+            user: req.user,
+            request_id: req.request_id,
+            actor: ctx.accounts.signer.key(),
+            timestamp: marker.redeemed_at,
+        });
+        Ok(())
+    }
+
+    /// Emit whether a serial has already been redeemed (Public, read-only)
+    pub fn check_serial_redeemed(ctx: Context<CheckSerialRedeemed>, serial_leaf: [u8; 32]) -> Result<()> {
+        let info = &ctx.accounts.redeemed_serial;
+        let marker = if info.owner == &crate::ID && !info.data_is_empty() {
+            let data = info.try_borrow_data()?;
+            Some(RedeemedSerial::try_deserialize(&mut &data[..])?)
+        } else {
+            None
+        };
+
+        emit!(SerialRedemptionStatus {
+            serial_leaf,
+            redeemed: marker.is_some(),
+            request_id: marker.as_ref().map_or(0, |m| m.request_id),
+            timestamp: Clock::get()?.unix_timestamp,
+        });
+        Ok(())
+    }
+
+    /// Create the RedemptionStats counters (Admin only) — transitions are counted from here on
+    pub fn init_redemption_stats(ctx: Context<InitRedemptionStats>) -> Result<()> {
+        let stats = &mut ctx.accounts.redemption_stats;
+        stats.bump = ctx.bumps.redemption_stats;
+        msg!("Redemption stats initialized");
+        Ok(())
+    }
+
+    /// Emit the protocol-wide redemption counters (Public, read-only)
+    pub fn emit_redemption_stats(ctx: Context<GetRedemptionStats>) -> Result<()> {
+        let stats = &ctx.accounts.redemption_stats;
+        emit!(RedemptionStatsView {
+            created: stats.created,
+            claimed: stats.claimed,
+            shipped: stats.shipped,
+            disputed: stats.disputed,
+            confirmed: stats.confirmed,
+            cancelled: stats.cancelled,
+            expired: stats.expired,
+            timestamp: Clock::get()?.unix_timestamp,
+        });
+        Ok(())
+    }
+
+    /// Expire a stale claim (Public) — returns the order to Pending and slashes the fulfiller
+    pub fn expire_claim(ctx: Context<ExpireClaim>) -> Result<()> {
+        let state = &ctx.accounts.protocol_state;
+        let req = &mut ctx.accounts.redemption_request;
+        let now = Clock::get()?.unix_timestamp;
+        let timeouts = state.redemption_timeouts();
+
+        // A zero timeout disables expiry entirely
+        require!(
+            timeouts.claim_to_ship_secs > 0 && now - req.claimed_at > timeouts.claim_to_ship_secs,
+            WGBError::ClaimNotExpired
+        );
+
+        let fulfiller = req.fulfiller;
+        req.status = 0; // Back to Pending
+        req.fulfiller = Pubkey::default();
+        req.claimed_at = 0;
+        bump_redemption_stat(&ctx.accounts.redemption_stats, |s| &mut s.expired, 1)?;
+
+        // Profile is loaded by address so the caller cannot dodge the slash by omitting it
+        slash_fulfiller(state, &ctx.accounts.fulfiller_profile, req.request_id, fulfiller)?;
+        release_claim_slot(&ctx.accounts.fulfiller_profile)?;
+
+        emit!(ClaimExpired {
+            request_id: req.request_id,
+            fulfiller,
+            timestamp: now,
+        });
+
+        msg!("Claim on redemption #{} by {} expired", req.request_id, fulfiller);
+        Ok(())
+    }
+
+    /// Cancel a redemption order and re-mint the burned W3B to the user (Admin only)
+    pub fn cancel_redemption(ctx: Context<CancelRedemption>) -> Result<()> {
+        let req = &mut ctx.accounts.redemption_request;
+
+        // Can only cancel Pending (0), Claimed (1) or Disputed (5) orders.
+        // Cancelled (4) is terminal, so a request can never be refunded twice.
+        require!(
+            req.status == 0 || req.status == 1 || req.status == 5,
+            WGBError::InvalidRedemptionStatus
+        );
+
+        // Cancelling a Disputed order resolves the dispute against the fulfiller
+        if req.status == 5 {
+            let (request_id, fulfiller) = (req.request_id, req.fulfiller);
+            slash_fulfiller(&ctx.accounts.protocol_state, &ctx.accounts.fulfiller_profile, request_id, fulfiller)?;
+        }
+        // Claimed or Disputed orders hold one of the fulfiller's claim slots
+        if req.status != 0 {
+            release_claim_slot(&ctx.accounts.fulfiller_profile)?;
+        }
+
+        req.status = 4; // Cancelled
+        bump_redemption_stat(&ctx.accounts.redemption_stats, |s| &mut s.cancelled, 1)?;
+        let amount = req.amount;
+        let request_id = req.request_id;
+        let user = req.user;
+
+        // Refund: reverses the burn, so no reserve check (the supply was already backed)
         let state = &ctx.accounts.protocol_state;
         let seeds = &[b"protocol_state".as_ref(), &[state.bump]];
         let signer = &[&seeds[..]];
 
-        let ix = set_transfer_fee(
-            &ctx.accounts.token_program.key(),
-            &ctx.accounts.wgb_mint.key(),
-            &state.key(),
-            &[],
-            new_fee_bps,
-            new_max_fee,
-        )?;
+        token_2022::mint_to_checked(
+            CpiContext::new_with_signer(
+                ctx.accounts.token_program.to_account_info(),
+                MintToChecked {
+                    mint: ctx.accounts.wgb_mint.to_account_info(),
+                    to: ctx.accounts.user_token_account.to_account_info(),
+                    authority: ctx.accounts.protocol_state.to_account_info(),
+                },
+                signer,
+            ),
+            amount,
+            WGB_DECIMALS,
+        )?;
+
+        let state = &mut ctx.accounts.protocol_state;
+        state.total_supply = state.total_supply.checked_add(amount).ok_or(WGBError::MathOverflow)?;
+        state.total_burned = state.total_burned.checked_sub(amount).ok_or(WGBError::MathOverflow)?;
+        update_coverage(state);
+
+        let now = Clock::get()?.unix_timestamp;
+        emit!(RedemptionRefunded {
+            request_id,
+            user,
+            amount,
+            new_total_supply: state.total_supply,
+            actor: ctx.accounts.authority.key(),
+            timestamp: now,
+        });
+        emit!(RedemptionCancelled {
+            request_id,
+            actor: ctx.accounts.authority.key(),
+            timestamp: now,
+        });
+
+        msg!("Redemption #{} cancelled, {} W3B refunded to {}", request_id, amount, user);
+        settle_priority_fee(&ctx.accounts.redemption_request, &ctx.accounts.user, true)?;
+        Ok(())
+    }
+
+    /// Cancel your own Pending redemption (Redeemer)
+    /// Refunds amount minus cancel_penalty_bps; the penalty stays burned for good. The
+    /// redemption points credited at burn are clawed back (at the current multiplier), so
+    /// burn→cancel cycling earns nothing.
+    pub fn cancel_own_redemption(ctx: Context<CancelOwnRedemption>) -> Result<()> {
+        require!(!ctx.accounts.protocol_state.is_paused, WGBError::ProtocolPaused);
+
+        let req = &mut ctx.accounts.redemption_request;
+        req.status = 4; // Cancelled
+        bump_redemption_stat(&ctx.accounts.redemption_stats, |s| &mut s.cancelled, 1)?;
+        let amount = req.amount;
+        let request_id = req.request_id;
+        let user = req.user;
+
+        let penalty_bps = ctx.accounts.protocol_state.cancel_penalty_bps as u128;
+        let penalty = (amount as u128 * penalty_bps / 10_000) as u64;
+        let refund = amount - penalty;
+
+        let clawed_back =
+            clawback_burn_points(&ctx.accounts.user_profile, &ctx.accounts.points_config, amount)?;
+        let accounts = &mut *ctx.accounts;
+        remint_refund(
+            &mut accounts.protocol_state,
+            &accounts.wgb_mint,
+            &accounts.user_token_account,
+            &accounts.token_program,
+            refund,
+        )?;
+
+        let now = Clock::get()?.unix_timestamp;
+        emit!(RedemptionRefunded {
+            request_id,
+            user,
+            amount: refund,
+            new_total_supply: accounts.protocol_state.total_supply,
+            actor: user,
+            timestamp: now,
+        });
+        emit!(CancelPenaltyApplied {
+            request_id,
+            user,
+            penalty,
+            points_clawed_back: clawed_back,
+            timestamp: now,
+        });
+        emit!(RedemptionCancelled {
+            request_id,
+            actor: user,
+            timestamp: now,
+        });
+
+        msg!("Redemption #{} cancelled by {}, {} W3B refunded ({} kept)", request_id, user, refund, penalty);
+        settle_priority_fee(&accounts.redemption_request, &accounts.user, true)?;
+        Ok(())
+    }
+
+    /// Expire a Pending order nobody claimed within pending_expiry_secs (Public)
+    /// Cancels it with a full refund; the burn points are clawed back as on a user cancel.
+    /// Measured from created_at, so an order returned to Pending by expire_claim keeps its age.
+    pub fn expire_pending(ctx: Context<ExpirePending>) -> Result<()> {
+        let timeouts = ctx.accounts.protocol_state.redemption_timeouts();
+        let req = &mut ctx.accounts.redemption_request;
+        let now = Clock::get()?.unix_timestamp;
+        require!(
+            timeouts.pending_expiry_secs > 0 && now - req.created_at > timeouts.pending_expiry_secs,
+            WGBError::PendingNotExpired
+        );
+
+        req.status = 4; // Cancelled
+        bump_redemption_stat(&ctx.accounts.redemption_stats, |s| &mut s.cancelled, 1)?;
+        let (amount, request_id, user) = (req.amount, req.request_id, req.user);
+
+        let clawed_back =
+            clawback_burn_points(&ctx.accounts.user_profile, &ctx.accounts.points_config, amount)?;
+        let accounts = &mut *ctx.accounts;
+        remint_refund(
+            &mut accounts.protocol_state,
+            &accounts.wgb_mint,
+            &accounts.user_token_account,
+            &accounts.token_program,
+            amount,
+        )?;
+
+        let caller = accounts.caller.key();
+        emit!(RedemptionRefunded {
+            request_id,
+            user,
+            amount,
+            new_total_supply: accounts.protocol_state.total_supply,
+            actor: caller,
+            timestamp: now,
+        });
+        emit!(PendingExpired {
+            request_id,
+            user,
+            created_at: accounts.redemption_request.created_at,
+            points_clawed_back: clawed_back,
+            timestamp: now,
+        });
+        emit!(RedemptionCancelled {
+            request_id,
+            actor: caller,
+            timestamp: now,
+        });
+
+        msg!("Pending redemption #{} expired, {} W3B refunded to {}", request_id, amount, user);
+        settle_priority_fee(&accounts.redemption_request, &accounts.user, true)?;
+        Ok(())
+    }
+
+    /// Break-glass: force a redemption into any status (Admin only)
+    /// Skips the normal transition rules for stuck or corrupted orders. `reason_code` is
+    /// recorded in the AdminStatusOverride event. Leaving a fulfiller-held status (Claimed,
+    /// Shipped, Disputed) frees the fulfiller's claim slot, and Disputed -> Cancelled slashes
+    /// the fulfiller like cancel_redemption does; RedemptionStats are not adjusted. Cancelled
+    /// orders have already been refunded and are final, so they can't be moved back out.
+    pub fn admin_set_redemption_status(
+        ctx: Context<AdminSetStatus>,
+        status: u8,
+        reason_code: u8,
+    ) -> Result<()> {
+        require!(status <= 5, WGBError::InvalidRedemptionStatus);
+
+        let req = &mut ctx.accounts.redemption_request;
+        let old_status = req.status;
+        // Reopening a refunded order would let it be cancelled and refunded again
+        require!(old_status != 4, WGBError::InvalidRedemptionStatus);
+        let holds_claim = |s: u8| s == 1 || s == 2 || s == 5;
+        if old_status == 5 && status == 4 {
+            let (request_id, fulfiller) = (req.request_id, req.fulfiller);
+            slash_fulfiller(&ctx.accounts.protocol_state, &ctx.accounts.fulfiller_profile, request_id, fulfiller)?;
+        }
+        if holds_claim(old_status) && !holds_claim(status) {
+            release_claim_slot(&ctx.accounts.fulfiller_profile)?;
+        }
+        req.status = status;
+
+        emit!(AdminStatusOverride {
+            request_id: req.request_id,
+            user: req.user,
+            old_status,
+            new_status: status,
+            reason_code,
+            authority: ctx.accounts.authority.key(),
+            timestamp: Clock::get()?.unix_timestamp,
+        });
+
+        msg!(
+            "Redemption #{} status forced {} -> {} (reason {})",
+            req.request_id,
+            old_status,
+            status,
+            reason_code
+        );
+        Ok(())
+    }
+
+    // ==================== ADMIN OPS ====================
+
+    /// Close ProtocolState PDA (Admin + multisig) — enables clean-slate reinit
+    pub fn close_protocol_state(ctx: Context<CloseProtocolState>) -> Result<()> {
+        require_multisig(&ctx.accounts.admin_multisig, &ctx.accounts.authority, ctx.remaining_accounts)?;
+        msg!("Protocol state closed — ready for fresh initialization");
+        Ok(())
+    }
+
+    /// Configure the co-signers required for the most sensitive admin instructions (Admin + multisig)
+    /// Co-signers sign the transaction and are passed as remaining_accounts. Once a threshold
+    /// is set, changing it needs the current threshold too; threshold 0 turns the check off.
+    pub fn set_admin_multisig(
+        ctx: Context<SetAdminMultisig>,
+        signers: Vec<Pubkey>,
+        threshold: u8,
+    ) -> Result<()> {
+        let multisig = &mut ctx.accounts.admin_multisig;
+        require!(
+            multisig.is_satisfied(&ctx.accounts.authority.key(), ctx.remaining_accounts),
+            WGBError::MultisigThresholdNotMet
+        );
+
+        require!(signers.len() <= MAX_MULTISIG_SIGNERS, WGBError::InvalidConfig);
+        require!(threshold as usize <= signers.len(), WGBError::InvalidConfig);
+        for (i, signer) in signers.iter().enumerate() {
+            require!(!signers[..i].contains(signer), WGBError::InvalidConfig);
+        }
+
+        multisig.signers = signers;
+        multisig.threshold = threshold;
+        multisig.bump = ctx.bumps.admin_multisig;
+
+        emit!(AdminMultisigUpdated {
+            signers: multisig.signers.clone(),
+            threshold,
+            actor: ctx.accounts.authority.key(),
+            timestamp: Clock::get()?.unix_timestamp,
+        });
+        Ok(())
+    }
+
+    /// Split buy proceeds across several receivers by bps (Admin only)
+    /// Shares must sum to 10_000; an empty list falls back to the single sol_receiver.
+    /// Buyers then pass the receivers, in this order, as writable remaining_accounts.
+    pub fn set_sol_split(ctx: Context<SetSolSplit>, receivers: Vec<SolShare>) -> Result<()> {
+        require!(receivers.len() <= MAX_SOL_RECEIVERS, WGBError::InvalidSplit);
+        if !receivers.is_empty() {
+            let mut total: u32 = 0;
+            for (i, share) in receivers.iter().enumerate() {
+                require!(share.bps > 0, WGBError::InvalidSplit);
+                require!(
+                    !receivers[..i].iter().any(|s| s.receiver == share.receiver),
+                    WGBError::InvalidSplit
+                );
+                total += share.bps as u32;
+            }
+            require!(total == 10_000, WGBError::InvalidSplit);
+        }
+
+        let split = &mut ctx.accounts.sol_split;
+        split.receivers = receivers;
+        split.bump = ctx.bumps.sol_split;
+
+        emit!(SolSplitUpdated {
+            receivers: split.receivers.clone(),
+            actor: ctx.accounts.authority.key(),
+            timestamp: Clock::get()?.unix_timestamp,
+        });
+        Ok(())
+    }
+
+    /// Program ids allowed to CPI into the buy path under CPI_GATE_ALLOWLIST (Admin only)
+    pub fn set_cpi_allowlist(ctx: Context<SetCpiAllowlist>, programs: Vec<Pubkey>) -> Result<()> {
+        require!(programs.len() <= MAX_CPI_ALLOWLIST, WGBError::InvalidConfig);
+        for (i, program) in programs.iter().enumerate() {
+            require!(!programs[..i].contains(program), WGBError::InvalidConfig);
+        }
+
+        let allowlist = &mut ctx.accounts.cpi_allowlist;
+        allowlist.programs = programs;
+        allowlist.bump = ctx.bumps.cpi_allowlist;
+
+        emit!(CpiAllowlistUpdated {
+            programs: allowlist.programs.clone(),
+            actor: ctx.accounts.authority.key(),
+            timestamp: Clock::get()?.unix_timestamp,
+        });
+        Ok(())
+    }
+
+    /// Bonus points for the first buy of a day, by streak length (Admin only)
+    /// Entry i applies to a streak of i + 1 days; the last entry covers every longer streak.
+    pub fn set_streak_bonus(ctx: Context<AdminOnly>, bonus_points: [u32; STREAK_BONUS_DAYS]) -> Result<()> {
+        ctx.accounts.protocol_state.streak_bonus_points = bonus_points;
+        emit_config_updated("streak_bonus", ctx.accounts.authority.key())?;
+        msg!("Streak bonus set to {:?}", bonus_points);
+        Ok(())
+    }
+
+    /// How long lock_quote holds a price (Admin only) — 0 = DEFAULT_QUOTE_LOCK_SECS
+    pub fn set_quote_lock_secs(ctx: Context<AdminOnly>, secs: i64) -> Result<()> {
+        let state = &mut ctx.accounts.protocol_state;
+        let windows = TimeWindows { quote_lock_secs: secs, ..state.time_windows() };
+        apply_time_windows(state, windows)?;
+        emit_config_updated("quote_lock_secs", ctx.accounts.authority.key())?;
+        msg!("Quote lock window set to {}s", secs);
+        Ok(())
+    }
+
+    /// Who may reach buys through CPI (Admin only) — CPI_GATE_OPEN, _BLOCK_ALL or _ALLOWLIST
+    pub fn set_cpi_gate(ctx: Context<AdminOnly>, mode: u8) -> Result<()> {
+        require!(mode <= CPI_GATE_ALLOWLIST, WGBError::InvalidConfig);
+        ctx.accounts.protocol_state.cpi_gate = mode;
+        emit_config_updated("cpi_gate", ctx.accounts.authority.key())?;
+        msg!("CPI gate set to {}", mode);
+        Ok(())
+    }
+
+    /// Pause or unpause (Admin only) — the only path that can resume operations
+    pub fn set_paused(ctx: Context<AdminOnly>, paused: bool) -> Result<()> {
+        apply_pause(ctx.accounts, paused, PauseReason::Unspecified)
+    }
+
+    /// Pause or unpause with a reason front-ends can display (Admin only)
+    /// Unpausing always clears the reason.
+    pub fn set_paused_with_reason(
+        ctx: Context<AdminOnly>,
+        paused: bool,
+        reason: PauseReason,
+    ) -> Result<()> {
+        apply_pause(ctx.accounts, paused, reason)
+    }
+
+    /// Pause for scheduled maintenance until `until` (Admin only)
+    /// Anyone can resume the protocol with try_auto_unpause once the timestamp has passed.
+    pub fn set_paused_until(ctx: Context<AdminOnly>, until: i64) -> Result<()> {
+        require!(until > Clock::get()?.unix_timestamp, WGBError::InvalidConfig);
+        apply_pause(ctx.accounts, true, PauseReason::Maintenance)?;
+        ctx.accounts.protocol_state.paused_until = until;
+        emit_config_updated("paused_until", ctx.accounts.authority.key())?;
+        msg!("Protocol paused for maintenance until {}", until);
+        Ok(())
+    }
+
+    /// Resume a scheduled maintenance pause once its timestamp has passed (Public)
+    /// Stays paused while supply is under-collateralized; the admin must then unpause manually.
+    pub fn try_auto_unpause(ctx: Context<TryAutoUnpause>) -> Result<()> {
+        let now = Clock::get()?.unix_timestamp;
+        let state = &mut ctx.accounts.protocol_state;
+        require!(
+            state.is_paused && state.paused_until > 0 && now >= state.paused_until,
+            WGBError::AutoUnpauseNotDue
+        );
+        update_coverage(state);
+        require!(state.coverage_bps >= 10_000, WGBError::UnderCollateralized);
+
+        let paused_until = state.paused_until;
+        state.is_paused = false;
+        state.pause_reason = PauseReason::Unspecified as u8;
+        state.paused_until = 0;
+
+        emit!(AutoUnpaused {
+            paused_until,
+            actor: ctx.accounts.signer.key(),
+            timestamp: now,
+        });
+        msg!("Maintenance pause ended at {}", now);
+        Ok(())
+    }
+
+    /// Maximum reserve-proof age for mints and yield (Admin only) — 0 restores the 48h default
+    pub fn set_max_proof_age_secs(ctx: Context<AdminOnly>, secs: i64) -> Result<()> {
+        require!(secs >= 0, WGBError::InvalidConfig);
+        ctx.accounts.protocol_state.max_proof_age_secs = secs;
+        emit_config_updated("max_proof_age_secs", ctx.accounts.authority.key())?;
+        msg!("Max proof age set to {}s", secs);
+        Ok(())
+    }
+
+    /// Delay after each proof before mint_wgb accepts it (Admin only) — 0 disables it
+    pub fn set_mint_delay(ctx: Context<AdminOnly>, secs: i64) -> Result<()> {
+        require!(secs >= 0, WGBError::InvalidConfig);
+        ctx.accounts.protocol_state.mint_delay_secs = secs;
+        emit_config_updated("mint_delay", ctx.accounts.authority.key())?;
+        msg!("Mint delay set to {}s", secs);
+        Ok(())
+    }
+
+    /// Minimum time between two mint_wgb calls (Admin only) — 0 disables the cool-down
+    pub fn set_min_mint_interval(ctx: Context<AdminOnly>, secs: i64) -> Result<()> {
+        require!(secs >= 0, WGBError::InvalidConfig);
+        ctx.accounts.protocol_state.min_mint_interval_secs = secs;
+        emit_config_updated("min_mint_interval", ctx.accounts.authority.key())?;
+        msg!("Min mint interval set to {}s", secs);
+        Ok(())
+    }
+
+    /// Configure the low-treasury signal emitted by buy_wgb (Admin only) — threshold 0 disables it
+    pub fn set_replenish_config(
+        ctx: Context<AdminOnly>,
+        threshold: u64,
+        top_up: u64,
+        auto_replenish: bool,
+    ) -> Result<()> {
+        require!(threshold == 0 || top_up > 0, WGBError::InvalidConfig);
+        let state = &mut ctx.accounts.protocol_state;
+        state.replenish_threshold = threshold;
+        state.replenish_amount = top_up;
+        state.auto_replenish = auto_replenish;
+        emit_config_updated("replenish_config", ctx.accounts.authority.key())?;
+        msg!("Replenish below {} with {} (auto: {})", threshold, top_up, auto_replenish);
+        Ok(())
+    }
+
+    /// Align redemptions to whole bars of `bar_unit` tokens (Admin only) — bar_unit 0 turns it off
+    /// `mode` is REDEMPTION_ROUND_FLOOR, _NEAREST or _REJECT; rounded-off tokens stay in the wallet.
+    pub fn set_redemption_rounding(ctx: Context<AdminOnly>, mode: u8, bar_unit: u64) -> Result<()> {
+        require!(mode <= REDEMPTION_ROUND_REJECT, WGBError::InvalidConfig);
+        let state = &mut ctx.accounts.protocol_state;
+        state.redemption_rounding = mode;
+        state.redemption_bar_unit = bar_unit;
+        emit_config_updated("redemption_rounding", ctx.accounts.authority.key())?;
+        msg!("Redemption rounding set to mode {} with {}-token bars", mode, bar_unit);
+        Ok(())
+    }
+
+    /// Back the supply by serial count (0) or by attested weight (1) (Admin only)
+    /// Weight basis needs grams_per_token_bps so weight can be converted to tokens.
+    pub fn set_reserve_unit(ctx: Context<AdminOnly>, unit: u8) -> Result<()> {
+        let state = &mut ctx.accounts.protocol_state;
+        require!(unit <= RESERVE_UNIT_WEIGHT, WGBError::InvalidConfig);
+        require!(
+            unit == RESERVE_UNIT_SERIALS || state.grams_per_token_bps > 0,
+            WGBError::InvalidConfig
+        );
+        state.reserve_unit = unit;
+        update_coverage(state);
+        emit_config_updated("reserve_unit", ctx.accounts.authority.key())?;
+        msg!("Reserve unit set to {}", unit);
+        Ok(())
+    }
+
+    /// Gold weight per token in 1/10_000 g (Admin only) — e.g. 10_000 for one gram per W3B
+    pub fn set_grams_per_token_bps(ctx: Context<AdminOnly>, grams_per_token_bps: u64) -> Result<()> {
+        require!(grams_per_token_bps > 0, WGBError::InvalidConfig);
+        ctx.accounts.protocol_state.grams_per_token_bps = grams_per_token_bps;
+        update_coverage(&mut ctx.accounts.protocol_state);
+        emit_config_updated("grams_per_token_bps", ctx.accounts.authority.key())?;
+        msg!("Gold weight set to {} bps of a gram per token", grams_per_token_bps);
+        Ok(())
+    }
+
+    /// Cap issuance per rolling window (Admin only) — max_per_period 0 disables the limit
+    pub fn set_mint_rate_limit(
+        ctx: Context<AdminOnly>,
+        max_per_period: u64,
+        period_secs: i64,
+    ) -> Result<()> {
+        require!(max_per_period == 0 || period_secs > 0, WGBError::InvalidConfig);
+        let state = &mut ctx.accounts.protocol_state;
+        state.max_mint_per_period = max_per_period;
+        state.mint_period_secs = period_secs;
+        // Start a fresh window under the new budget
+        state.mint_period_start = 0;
+        state.minted_in_period = 0;
+        emit_config_updated("mint_rate_limit", ctx.accounts.authority.key())?;
+        msg!("Mint rate limit set to {} per {}s", max_per_period, period_secs);
+        Ok(())
+    }
+
+    /// Register the reserve oracle key (Admin only) — Pubkey::default() disables signed proofs
+    pub fn set_oracle(ctx: Context<AdminOnly>, oracle: Pubkey) -> Result<()> {
+        let previous = ctx.accounts.protocol_state.oracle;
+        ctx.accounts.protocol_state.oracle = oracle;
+        emit_role_key_updated("oracle", previous, oracle, ctx.accounts.authority.key())?;
+        msg!("Oracle updated to {}", oracle);
+        Ok(())
+    }
+
+    /// Set the Guardian key (Admin only) — Pubkey::default() disables the role
+    pub fn set_guardian(ctx: Context<AdminOnly>, guardian: Pubkey) -> Result<()> {
+        let previous = ctx.accounts.protocol_state.guardian;
+        ctx.accounts.protocol_state.guardian = guardian;
+        emit_role_key_updated("guardian", previous, guardian, ctx.accounts.authority.key())?;
+        msg!("Guardian updated to {}", guardian);
+        Ok(())
+    }
+
+    /// Emergency brake (Guardian, Operator or Admin) — can pause, never unpause
+    pub fn guardian_pause(ctx: Context<GuardianPause>) -> Result<()> {
+        note_backup_operator(&ctx.accounts.protocol_state, &ctx.accounts.signer.key());
+        let state = &mut ctx.accounts.protocol_state;
+        state.is_paused = true;
+        state.pause_reason = PauseReason::Emergency as u8;
+        state.paused_until = 0;
+
+        emit!(EmergencyPaused {
+            actor: ctx.accounts.signer.key(),
+            timestamp: Clock::get()?.unix_timestamp,
+        });
+
+        msg!("Protocol paused by {}", ctx.accounts.signer.key());
+        Ok(())
+    }
+
+    pub fn set_sol_receiver(ctx: Context<MultisigAdminOnly>, receiver: Pubkey) -> Result<()> {
+        require_multisig(&ctx.accounts.admin_multisig, &ctx.accounts.authority, ctx.remaining_accounts)?;
+        let previous = ctx.accounts.protocol_state.sol_receiver;
+        ctx.accounts.protocol_state.sol_receiver = receiver;
+        emit_role_key_updated("sol_receiver", previous, receiver, ctx.accounts.authority.key())?;
+        msg!("SOL receiver updated to {}", receiver);
+        Ok(())
+    }
+
+    /// Point the protocol at a new treasury token account (Admin + multisig)
+    /// `owner_mode` must match who owns it: the protocol PDA (TREASURY_OWNER_PDA) or the
+    /// authority (TREASURY_OWNER_AUTHORITY). Rerun after set_authority in authority mode.
+    pub fn set_treasury(ctx: Context<SetTreasury>, owner_mode: u8) -> Result<()> {
+        require_multisig(&ctx.accounts.admin_multisig, &ctx.accounts.authority, ctx.remaining_accounts)?;
+        let expected_owner = match owner_mode {
+            TREASURY_OWNER_PDA => ctx.accounts.protocol_state.key(),
+            TREASURY_OWNER_AUTHORITY => ctx.accounts.authority.key(),
+            _ => return err!(WGBError::InvalidConfig),
+        };
+        require_keys_eq!(ctx.accounts.treasury.owner, expected_owner, WGBError::InvalidConfig);
+
+        let state = &mut ctx.accounts.protocol_state;
+        let previous = state.treasury;
+        state.treasury = ctx.accounts.treasury.key();
+        state.treasury_owner_mode = owner_mode;
+        emit_role_key_updated("treasury", previous, state.treasury, ctx.accounts.authority.key())?;
+        msg!("Treasury set to {} (owner mode {})", state.treasury, owner_mode);
+        Ok(())
+    }
+    
+    /// Move tokens sent to the protocol PDA by mistake out to `destination` (Admin only)
+    /// Works for SPL Token and Token-2022 mints; the W3B mint itself is refused so the
+    /// treasury can never be drained this way.
+    pub fn recover_stranded_tokens(ctx: Context<RecoverStranded>, amount: u64) -> Result<()> {
+        let state = &ctx.accounts.protocol_state;
+        consume_withdrawal(&ctx.accounts.withdrawal_throttle, state.is_paused, 0, amount)?;
+        let seeds = &[b"protocol_state".as_ref(), &[state.bump]];
+        let signer = &[&seeds[..]];
+
+        token_interface::transfer_checked(
+            CpiContext::new_with_signer(
+                ctx.accounts.token_program.to_account_info(),
+                token_interface::TransferChecked {
+                    from: ctx.accounts.source.to_account_info(),
+                    to: ctx.accounts.destination.to_account_info(),
+                    mint: ctx.accounts.stranded_mint.to_account_info(),
+                    authority: ctx.accounts.protocol_state.to_account_info(),
+                },
+                signer,
+            ),
+            amount,
+            ctx.accounts.stranded_mint.decimals,
+        )?;
+
+        emit!(StrandedTokensRecovered {
+            mint: ctx.accounts.stranded_mint.key(),
+            source: ctx.accounts.source.key(),
+            destination: ctx.accounts.destination.key(),
+            amount,
+            actor: ctx.accounts.authority.key(),
+            timestamp: Clock::get()?.unix_timestamp,
+        });
+        Ok(())
+    }
+
+    /// Create or replace the withdrawal throttle (Admin + multisig)
+    /// Caps what trigger_buyback, challenge-bond payouts and operator rebates (SOL) and
+    /// recover_stranded_tokens (tokens) can move out per window; while paused only
+    /// `paused_limit_bps` of each cap is available.
+    pub fn set_withdrawal_throttle(
+        ctx: Context<SetWithdrawalThrottle>,
+        window_secs: i64,
+        sol_limit: u64,
+        token_limit: u64,
+        paused_limit_bps: u16,
+    ) -> Result<()> {
+        require_multisig(&ctx.accounts.admin_multisig, &ctx.accounts.authority, ctx.remaining_accounts)?;
+        require!(window_secs >= 0 && paused_limit_bps <= 10_000, WGBError::InvalidConfig);
+
+        let throttle = &mut ctx.accounts.withdrawal_throttle;
+        if throttle.window_secs != window_secs {
+            // A new period length starts a fresh window
+            throttle.window_start = 0;
+            throttle.sol_out = 0;
+            throttle.tokens_out = 0;
+        }
+        throttle.window_secs = window_secs;
+        throttle.sol_limit = sol_limit;
+        throttle.token_limit = token_limit;
+        throttle.paused_limit_bps = paused_limit_bps;
+        throttle.bump = ctx.bumps.withdrawal_throttle;
+
+        emit_throttle_updated(throttle, ctx.accounts.authority.key())
+    }
+
+    /// Lower the withdrawal caps (Operator, guardian or admin)
+    /// Only ever tightens, so a hot key can clamp outflows without being able to reopen them.
+    pub fn tighten_withdrawal_throttle(
+        ctx: Context<TightenWithdrawalThrottle>,
+        sol_limit: u64,
+        token_limit: u64,
+    ) -> Result<()> {
+        let throttle = &mut ctx.accounts.withdrawal_throttle;
+        require!(throttle.window_secs > 0, WGBError::InvalidConfig);
+        require!(
+            sol_limit <= throttle.effective_sol_limit() && token_limit <= throttle.effective_token_limit(),
+            WGBError::InvalidConfig
+        );
+        throttle.sol_limit = sol_limit;
+        throttle.token_limit = token_limit;
+
+        emit_throttle_updated(throttle, ctx.accounts.signer.key())
+    }
+
+    /// Share of buy proceeds routed into the insurance fund, in bps (Admin only) — 0 = none
+    /// A cut that would leave the fund PDA below its rent-exempt minimum goes to the receiver
+    /// instead, so the fund only starts filling once one cut (or a direct deposit) covers rent.
+    pub fn set_insurance_fee_bps(ctx: Context<AdminOnly>, bps: u16) -> Result<()> {
+        require!(bps <= 10_000, WGBError::InvalidConfig);
+        ctx.accounts.protocol_state.insurance_fee_bps = bps;
+        emit_config_updated("insurance_fee_bps", ctx.accounts.authority.key())?;
+        msg!("Insurance fee set to {} bps", bps);
+        Ok(())
+    }
+
+    /// Pay a disputed redemption's user out of the insurance fund (Admin only)
+    /// Only lamports the fund received through insurance_fee_bps (insurance_balance) can be
+    /// paid out, the fund keeps its rent-exempt minimum, and payouts count against the
+    /// withdrawal throttle. Each order is covered at most once (CoveredLoss marker), for no
+    /// more than its redeemed amount at the current price.
+    pub fn cover_loss(ctx: Context<CoverLoss>, amount: u64) -> Result<()> {
+        require!(amount > 0, WGBError::ZeroAmount);
+        let state = &ctx.accounts.protocol_state;
+        let req = &ctx.accounts.redemption_request;
+        let order_value = compute_cost(state.wgb_price_lamports, req.amount, WGB_DECIMALS, 0, false)?;
+        require!(amount <= order_value, WGBError::LossExceedsOrderValue);
+        let available = ctx
+            .accounts
+            .insurance_fund
+            .lamports()
+            .saturating_sub(Rent::get()?.minimum_balance(0));
+        require!(
+            amount <= state.insurance_balance && amount <= available,
+            WGBError::InsufficientInsurance
+        );
+        consume_withdrawal(&ctx.accounts.withdrawal_throttle, state.is_paused, amount, 0)?;
+
+        let fund_bump = ctx.bumps.insurance_fund;
+        let seeds = &[b"insurance_fund".as_ref(), &[fund_bump]];
+        let signer = &[&seeds[..]];
+        system_program::transfer(
+            CpiContext::new_with_signer(
+                ctx.accounts.system_program.to_account_info(),
+                system_program::Transfer {
+                    from: ctx.accounts.insurance_fund.to_account_info(),
+                    to: ctx.accounts.user.to_account_info(),
+                },
+                signer,
+            ),
+            amount,
+        )?;
+
+        let state = &mut ctx.accounts.protocol_state;
+        state.insurance_balance -= amount;
+
+        let req = &ctx.accounts.redemption_request;
+        let covered = &mut ctx.accounts.covered_loss;
+        covered.redemption_request = req.key();
+        covered.amount = amount;
+        covered.covered_at = Clock::get()?.unix_timestamp;
+        covered.bump = ctx.bumps.covered_loss;
+
+        emit!(LossCovered {
+            request_id: req.request_id,
+            user: req.user,
+            amount,
+            insurance_balance: state.insurance_balance,
+            actor: ctx.accounts.authority.key(),
+            timestamp: Clock::get()?.unix_timestamp,
+        });
+
+        msg!("Covered {} lamports of loss on redemption #{}", amount, req.request_id);
+        Ok(())
+    }
+
+    /// Update a Token-2022 metadata field on the W3B mint (Admin only)
+    /// `field` is "name", "symbol", "uri" or any custom key. The protocol PDA must be the
+    /// metadata update authority; the authority tops up the mint's rent if the entry grows.
+    pub fn update_token_metadata(
+        ctx: Context<UpdateMetadata>,
+        field: String,
+        value: String,
+    ) -> Result<()> {
+        // Upper bound on growth: the whole new entry (plus length prefixes) may be appended
+        let mint_info = ctx.accounts.wgb_mint.to_account_info();
+        let max_len = mint_info.data_len() + field.len() + value.len() + 8;
+        let required = Rent::get()?.minimum_balance(max_len);
+        let shortfall = required.saturating_sub(mint_info.lamports());
+        if shortfall > 0 {
+            system_program::transfer(
+                CpiContext::new(
+                    ctx.accounts.system_program.to_account_info(),
+                    system_program::Transfer {
+                        from: ctx.accounts.authority.to_account_info(),
+                        to: mint_info.clone(),
+                    },
+                ),
+                shortfall,
+            )?;
+        }
+
+        let metadata_field = match field.as_str() {
+            "name" => Field::Name,
+            "symbol" => Field::Symbol,
+            "uri" => Field::Uri,
+            key => Field::Key(key.to_string()),
+        };
+
+        let bump = ctx.accounts.protocol_state.bump;
+        let seeds = &[b"protocol_state".as_ref(), &[bump]];
+        let signer = &[&seeds[..]];
+
+        token_metadata_update_field(
+            CpiContext::new_with_signer(
+                ctx.accounts.token_program.to_account_info(),
+                TokenMetadataUpdateField {
+                    program_id: ctx.accounts.token_program.to_account_info(),
+                    metadata: mint_info,
+                    update_authority: ctx.accounts.protocol_state.to_account_info(),
+                },
+                signer,
+            ),
+            metadata_field,
+            value.clone(),
+        )?;
+
+        emit!(MetadataUpdated {
+            field,
+            value,
+            actor: ctx.accounts.authority.key(),
+            timestamp: Clock::get()?.unix_timestamp,
+        });
+        Ok(())
+    }
+
+    /// Choose ceiling (true) or floor (false) rounding for buy cost (Admin only)
+    pub fn set_round_up_cost(ctx: Context<AdminOnly>, round_up: bool) -> Result<()> {
+        ctx.accounts.protocol_state.round_up_cost = round_up;
+        emit_config_updated("round_up_cost", ctx.accounts.authority.key())?;
+        msg!("Cost rounding set to {}", if round_up { "ceiling" } else { "floor" });
+        Ok(())
+    }
+
+    /// Set every redemption lifecycle deadline at once (Admin only) — 0 disables a stage
+    pub fn set_redemption_timeouts(ctx: Context<AdminOnly>, timeouts: RedemptionTimeouts) -> Result<()> {
+        let state = &mut ctx.accounts.protocol_state;
+        let windows = TimeWindows { redemption: timeouts, ..state.time_windows() };
+        apply_time_windows(state, windows)?;
+        emit_config_updated("redemption_timeouts", ctx.accounts.authority.key())?;
+        msg!(
+            "Redemption timeouts: pending {}s, claim→ship {}s, ship→confirm {}s",
+            timeouts.pending_expiry_secs,
+            timeouts.claim_to_ship_secs,
+            timeouts.ship_to_confirm_secs
+        );
+        Ok(())
+    }
+
+    /// Set every commitment window at once (Admin only) — see TimeWindows for the bounds
+    /// The single-field setters go through the same checks; use this one when a change
+    /// would only be consistent once several windows move together.
+    pub fn set_time_windows(ctx: Context<AdminOnly>, windows: TimeWindows) -> Result<()> {
+        apply_time_windows(&mut ctx.accounts.protocol_state, windows)?;
+        emit_config_updated("time_windows", ctx.accounts.authority.key())?;
+        msg!(
+            "Time windows: quote {}s, pending {}s, claim→ship {}s, ship→confirm {}s, holdback {}s",
+            windows.quote_lock_secs,
+            windows.redemption.pending_expiry_secs,
+            windows.redemption.claim_to_ship_secs,
+            windows.redemption.ship_to_confirm_secs,
+            windows.reward_holdback_secs
+        );
+        Ok(())
+    }
+
+    /// Seconds a fulfiller may hold a claim before anyone can expire it; 0 disables (Admin only)
+    pub fn set_claim_timeout(ctx: Context<AdminOnly>, timeout_secs: i64) -> Result<()> {
+        let state = &mut ctx.accounts.protocol_state;
+        let mut windows = state.time_windows();
+        windows.redemption.claim_to_ship_secs = timeout_secs;
+        apply_time_windows(state, windows)?;
+        emit_config_updated("claim_timeout", ctx.accounts.authority.key())?;
+        msg!("Claim timeout set to {}s", timeout_secs);
+        Ok(())
+    }
+
+    /// Seconds after shipping before anyone can auto-confirm; 0 disables (Admin only)
+    pub fn set_auto_confirm_secs(ctx: Context<AdminOnly>, secs: i64) -> Result<()> {
+        let state = &mut ctx.accounts.protocol_state;
+        let mut windows = state.time_windows();
+        windows.redemption.ship_to_confirm_secs = secs;
+        apply_time_windows(state, windows)?;
+        emit_config_updated("auto_confirm_secs", ctx.accounts.authority.key())?;
+        msg!("Auto-confirm window set to {}s", secs);
+        Ok(())
+    }
+
+    /// Hold fulfiller rewards for a dispute window after confirmation; 0 pays at once (Admin only)
+    pub fn set_reward_holdback_secs(ctx: Context<AdminOnly>, secs: i64) -> Result<()> {
+        let state = &mut ctx.accounts.protocol_state;
+        let windows = TimeWindows { reward_holdback_secs: secs, ..state.time_windows() };
+        apply_time_windows(state, windows)?;
+        emit_config_updated("reward_holdback_secs", ctx.accounts.authority.key())?;
+        msg!("Fulfiller reward holdback set to {}s", secs);
+        Ok(())
+    }
+
+    /// Share of the burn kept when a redeemer cancels their own order, in bps (Admin only)
+    pub fn set_cancel_penalty_bps(ctx: Context<AdminOnly>, bps: u16) -> Result<()> {
+        require!(bps <= 10_000, WGBError::InvalidConfig);
+        ctx.accounts.protocol_state.cancel_penalty_bps = bps;
+        emit_config_updated("cancel_penalty_bps", ctx.accounts.authority.key())?;
+        msg!("Cancel penalty set to {} bps", bps);
+        Ok(())
+    }
+
+    /// Points deducted from a fulfiller whose claim expires (Admin only)
+    pub fn set_fulfiller_slash_points(ctx: Context<AdminOnly>, points: u64) -> Result<()> {
+        ctx.accounts.protocol_state.fulfiller_slash_points = points;
+        emit_config_updated("fulfiller_slash_points", ctx.accounts.authority.key())?;
+        msg!("Fulfiller slash set to {} points", points);
+        Ok(())
+    }
+
+    /// Max lamports a single buy may cost; 0 removes the cap (Admin only)
+    pub fn set_max_sol_per_buy(ctx: Context<AdminOnly>, max_lamports: u64) -> Result<()> {
+        ctx.accounts.protocol_state.max_sol_per_buy = max_lamports;
+        emit_config_updated("max_sol_per_buy", ctx.accounts.authority.key())?;
+        msg!("Max SOL per buy set to {} lamports", max_lamports);
+        Ok(())
+    }
+
+    /// Publish a new points airdrop root; zero disables claims (Admin only)
+    pub fn set_airdrop_root(ctx: Context<AdminOnly>, root: [u8; 32]) -> Result<()> {
+        ctx.accounts.protocol_state.airdrop_root = root;
+        emit_config_updated("airdrop_root", ctx.accounts.authority.key())?;
+        msg!("Airdrop root updated");
+        Ok(())
+    }
+
+    /// Stamp the reserved-block schema this build understands (Admin only)
+    /// Run after an upgrade that assigns new RESERVED_SLOTS, so readers can tell which slots are live.
+    pub fn upgrade_reserved_schema(ctx: Context<AdminOnly>) -> Result<()> {
+        let state = &mut ctx.accounts.protocol_state;
+        let current = state.reserved_schema_version();
+        require!(current <= RESERVED_SCHEMA_VERSION, WGBError::InvalidConfig);
+
+        write_reserved_field(state, ReservedSlot::SCHEMA_VERSION, &[RESERVED_SCHEMA_VERSION]);
+        emit_config_updated("upgrade_reserved_schema", ctx.accounts.authority.key())?;
+        msg!("Reserved schema upgraded {} -> {}", current, RESERVED_SCHEMA_VERSION);
+        Ok(())
+    }
+
+    pub fn set_wgb_price_admin(ctx: Context<MultisigAdminOnly>, price: u64) -> Result<()> {
+        require_multisig(&ctx.accounts.admin_multisig, &ctx.accounts.authority, ctx.remaining_accounts)?;
+        let state = &mut ctx.accounts.protocol_state;
+        state.wgb_price_lamports = price; // Unbounded override
+        apply_spread(state);
+
+        emit!(PriceUpdated {
+            buy_price_lamports: state.wgb_price_lamports,
+            sell_price_lamports: sell_price(state),
+            actor: ctx.accounts.authority.key(),
+            timestamp: Clock::get()?.unix_timestamp,
+        });
+        Ok(())
+    }
+
+    /// Recompute the cached coverage_bps from supply and backing (Public)
+    /// Only needed once on accounts that predate the field; every supply or reserve change keeps it current.
+    pub fn refresh_coverage(ctx: Context<RefreshCoverage>) -> Result<()> {
+        let state = &mut ctx.accounts.protocol_state;
+        update_coverage(state);
+        msg!("Coverage refreshed: {} bps", state.coverage_bps);
+        Ok(())
+    }
+
+    // ==================== VIEWS ====================
+
+    /// Emit an integrity snapshot comparing tracked supply against the live mint (Public, read-only)
+    pub fn emit_accounting(ctx: Context<GetCirculating>) -> Result<()> {
+        let state = &ctx.accounts.protocol_state;
+        let live_supply = ctx.accounts.wgb_mint.supply;
+
+        emit!(AccountingView {
+            live_supply,
+            total_supply: state.total_supply,
+            supply_mismatch: live_supply != state.total_supply,
+            total_burned: state.total_burned,
+            total_yield_distributed: state.total_yield_distributed,
+            proven_reserves: state.proven_reserves,
+            coverage_bps: state.coverage_bps,
+            insurance_balance: state.insurance_balance,
+            timestamp: Clock::get()?.unix_timestamp,
+        });
+        Ok(())
+    }
+
+    /// Emit the whole ProtocolState in one ProtocolSnapshot event (Public, read-only)
+    /// Lets an indexer bootstrap from a single log instead of replaying history; decode
+    /// `state` with the ProtocolState layout matching `schema_version`.
+    pub fn emit_full_snapshot(ctx: Context<GetCirculating>) -> Result<()> {
+        let clock = Clock::get()?;
+        emit!(ProtocolSnapshot {
+            schema_version: SNAPSHOT_SCHEMA_VERSION,
+            state: Box::new((*ctx.accounts.protocol_state).clone()),
+            live_supply: ctx.accounts.wgb_mint.supply,
+            slot: clock.slot,
+            timestamp: clock.unix_timestamp,
+        });
+        Ok(())
+    }
+
+    /// Emit every tunable ProtocolState parameter in one ConfigView event (Public, read-only)
+    /// Runtime counters stay in emit_accounting; new config fields belong in ConfigView too.
+    pub fn emit_config(ctx: Context<GetCirculating>) -> Result<()> {
+        let state = &ctx.accounts.protocol_state;
+        emit!(ConfigView {
+            authority: state.authority,
+            operator: state.operator,
+            backup_operator: state.backup_operator,
+            operator_rotation_cooldown_secs: state.operator_rotation_cooldown_secs,
+            guardian: state.guardian,
+            oracle: state.oracle,
+            treasury: state.treasury,
+            treasury_owner_mode: state.treasury_owner_mode,
+            sol_receiver: state.sol_receiver,
+            wgb_price_lamports: state.wgb_price_lamports,
+            wgb_sell_price_lamports: state.wgb_sell_price_lamports,
+            maintain_spread: state.maintain_spread,
+            spread_bps: state.spread_bps,
+            yield_apy_bps: state.yield_apy_bps,
+            yield_min_coverage_bps: state.yield_min_coverage_bps,
+            is_paused: state.is_paused,
+            pause_reason: state.pause_reason,
+            paused_until: state.paused_until,
+            round_up_cost: state.round_up_cost,
+            pending_expiry_secs: state.pending_expiry_secs,
+            reward_holdback_secs: state.reward_holdback_secs,
+            redemption_rounding: state.redemption_rounding,
+            redemption_bar_unit: state.redemption_bar_unit,
+            insurance_fee_bps: state.insurance_fee_bps,
+            cpi_gate: state.cpi_gate,
+            quote_lock_secs: state.quote_lock_secs,
+            buy_cooldown_secs: state.buy_cooldown_secs,
+            streak_bonus_points: state.streak_bonus_points,
+            claim_timeout_secs: state.claim_timeout_secs,
+            fulfiller_slash_points: state.fulfiller_slash_points,
+            cancel_penalty_bps: state.cancel_penalty_bps,
+            max_sol_per_buy: state.max_sol_per_buy,
+            airdrop_root: state.airdrop_root,
+            auto_confirm_secs: state.auto_confirm_secs,
+            max_mint_per_period: state.max_mint_per_period,
+            mint_period_secs: state.mint_period_secs,
+            min_referrer_tier: state.min_referrer_tier,
+            max_proof_age_secs: state.max_proof_age_secs,
+            min_mint_interval_secs: state.min_mint_interval_secs,
+            replenish_threshold: state.replenish_threshold,
+            replenish_amount: state.replenish_amount,
+            auto_replenish: state.auto_replenish,
+            grams_per_token_bps: state.grams_per_token_bps,
+            welcome_tier: state.welcome_tier,
+            event_verbosity: state.event_verbosity,
+            buyback_budget_lamports: state.buyback_budget_lamports,
+            max_active_claims_per_fulfiller: state.max_active_claims_per_fulfiller,
+            tier_priority: state.tier_priority,
+            tier_fulfiller_multiplier_bps: state.tier_fulfiller_multiplier_bps,
+            multi_fulfiller_threshold: state.multi_fulfiller_threshold,
+            min_distinct_fulfillers: state.min_distinct_fulfillers,
+            operator_rebate_lamports: state.operator_rebate_lamports,
+            operator_price_band_bps: state.operator_price_band_bps,
+            authority_price_band_bps: state.authority_price_band_bps,
+            max_user_points: state.max_user_points,
+            mint_delay_secs: state.mint_delay_secs,
+            points_transfers_enabled: state.points_transfers_enabled,
+            tier_basis: state.tier_basis,
+            min_challenge_bond: state.min_challenge_bond,
+            allow_reserve_shrink: state.allow_reserve_shrink,
+            buy_points_bps: state.buy_points_bps,
+            min_buy_amount: state.min_buy_amount,
+            require_kyc: state.require_kyc,
+            min_kyc_level: state.min_kyc_level,
+            reserve_unit: state.reserve_unit,
+            timestamp: Clock::get()?.unix_timestamp,
+        });
+        Ok(())
+    }
+
+    /// Preflight for issuance: emit whether the protocol PDA is the mint's authority (Public, read-only)
+    /// mint_wgb otherwise fails late with an opaque token-program error.
+    pub fn verify_mint_authority(ctx: Context<VerifyMintAuthority>) -> Result<()> {
+        let expected = ctx.accounts.protocol_state.key();
+        let mint_authority: Option<Pubkey> = ctx.accounts.wgb_mint.mint_authority.into();
+        let matches = mint_authority == Some(expected);
+
+        emit!(MintAuthorityVerified {
+            wgb_mint: ctx.accounts.wgb_mint.key(),
+            expected_authority: expected,
+            mint_authority,
+            matches,
+            timestamp: Clock::get()?.unix_timestamp,
+        });
+
+        msg!("Mint authority matches protocol PDA: {}", matches);
+        Ok(())
+    }
+
+    /// Emit how much mint_wgb would accept right now under every active guard (Public, read-only)
+    pub fn emit_mintable_headroom(ctx: Context<CanMint>) -> Result<()> {
+        let state = &ctx.accounts.protocol_state;
+        let now = Clock::get()?.unix_timestamp;
+
+        let reserve_headroom = backing_reserves(state).saturating_sub(state.total_supply);
+        let period_remaining = if state.max_mint_per_period > 0 {
+            let (_, minted) = current_mint_window(state, now);
+            state.max_mint_per_period.saturating_sub(minted)
+        } else {
+            u64::MAX
+        };
+        let proof_fresh = proof_is_fresh(state, now);
+
+        let cooling_down = state.min_mint_interval_secs > 0
+            && now - state.last_mint_at < state.min_mint_interval_secs;
+
+        let headroom = if state.is_paused || !proof_fresh || cooling_down
+            || in_challenge_window(state, now)
+            || state.proof_challenged
+        {
+            0
+        } else {
+            reserve_headroom.min(period_remaining)
+        };
+
+        emit!(MintableHeadroom {
+            headroom,
+            reserve_headroom,
+            period_remaining,
+            proof_fresh,
+            paused: state.is_paused,
+            timestamp: now,
+        });
+        Ok(())
+    }
+
+    /// Emit the gold weight backing `amount` W3B (Public, read-only)
+    /// Weights are in 1/10_000 g. Reserves are proven per serial and one serial backs one
+    /// token, so the same rate converts proven_reserves and the circulating supply.
+    pub fn emit_gold_equivalent(ctx: Context<GetGoldEquivalent>, amount: u64) -> Result<()> {
+        let state = &ctx.accounts.protocol_state;
+        require!(state.grams_per_token_bps > 0, WGBError::InvalidConfig);
+
+        emit!(GoldEquivalent {
+            amount,
+            grams_bps: to_grams_bps(amount, state.grams_per_token_bps)?,
+            reserves_grams_bps: to_grams_bps(state.proven_reserves, state.grams_per_token_bps)?,
+            supply_grams_bps: to_grams_bps(state.total_supply, state.grams_per_token_bps)?,
+            grams_per_token_bps: state.grams_per_token_bps,
+            timestamp: Clock::get()?.unix_timestamp,
+        });
+        Ok(())
+    }
+
+    /// Emit every UserProfile field in one ProfileView event (Public, read-only)
+    /// Lets SDKs read profiles from logs instead of hand-decoding the account layout.
+    pub fn emit_profile(ctx: Context<EmitProfile>) -> Result<()> {
+        let profile = &ctx.accounts.user_profile;
+        emit!(ProfileView {
+            user: profile.user,
+            points: profile.points,
+            tier: profile.tier,
+            total_volume: profile.total_volume,
+            total_redeemed: profile.total_redeemed,
+            total_fulfilled: profile.total_fulfilled,
+            fulfiller_rewards: profile.fulfiller_rewards,
+            last_award_nonce: profile.last_award_nonce,
+            failed_fulfillments: profile.failed_fulfillments,
+            compliance_locked: profile.compliance_locked,
+            active_claims: profile.active_claims,
+            points_dust: profile.points_dust,
+            streak_days: profile.streak_days,
+            last_streak_day: profile.last_streak_day,
+            last_buy_at: profile.last_buy_at,
+            welcome_tier_floor: profile.welcome_tier_floor,
+            timestamp: Clock::get()?.unix_timestamp,
+        });
+        Ok(())
+    }
+
+    /// Reset tracked total_supply to the live mint supply (Admin only) — fixes drift flagged by emit_accounting
+    pub fn reconcile_supply(ctx: Context<ReconcileSupply>) -> Result<()> {
+        let state = &mut ctx.accounts.protocol_state;
+        let previous = state.total_supply;
+        state.total_supply = ctx.accounts.wgb_mint.supply;
+        update_coverage(state);
+
+        emit!(SupplyReconciled {
+            previous_total_supply: previous,
+            new_total_supply: state.total_supply,
+            actor: ctx.accounts.authority.key(),
+            timestamp: Clock::get()?.unix_timestamp,
+        });
+
+        msg!("Supply reconciled: {} -> {}", previous, state.total_supply);
+        Ok(())
+    }
+
+    // ==================== POINTS CONFIG ====================
+
+    /// Create or update the PointsConfig PDA read by every point-awarding path (Admin only)
+    pub fn set_points_config(
+        ctx: Context<SetPointsConfig>,
+        buy_points_per_token: u64,
+        redemption_multiplier: u64,
+        fulfiller_reward: u64,
+        referral_bonus: u64,
+    ) -> Result<()> {
+        let config = &mut ctx.accounts.points_config;
+        config.buy_points_per_token = buy_points_per_token;
+        config.redemption_multiplier = redemption_multiplier;
+        config.fulfiller_reward = fulfiller_reward;
+        config.referral_bonus = referral_bonus;
+        config.bump = ctx.bumps.points_config;
+
+        emit!(PointsConfigUpdated {
+            buy_points_per_token,
+            redemption_multiplier,
+            fulfiller_reward,
+            referral_bonus,
+            actor: ctx.accounts.authority.key(),
+            timestamp: Clock::get()?.unix_timestamp,
+        });
+        Ok(())
+    }
+
+    // ==================== TIER CONFIG ====================
+
+    /// Create the TierConfig PDA (Admin only)
+    pub fn init_tier_config(ctx: Context<InitTierConfig>, tiers: Vec<TierDefinition>) -> Result<()> {
+        validate_tiers(&tiers)?;
+
+        let config = &mut ctx.accounts.tier_config;
+        config.tiers = tiers;
+        config.bump = ctx.bumps.tier_config;
+
+        emit!(TierConfigUpdated {
+            tiers: config.tiers.clone(),
+            actor: ctx.accounts.authority.key(),
+            timestamp: Clock::get()?.unix_timestamp,
+        });
+        Ok(())
+    }
+
+    /// Replace the tier table (Admin only)
+    pub fn update_tier_config(ctx: Context<UpdateTierConfig>, tiers: Vec<TierDefinition>) -> Result<()> {
+        validate_tiers(&tiers)?;
+
+        let config = &mut ctx.accounts.tier_config;
+        config.tiers = tiers;
+
+        emit!(TierConfigUpdated {
+            tiers: config.tiers.clone(),
+            actor: ctx.accounts.authority.key(),
+            timestamp: Clock::get()?.unix_timestamp,
+        });
+        Ok(())
+    }
+
+    // ==================== YIELD OPS ====================
+
+    /// Reserve coverage required to record yield, in bps of supply (Admin only)
+    /// 10_000 = 1:1, 12_000 = 120%; 0 restores the 1:1 default.
+    pub fn set_yield_min_coverage_bps(ctx: Context<AdminOnly>, bps: u16) -> Result<()> {
+        require!(bps == 0 || bps >= 10_000, WGBError::InvalidConfig);
+        ctx.accounts.protocol_state.yield_min_coverage_bps = bps;
+        emit_config_updated("yield_min_coverage_bps", ctx.accounts.authority.key())?;
+        msg!("Yield min coverage set to {} bps", bps);
+        Ok(())
+    }
+
+    /// Set yield APY rate in basis points (Admin only)
+    pub fn set_yield_rate(ctx: Context<AdminOnly>, apy_bps: u16) -> Result<()> {
+        ctx.accounts.protocol_state.yield_apy_bps = apy_bps;
+
+        emit!(YieldRateUpdated {
+            apy_bps,
+            actor: ctx.accounts.authority.key(),
+            timestamp: Clock::get()?.unix_timestamp,
+        });
+
+        msg!("Yield rate set to {} bps", apy_bps);
+        Ok(())
+    }
+
+    /// Record that yield was distributed off-chain (Operator)
+    pub fn record_yield_distribution(ctx: Context<OperatorOnly>, amount: u64) -> Result<()> {
+        note_backup_operator(&ctx.accounts.protocol_state, &ctx.accounts.operator.key());
+        require!(amount > 0, WGBError::ZeroAmount);
+        let state = &mut ctx.accounts.protocol_state;
+        let now = Clock::get()?.unix_timestamp;
+
+        // Same backing guarantee as mint_wgb: only against a current reserve proof
+        require!(state.last_proof_timestamp > 0, WGBError::NoProofYet);
+        require!(proof_is_fresh(state, now), WGBError::StaleMerkleRoot);
+
+        // Yield dilutes backing, so reserves must still cover the grown supply by the minimum ratio
+        let min_coverage_bps = match state.yield_min_coverage_bps {
+            0 => DEFAULT_YIELD_MIN_COVERAGE_BPS,
+            bps => bps,
+        };
+        let supply_after = state.total_supply.checked_add(amount).ok_or(WGBError::MathOverflow)? as u128;
+        require!(
+            backing_reserves(state) as u128 * 10_000 >= supply_after * min_coverage_bps as u128,
+            WGBError::InsufficientCoverageForYield
+        );
+
+        state.total_yield_distributed = state
+            .total_yield_distributed
+            .checked_add(amount)
+            .ok_or(WGBError::MathOverflow)?;
+        state.last_yield_distribution = now;
+
+        emit!(YieldDistributed {
+            amount,
+            new_total: state.total_yield_distributed,
+            actor: ctx.accounts.operator.key(),
+            timestamp: state.last_yield_distribution,
+        });
+
+        msg!("Yield distribution recorded: {} WGB", amount);
+        Ok(())
+    }
+
+    /// Update the Transfer Fee Extension config on the WGB mint (Admin only)
+    pub fn update_transfer_fee(
+        ctx: Context<UpdateTransferFee>,
+        new_fee_bps: u16,
+        new_max_fee: u64,
+    ) -> Result<()> {
+        let state = &ctx.accounts.protocol_state;
+        let seeds = &[b"protocol_state".as_ref(), &[state.bump]];
+        let signer = &[&seeds[..]];
+
+        let ix = set_transfer_fee(
+            &ctx.accounts.token_program.key(),
+            &ctx.accounts.wgb_mint.key(),
+            &state.key(),
+            &[],
+            new_fee_bps,
+            new_max_fee,
+        )?;
+
+        invoke_signed(
+            &ix,
+            &[
+                ctx.accounts.wgb_mint.to_account_info(),
+                ctx.accounts.protocol_state.to_account_info(),
+            ],
+            signer,
+        )?;
+
+        msg!("Transfer fee updated: {} bps, max {}", new_fee_bps, new_max_fee);
+        Ok(())
+    }
+}
+
+/// Authority check for instructions that take ProtocolState as raw bytes (authority = [8..40])
+fn check_raw_authority(protocol_state: &AccountInfo, authority: &Signer) -> Result<()> {
+    let data = protocol_state.try_borrow_data()?;
+    require!(data.len() >= 40, WGBError::Unauthorized);
+    let stored_authority = Pubkey::try_from(&data[8..40])
+        .map_err(|_| error!(WGBError::Unauthorized))?;
+    require!(stored_authority == authority.key(), WGBError::Unauthorized);
+    Ok(())
+}
+
+/// Top up rent from the authority and resize the ProtocolState account (new bytes are zeroed)
+fn grow_protocol_state(ctx: &Context<MigrateV2>, new_size: usize) -> Result<()> {
+    let protocol_state = &ctx.accounts.protocol_state;
+    let authority = &ctx.accounts.authority;
+
+    let rent = Rent::get()?;
+    let current_lamports = protocol_state.lamports();
+    let new_min_rent = rent.minimum_balance(new_size);
+
+    if current_lamports < new_min_rent {
+        let diff = new_min_rent - current_lamports;
+        let transfer_ix = anchor_lang::solana_program::system_instruction::transfer(
+            authority.key,
+            protocol_state.key,
+            diff,
+        );
+        invoke(
+            &transfer_ix,
+            &[
+                authority.to_account_info(),
+                protocol_state.to_account_info(),
+                ctx.accounts.system_program.to_account_info(),
+            ],
+        )?;
+    }
+
+    protocol_state.resize(new_size)?;
+    Ok(())
+}
+
+/// Read the TierConfig PDA, or None (DEFAULT_TIER_THRESHOLDS, no discounts) until it exists.
+/// Taken by address like load_points_config, so a buyer can't pick which table applies.
+fn load_tier_config(info: &AccountInfo) -> Result<Option<TierConfig>> {
+    if info.owner != &crate::ID || info.data_is_empty() {
+        return Ok(None);
+    }
+    let data = info.try_borrow_data()?;
+    TierConfig::try_deserialize(&mut &data[..]).map(Some)
+}
+
+/// Read the PointsConfig PDA, falling back to the built-in rates until it has been created.
+/// Taken by address (not as an Option) so callers can't omit it to dodge a configured rate.
+fn load_points_config(info: &AccountInfo) -> Result<PointsConfig> {
+    if info.owner != &crate::ID || info.data_is_empty() {
+        return Ok(PointsConfig::default());
+    }
+    let data = info.try_borrow_data()?;
+    PointsConfig::try_deserialize(&mut &data[..])
+}
+
+/// Check that the instruction right before the current one is an ed25519 program
+/// verification of exactly (pubkey, signature, message), with all data inline.
+fn verify_ed25519_ix(
+    instructions_sysvar: &AccountInfo,
+    pubkey: &Pubkey,
+    signature: &[u8; 64],
+    message: &[u8],
+) -> Result<()> {
+    let current = load_current_index_checked(instructions_sysvar)?;
+    require!(current > 0, WGBError::InvalidOracleSignature);
+    let ix = load_instruction_at_checked(current as usize - 1, instructions_sysvar)?;
+    require_keys_eq!(ix.program_id, ed25519_program::ID, WGBError::InvalidOracleSignature);
+
+    // Header: num_signatures (u8), padding (u8), then one 14-byte offsets block
+    let data = &ix.data;
+    require!(data.len() >= 16 && data[0] == 1, WGBError::InvalidOracleSignature);
+    let read_u16 = |at: usize| u16::from_le_bytes([data[at], data[at + 1]]);
+    let sig_offset = read_u16(2) as usize;
+    let pubkey_offset = read_u16(6) as usize;
+    let msg_offset = read_u16(10) as usize;
+    let msg_size = read_u16(12) as usize;
+
+    // Every offset must point into the ed25519 instruction itself (u16::MAX)
+    require!(
+        read_u16(4) == u16::MAX && read_u16(8) == u16::MAX && read_u16(14) == u16::MAX,
+        WGBError::InvalidOracleSignature
+    );
+
+    let slice = |offset: usize, len: usize| data.get(offset..offset + len);
+    require!(
+        slice(pubkey_offset, 32) == Some(pubkey.as_ref())
+            && slice(sig_offset, 64) == Some(signature.as_ref())
+            && msg_size == message.len()
+            && slice(msg_offset, msg_size) == Some(message),
+        WGBError::InvalidOracleSignature
+    );
+    Ok(())
+}
+
+/// Refund the operator's attestation cost from SOL held by the ProtocolState PDA.
+/// Anyone can top the pool up with a plain transfer; it never dips into the rent reserve,
+/// and an empty pool or an exhausted withdrawal throttle skips the rebate rather than
+/// failing the proof.
+fn pay_operator_rebate(
+    protocol_state: &mut Account<ProtocolState>,
+    operator: &AccountInfo,
+    withdrawal_throttle: &AccountInfo,
+) -> Result<u64> {
+    let rebate = protocol_state.operator_rebate_lamports;
+    if rebate == 0 {
+        return Ok(0);
+    }
+
+    let state_info = protocol_state.to_account_info();
+    let rent_floor = Rent::get()?.minimum_balance(state_info.data_len());
+    if state_info.lamports().saturating_sub(rent_floor) < rebate {
+        msg!("Operator rebate skipped: pool below {} lamports", rebate);
+        return Ok(0);
+    }
+    if !try_consume_withdrawal(withdrawal_throttle, protocol_state.is_paused, rebate, 0)? {
+        msg!("Operator rebate skipped: withdrawal throttle reached");
+        return Ok(0);
+    }
+
+    **state_info.try_borrow_mut_lamports()? -= rebate;
+    **operator.try_borrow_mut_lamports()? = operator
+        .lamports()
+        .checked_add(rebate)
+        .ok_or(WGBError::MathOverflow)?;
+
+    protocol_state.total_operator_rebates = protocol_state
+        .total_operator_rebates
+        .checked_add(rebate)
+        .ok_or(WGBError::MathOverflow)?;
+    Ok(rebate)
+}
+
+/// Add `by` to one RedemptionStats counter. A no-op until init_redemption_stats has run,
+/// so the counters cover every transition from that point on.
+fn bump_redemption_stat(
+    info: &AccountInfo,
+    counter: impl FnOnce(&mut RedemptionStats) -> &mut u64,
+    by: u64,
+) -> Result<()> {
+    if info.owner != &crate::ID || info.data_is_empty() {
+        return Ok(());
+    }
+    let mut data = info.try_borrow_mut_data()?;
+    let mut stats = RedemptionStats::try_deserialize(&mut &data[..])?;
+    let value = counter(&mut stats);
+    *value = value.checked_add(by).ok_or(WGBError::MathOverflow)?;
+    stats.try_serialize(&mut &mut data[..])
+}
+
+/// Append a proof to the ProofHistory ring buffer (no-op until init_proof_history)
+fn record_proof(info: &AccountInfo, proof_hash: [u8; 32], timestamp: i64) -> Result<()> {
+    if info.owner != &crate::ID || info.data_is_empty() {
+        return Ok(());
+    }
+    let mut data = info.try_borrow_mut_data()?;
+    let mut history = ProofHistory::try_deserialize(&mut &data[..])?;
+    let slot = (history.total_recorded % PROOF_HISTORY_LEN as u64) as usize;
+    history.entries[slot] = ProofRecord { proof_hash, timestamp };
+    history.total_recorded = history.total_recorded.checked_add(1).ok_or(WGBError::MathOverflow)?;
+    history.try_serialize(&mut &mut data[..])
+}
+
+/// Count an outflow against the WithdrawalThrottle at `info`, if one exists and is on.
+/// The window resets once window_secs have passed since it opened.
+fn consume_withdrawal(info: &AccountInfo, paused: bool, lamports: u64, tokens: u64) -> Result<()> {
+    require!(
+        try_consume_withdrawal(info, paused, lamports, tokens)?,
+        WGBError::WithdrawalThrottled
+    );
+    Ok(())
+}
+
+/// consume_withdrawal without the error: returns false, recording nothing, when the outflow
+/// would exceed the current window's cap.
+fn try_consume_withdrawal(info: &AccountInfo, paused: bool, lamports: u64, tokens: u64) -> Result<bool> {
+    if info.owner != &crate::ID || info.data_is_empty() {
+        return Ok(true);
+    }
+    let mut data = info.try_borrow_mut_data()?;
+    let mut throttle = WithdrawalThrottle::try_deserialize(&mut &data[..])?;
+    if throttle.window_secs == 0 {
+        return Ok(true);
+    }
+
+    let now = Clock::get()?.unix_timestamp;
+    if now.saturating_sub(throttle.window_start) >= throttle.window_secs {
+        throttle.window_start = now;
+        throttle.sol_out = 0;
+        throttle.tokens_out = 0;
+    }
+
+    throttle.sol_out = throttle.sol_out.checked_add(lamports).ok_or(WGBError::MathOverflow)?;
+    throttle.tokens_out = throttle.tokens_out.checked_add(tokens).ok_or(WGBError::MathOverflow)?;
+    if throttle.sol_out > throttle.limit_now(throttle.effective_sol_limit(), paused)
+        || throttle.tokens_out > throttle.limit_now(throttle.effective_token_limit(), paused)
+    {
+        return Ok(false);
+    }
+    throttle.try_serialize(&mut &mut data[..])?;
+    Ok(true)
+}
+
+/// Attribute an admin setting change to its signer; the new values are read back with emit_config
+fn emit_config_updated(setting: &str, actor: Pubkey) -> Result<()> {
+    emit!(ConfigUpdated {
+        setting: setting.to_string(),
+        actor,
+        timestamp: Clock::get()?.unix_timestamp,
+    });
+    Ok(())
+}
+
+fn emit_role_key_updated(role: &str, previous: Pubkey, new_key: Pubkey, actor: Pubkey) -> Result<()> {
+    emit!(RoleKeyUpdated {
+        role: role.to_string(),
+        previous,
+        new_key,
+        actor,
+        timestamp: Clock::get()?.unix_timestamp,
+    });
+    Ok(())
+}
+
+fn emit_throttle_updated(throttle: &WithdrawalThrottle, actor: Pubkey) -> Result<()> {
+    emit!(WithdrawalThrottleUpdated {
+        window_secs: throttle.window_secs,
+        sol_limit: throttle.sol_limit,
+        token_limit: throttle.token_limit,
+        paused_limit_bps: throttle.paused_limit_bps,
+        actor,
+        timestamp: Clock::get()?.unix_timestamp,
+    });
+    Ok(())
+}
+
+/// Apply `f` to the UserProfile at `info` if one exists there; returns whether it did
+fn with_profile_mut(
+    info: &AccountInfo,
+    f: impl FnOnce(&mut UserProfile) -> Result<()>,
+) -> Result<bool> {
+    if info.owner != &crate::ID || info.data_is_empty() {
+        return Ok(false);
+    }
+    let mut data = info.try_borrow_mut_data()?;
+    let mut profile = UserProfile::try_deserialize(&mut &data[..])?;
+    f(&mut profile)?;
+    profile.try_serialize(&mut &mut data[..])?;
+    Ok(true)
+}
+
+/// Count a new claim against the fulfiller's max_active_claims_per_fulfiller.
+/// With a limit set, fulfillers must have a profile so the count can be kept.
+fn take_claim_slot(state: &ProtocolState, fulfiller_profile: &AccountInfo) -> Result<()> {
+    let limit = state.max_active_claims_per_fulfiller;
+    let tracked = with_profile_mut(fulfiller_profile, |profile| {
+        require!(limit == 0 || profile.active_claims < limit, WGBError::TooManyActiveClaims);
+        profile.active_claims = profile.active_claims.checked_add(1).ok_or(WGBError::MathOverflow)?;
+        Ok(())
+    })?;
+    require!(tracked || limit == 0, WGBError::InvalidUserProfileAccount);
+    Ok(())
+}
+
+/// Penalise a failed fulfilment (expired claim, or a dispute resolved against the fulfiller):
+/// slash up to fulfiller_slash_points, count the failure and emit FulfillerSlashed.
+/// A no-op without a profile; callers pin the profile by seeds so it can't be omitted.
+fn slash_fulfiller(
+    state: &ProtocolState,
+    fulfiller_profile: &AccountInfo,
+    request_id: u64,
+    fulfiller: Pubkey,
+) -> Result<()> {
+    let mut slashed = 0;
+    let mut failed_fulfillments = 0;
+    let found = with_profile_mut(fulfiller_profile, |profile| {
+        slashed = profile.points.min(state.fulfiller_slash_points);
+        profile.points -= slashed;
+        profile.failed_fulfillments = profile
+            .failed_fulfillments
+            .checked_add(1)
+            .ok_or(WGBError::MathOverflow)?;
+        failed_fulfillments = profile.failed_fulfillments;
+        Ok(())
+    })?;
+    if found {
+        emit!(FulfillerSlashed {
+            request_id,
+            fulfiller,
+            points_slashed: slashed,
+            failed_fulfillments,
+            timestamp: Clock::get()?.unix_timestamp,
+        });
+    }
+    Ok(())
+}
+
+/// Free a claim slot. Saturating: claims taken before the counter existed were never counted.
+fn release_claim_slot(fulfiller_profile: &AccountInfo) -> Result<()> {
+    with_profile_mut(fulfiller_profile, |profile| {
+        profile.active_claims = profile.active_claims.saturating_sub(1);
+        Ok(())
+    })?;
+    Ok(())
+}
+
+/// Whether an event suppressed from `suppressed_at` upwards should still be emitted
+fn event_enabled(state: &ProtocolState, suppressed_at: u8) -> bool {
+    state.event_verbosity < suppressed_at
+}
+
+/// Token amount → gold weight in 1/10_000 g
+fn to_grams_bps(amount: u64, grams_per_token_bps: u64) -> Result<u64> {
+    amount
+        .checked_mul(grams_per_token_bps)
+        .ok_or_else(|| error!(WGBError::MathOverflow))
+}
+
+/// Whether the last reserve proof is recent enough to mint or distribute yield against
+fn proof_is_fresh(state: &ProtocolState, now: i64) -> bool {
+    let max_age = if state.max_proof_age_secs > 0 {
+        state.max_proof_age_secs
+    } else {
+        DEFAULT_MAX_PROOF_AGE_SECS
+    };
+    now - state.last_proof_timestamp < max_age
+}
+
+/// Audit trail for the backup operator key: emits BackupOperatorUsed when it is the signer
+fn note_backup_operator(state: &ProtocolState, signer: &Pubkey) {
+    if *signer != state.operator && state.is_operator(signer) {
+        emit!(BackupOperatorUsed {
+            backup_operator: *signer,
+            timestamp: Clock::get().map_or(0, |c| c.unix_timestamp),
+        });
+    }
+}
+
+/// Tokens the attested reserves can back: the serial count, or with RESERVE_UNIT_WEIGHT
+/// the attested weight converted at grams_per_token_bps
+fn backing_reserves(state: &ProtocolState) -> u64 {
+    if state.reserve_unit == RESERVE_UNIT_WEIGHT && state.grams_per_token_bps > 0 {
+        state.reserve_weight / state.grams_per_token_bps
+    } else {
+        state.proven_reserves
+    }
+}
+
+/// Round a burn to whole bars of redemption_bar_unit under redemption_rounding.
+/// Nearest rounds halves up, so it may burn slightly more than requested.
+fn round_redemption(state: &ProtocolState, amount: u64) -> Result<u64> {
+    let unit = state.redemption_bar_unit;
+    let remainder = if unit == 0 { 0 } else { amount % unit };
+    if remainder == 0 {
+        return Ok(amount);
+    }
+    let rounded = match state.redemption_rounding {
+        REDEMPTION_ROUND_NEAREST if remainder * 2 >= unit => {
+            amount.checked_add(unit - remainder).ok_or(WGBError::MathOverflow)?
+        }
+        REDEMPTION_ROUND_REJECT => return err!(WGBError::NonWholeRedemption),
+        _ => amount - remainder,
+    };
+    require!(rounded > 0, WGBError::NonWholeRedemption);
+    Ok(rounded)
+}
+
+/// Recompute the cached coverage_bps; call after any change to supply or backing.
+/// Saturates at u16::MAX, which is also what an empty supply reads as.
+fn update_coverage(state: &mut ProtocolState) {
+    state.coverage_bps = match state.total_supply {
+        0 => u16::MAX,
+        supply => (backing_reserves(state) as u128 * 10_000 / supply as u128).min(u16::MAX as u128) as u16,
+    };
+}
+
+/// Validate `windows` as a whole and write them to ProtocolState
+fn apply_time_windows(state: &mut ProtocolState, windows: TimeWindows) -> Result<()> {
+    let t = windows.redemption;
+    let in_range = |secs: i64| (0..=MAX_TIME_WINDOW_SECS).contains(&secs);
+    require!(
+        (0..=MAX_QUOTE_LOCK_SECS).contains(&windows.quote_lock_secs)
+            && in_range(t.pending_expiry_secs)
+            && in_range(t.claim_to_ship_secs)
+            && in_range(t.ship_to_confirm_secs)
+            && in_range(windows.reward_holdback_secs),
+        WGBError::InvalidConfig
+    );
+    require!(
+        t.ship_to_confirm_secs == 0
+            || windows.reward_holdback_secs == 0
+            || windows.reward_holdback_secs >= t.ship_to_confirm_secs,
+        WGBError::InvalidConfig
+    );
+
+    state.quote_lock_secs = windows.quote_lock_secs;
+    state.pending_expiry_secs = t.pending_expiry_secs;
+    state.claim_timeout_secs = t.claim_to_ship_secs;
+    state.auto_confirm_secs = t.ship_to_confirm_secs;
+    state.reward_holdback_secs = windows.reward_holdback_secs;
+    Ok(())
+}
+
+/// Owner the treasury token account must have under treasury_owner_mode
+fn expected_treasury_owner(state: &Account<ProtocolState>) -> Pubkey {
+    if state.treasury_owner_mode == TREASURY_OWNER_AUTHORITY {
+        state.authority
+    } else {
+        state.key()
+    }
+}
+
+/// Price trigger_buyback pays per token: the sell price, or the buy price while it is unset
+fn sell_price(state: &ProtocolState) -> u64 {
+    if state.wgb_sell_price_lamports > 0 {
+        state.wgb_sell_price_lamports
+    } else {
+        state.wgb_price_lamports
+    }
+}
+
+/// Take back the redemption points burn_wgb credited for `amount` (at the current
+/// multiplier, never below zero). Returns the points removed; 0 without a profile.
+fn clawback_burn_points(profile_info: &AccountInfo, points_config: &AccountInfo, amount: u64) -> Result<u64> {
+    let points_config = load_points_config(points_config)?;
+    let awarded = amount.checked_mul(points_config.redemption_multiplier).ok_or(WGBError::MathOverflow)?;
+    let mut clawed_back = 0;
+    with_profile_mut(profile_info, |profile| {
+        clawed_back = awarded.min(profile.points);
+        profile.points -= clawed_back;
+        Ok(())
+    })?;
+    Ok(clawed_back)
+}
+
+/// Lamports a redemption holds above rent exemption — its escrowed priority fee.
+/// RedemptionRequest has no spare bytes, so the fee lives in the account balance itself.
+fn priority_fee_of(info: &AccountInfo) -> Result<u64> {
+    Ok(info.lamports().saturating_sub(Rent::get()?.minimum_balance(info.data_len())))
+}
+
+/// Move `lamports` from the redeemer into the request's priority-fee escrow
+fn escrow_priority_fee<'info>(
+    system: &Program<'info, System>,
+    user: &Signer<'info>,
+    request: &Account<'info, RedemptionRequest>,
+    lamports: u64,
+) -> Result<()> {
+    system_program::transfer(
+        CpiContext::new(
+            system.to_account_info(),
+            system_program::Transfer {
+                from: user.to_account_info(),
+                to: request.to_account_info(),
+            },
+        ),
+        lamports,
+    )?;
+
+    let total = priority_fee_of(&request.to_account_info())?;
+    emit!(PriorityFeeSet {
+        request_id: request.request_id,
+        user: request.user,
+        added_lamports: lamports,
+        priority_fee_lamports: total,
+        timestamp: Clock::get()?.unix_timestamp,
+    });
+    msg!("Redemption #{} priority fee now {} lamports", request.request_id, total);
+    Ok(())
+}
+
+/// Pay out a request's escrowed priority fee to the fulfiller, or refund it to the redeemer
+fn settle_priority_fee(
+    request: &Account<RedemptionRequest>,
+    recipient: &AccountInfo,
+    refunded: bool,
+) -> Result<()> {
+    let info = request.to_account_info();
+    let fee = priority_fee_of(&info)?;
+    if fee == 0 {
+        return Ok(());
+    }
+    **info.try_borrow_mut_lamports()? -= fee;
+    **recipient.try_borrow_mut_lamports()? = recipient
+        .lamports()
+        .checked_add(fee)
+        .ok_or(WGBError::MathOverflow)?;
+
+    emit!(PriorityFeeSettled {
+        request_id: request.request_id,
+        recipient: recipient.key(),
+        lamports: fee,
+        refunded,
+        timestamp: Clock::get()?.unix_timestamp,
+    });
+    Ok(())
+}
+
+/// Re-mint burned W3B to the redeemer and reverse it in the supply counters.
+/// No reserve check: it undoes a burn, so the supply was already backed.
+fn remint_refund<'info>(
+    protocol_state: &mut Account<'info, ProtocolState>,
+    wgb_mint: &InterfaceAccount<'info, Mint>,
+    to: &InterfaceAccount<'info, TokenAccount>,
+    token_program: &Program<'info, Token2022>,
+    amount: u64,
+) -> Result<()> {
+    if amount > 0 {
+        let seeds = &[b"protocol_state".as_ref(), &[protocol_state.bump]];
+        let signer = &[&seeds[..]];
+        token_2022::mint_to_checked(
+            CpiContext::new_with_signer(
+                token_program.to_account_info(),
+                MintToChecked {
+                    mint: wgb_mint.to_account_info(),
+                    to: to.to_account_info(),
+                    authority: protocol_state.to_account_info(),
+                },
+                signer,
+            ),
+            amount,
+            WGB_DECIMALS,
+        )?;
+    }
+    protocol_state.total_supply = protocol_state.total_supply.checked_add(amount).ok_or(WGBError::MathOverflow)?;
+    protocol_state.total_burned = protocol_state.total_burned.checked_sub(amount).ok_or(WGBError::MathOverflow)?;
+    update_coverage(protocol_state);
+    Ok(())
+}
+
+/// Re-derive the sell price from the buy price when maintain_spread is on
+fn apply_spread(state: &mut ProtocolState) {
+    if state.maintain_spread {
+        state.wgb_sell_price_lamports =
+            (state.wgb_price_lamports as u128 * (10_000 - state.spread_bps as u128) / 10_000) as u64;
+    }
+}
+
+/// Whether the latest proof is still inside its mint_delay_secs challenge window
+fn in_challenge_window(state: &ProtocolState, now: i64) -> bool {
+    state.mint_delay_secs > 0 && now - state.last_proof_timestamp < state.mint_delay_secs
+}
+
+/// (start, minted) of the rate-limit window in effect at `now`, rolling over if it has elapsed
+fn current_mint_window(state: &ProtocolState, now: i64) -> (i64, u64) {
+    if now - state.mint_period_start >= state.mint_period_secs {
+        (now, 0)
+    } else {
+        (state.mint_period_start, state.minted_in_period)
+    }
+}
+
+/// Enforce the AdminMultisig threshold. Taken by address so it can't be skipped;
+/// an uninitialized multisig means single-key admin, as before.
+fn require_multisig(info: &AccountInfo, authority: &Signer, remaining: &[AccountInfo]) -> Result<()> {
+    if info.owner != &crate::ID || info.data_is_empty() {
+        return Ok(());
+    }
+    let data = info.try_borrow_data()?;
+    let multisig = AdminMultisig::try_deserialize(&mut &data[..])?;
+    require!(
+        multisig.is_satisfied(authority.key, remaining),
+        WGBError::MultisigThresholdNotMet
+    );
+    Ok(())
+}
+
+fn apply_pause(accounts: &mut AdminOnly, paused: bool, reason: PauseReason) -> Result<()> {
+    let reason = if paused { reason } else { PauseReason::Unspecified };
+    let state = &mut accounts.protocol_state;
+    state.is_paused = paused;
+    state.pause_reason = reason as u8;
+    state.paused_until = 0;
+
+    emit!(PauseStateChanged {
+        paused,
+        reason: state.pause_reason,
+        actor: accounts.authority.key(),
+        timestamp: Clock::get()?.unix_timestamp,
+    });
+    Ok(())
+}
+
+fn ensure_not_compliance_locked(user_profile: &Option<Account<UserProfile>>) -> Result<()> {
+    if let Some(profile) = user_profile {
+        require!(!profile.compliance_locked, WGBError::ComplianceLocked);
+    }
+    Ok(())
+}
+
+/// The buyer's KycAttestation must exist, be unexpired and reach `min_level`
+/// (its address is pinned by the `[b"kyc_attestation", buyer]` seeds constraint)
+fn ensure_kyc(info: &AccountInfo, min_level: u8) -> Result<()> {
+    require!(
+        info.owner == &crate::ID && !info.data_is_empty(),
+        WGBError::KycRequired
+    );
+    let attestation = KycAttestation::try_deserialize(&mut &info.try_borrow_data()?[..])?;
+    require!(attestation.level >= min_level, WGBError::KycRequired);
+    require!(
+        attestation.expires_at == 0 || attestation.expires_at > Clock::get()?.unix_timestamp,
+        WGBError::KycRequired
+    );
+    Ok(())
+}
+
+fn validate_optional_user_profile<'info>(
+    user_profile: &Option<Account<'info, UserProfile>>,
+    expected_user: &Pubkey,
+) -> Result<()> {
+    if let Some(profile) = user_profile {
+        let (expected_profile_pda, _) = Pubkey::find_program_address(
+            &[b"user_profile", expected_user.as_ref()],
+            &crate::ID,
+        );
+
+        require_keys_eq!(
+            profile.key(),
+            expected_profile_pda,
+            WGBError::InvalidUserProfileAccount
+        );
+        require_keys_eq!(
+            profile.user,
+            *expected_user,
+            WGBError::InvalidUserProfileAccount
+        );
+    }
+
+    Ok(())
+}
+
+/// Lamport cost of `amount` base units at `price_lamports` per whole token, less `discount_bps`.
+///
+/// Rounding is applied exactly once, on the final division by `10^decimals * 10_000`:
+/// - `round_up = false`: floor (fractions of a lamport favour the buyer)
+/// - `round_up = true`:  ceiling (fractions of a lamport favour the protocol)
+///
+/// The intermediate product is computed in u128, so it never rounds or clamps.
+/// Points and any refunds are derived from `amount`, not from this value, and stay floor-rounded.
+fn compute_cost(
+    price_lamports: u64,
+    amount: u64,
+    decimals: u8,
+    discount_bps: u16,
+    round_up: bool,
+) -> Result<u64> {
+    require!(discount_bps <= 10_000, WGBError::InvalidTierConfig);
+
+    let gross = (price_lamports as u128)
+        .checked_mul(amount as u128)
+        .and_then(|v| v.checked_mul(10_000 - discount_bps as u128))
+        .ok_or(WGBError::MathOverflow)?;
+    let unit = 10u128
+        .checked_pow(decimals as u32)
+        .and_then(|v| v.checked_mul(10_000))
+        .ok_or(WGBError::MathOverflow)?;
+
+    let cost = if round_up {
+        gross.div_ceil(unit)
+    } else {
+        gross / unit
+    };
+
+    u64::try_from(cost).map_err(|_| error!(WGBError::MathOverflow))
+}
+
+/// Add points to a profile, clipped so the balance never passes max_user_points (0 = unlimited).
+/// A balance already above a lowered cap is left alone. Returns the points actually granted.
+fn credit_points(profile: &mut UserProfile, points: u64, max_user_points: u64) -> Result<u64> {
+    let uncapped = profile.points.checked_add(points).ok_or(WGBError::MathOverflow)?;
+    let cap = if max_user_points == 0 { u64::MAX } else { max_user_points };
+    let new_points = uncapped.min(cap).max(profile.points);
+    let granted = new_points - profile.points;
+    profile.points = new_points;
+
+    if granted < points {
+        emit!(PointsCapReached {
+            user: profile.user,
+            requested: points,
+            granted,
+            cap,
+            timestamp: Clock::get()?.unix_timestamp,
+        });
+    }
+    Ok(granted)
+}
+
+/// Points for buying `amount`. With buy_points_bps set the rate can be fractional; the
+/// sub-point remainder is carried in profile.points_dust so small buys aren't rounded away.
+fn buy_points(
+    profile: &mut UserProfile,
+    amount: u64,
+    points_config: &PointsConfig,
+    buy_points_bps: u64,
+) -> Result<u64> {
+    if buy_points_bps == 0 {
+        return amount
+            .checked_mul(points_config.buy_points_per_token)
+            .ok_or(error!(WGBError::MathOverflow));
+    }
+    let scaled = amount as u128 * buy_points_bps as u128 + profile.points_dust as u128;
+    profile.points_dust = (scaled % 10_000) as u64;
+    u64::try_from(scaled / 10_000).map_err(|_| error!(WGBError::MathOverflow))
+}
+
+/// Fulfiller reward — PointsConfig.fulfiller_reward per order fulfilled + update stats.
+/// The claim slot is freed separately by release_claim_slot.
+fn pay_fulfiller_reward(profile: &mut UserProfile, reward: u64, max_user_points: u64) -> Result<()> {
+    credit_points(profile, reward, max_user_points)?;
+    profile.total_fulfilled = profile.total_fulfilled.checked_add(1).ok_or(WGBError::MathOverflow)?;
+    Ok(())
+}
+
+/// Scale the base fulfiller reward by the tier's multiplier; returns (reward, multiplier_bps)
+fn tiered_fulfiller_reward(state: &ProtocolState, tier: u8, base: u64) -> Result<(u64, u16)> {
+    let multiplier_bps = match state.tier_fulfiller_multiplier_bps[(tier as usize).min(MAX_TIERS - 1)] {
+        0 => 10_000,
+        bps => bps,
+    };
+    let reward = u64::try_from(base as u128 * multiplier_bps as u128 / 10_000)
+        .map_err(|_| error!(WGBError::MathOverflow))?;
+    Ok((reward, multiplier_bps))
+}
+
+/// Large orders (multi_fulfiller_threshold) must list min_distinct_fulfillers contributors
+/// in their FulfillerContributions account before confirming.
+fn ensure_multi_fulfilled(
+    state: &ProtocolState,
+    req: &RedemptionRequest,
+    contributions_info: &AccountInfo,
+) -> Result<()> {
+    if state.multi_fulfiller_threshold == 0 || req.amount < state.multi_fulfiller_threshold {
+        return Ok(());
+    }
+    let distinct = if contributions_info.owner == &crate::ID && !contributions_info.data_is_empty() {
+        let data = contributions_info.try_borrow_data()?;
+        FulfillerContributions::try_deserialize(&mut &data[..])?.contributors.len()
+    } else {
+        0
+    };
+    require!(
+        distinct >= state.min_distinct_fulfillers as usize,
+        WGBError::RequiresMultipleFulfillers
+    );
+    Ok(())
+}
+
+/// Move the profile's streak to `day` (unix time / 86_400): +1 on the day after the last buy,
+/// back to 1 after a gap. Returns false for a repeat buy on the same day.
+fn advance_streak(profile: &mut UserProfile, day: u32) -> bool {
+    if profile.last_streak_day == day && profile.streak_days > 0 {
+        return false;
+    }
+    profile.streak_days = if profile.streak_days > 0 && profile.last_streak_day.checked_add(1) == Some(day) {
+        profile.streak_days.saturating_add(1)
+    } else {
+        1
+    };
+    profile.last_streak_day = day;
+    true
+}
+
+/// Bonus points for reaching a streak of `streak_days` (all zero = no bonus)
+fn streak_bonus(state: &ProtocolState, streak_days: u16) -> u64 {
+    let index = (streak_days as usize).clamp(1, STREAK_BONUS_DAYS) - 1;
+    state.streak_bonus_points[index] as u64
+}
+
+/// Settle a confirmed order's fulfiller reward: paid now, or with reward_holdback_secs set,
+/// parked in full in the order's HeldReward until release_fulfiller_rewards. The request's
+/// held_reward keeps a saturated copy that marks the order as held. Stats update either way.
+fn settle_fulfiller_reward(
+    state: &mut ProtocolState,
+    req: &mut RedemptionRequest,
+    held: &mut HeldReward,
+    profile: &mut UserProfile,
+    reward: u64,
+) -> Result<()> {
+    if state.reward_holdback_secs == 0 || reward == 0 {
+        return pay_fulfiller_reward(profile, reward, state.max_user_points);
+    }
+    held.amount = reward;
+    req.held_reward = u16::try_from(reward).unwrap_or(u16::MAX);
+    state.pending_fulfiller_rewards = state
+        .pending_fulfiller_rewards
+        .checked_add(reward)
+        .ok_or(WGBError::MathOverflow)?;
+    pay_fulfiller_reward(profile, 0, state.max_user_points)
+}
+
+/// Clear a confirmed order's held reward and return it: the full amount from its HeldReward,
+/// or the request's own held_reward for orders held before HeldReward existed.
+fn take_held_reward(req: &mut RedemptionRequest, held_info: &AccountInfo) -> Result<u64> {
+    let mut amount = req.held_reward as u64;
+    if held_info.owner == &crate::ID && !held_info.data_is_empty() {
+        let mut data = held_info.try_borrow_mut_data()?;
+        let mut held = HeldReward::try_deserialize(&mut &data[..])?;
+        amount = held.amount;
+        held.amount = 0;
+        held.try_serialize(&mut &mut data[..])?;
+    }
+    req.held_reward = 0;
+    Ok(amount)
+}
+
+/// Chain a newly set reserve root into the lineage: lineage = sha256(lineage || root)
+fn extend_lineage(state: &mut ProtocolState, root: [u8; 32]) {
+    state.lineage_hash = hashv(&[&state.lineage_hash, &root]).to_bytes();
+}
+
+/// Sorted-pair SHA256 Merkle verification (matches merkletreejs `sortPairs: true` in services/api)
+fn verify_merkle_proof(leaf: [u8; 32], proof: &[[u8; 32]], root: [u8; 32]) -> bool {
+    let computed = proof.iter().fold(leaf, |node, sibling| {
+        if node <= *sibling {
+            hashv(&[&node, sibling]).to_bytes()
+        } else {
+            hashv(&[sibling, &node]).to_bytes()
+        }
+    });
+    computed == root
+}
+
+/// Prove a raw serial is in the reserve tree and that `serial_leaf` is its leaf.
+/// The leaf is derived here as sha256(serial) rather than trusted from the caller, the serial
+/// must be shorter than an internal node's 64-byte preimage, and the path must be non-empty,
+/// so neither the root nor any internal node can be passed off as a redeemable leaf.
+fn verify_serial_proof(serial: &str, serial_leaf: [u8; 32], proof: &[[u8; 32]], root: [u8; 32]) -> Result<()> {
+    require!(
+        !serial.is_empty() && serial.len() <= MAX_SERIAL_LEN,
+        WGBError::InvalidSerial
+    );
+    require!(hashv(&[serial.as_bytes()]).to_bytes() == serial_leaf, WGBError::InvalidSerial);
+    require!(
+        !proof.is_empty() && verify_merkle_proof(serial_leaf, proof, root),
+        WGBError::InvalidMerkleProof
+    );
+    Ok(())
+}
+
+/// Tier index for a points (or volume) figure: the highest tier whose threshold is exceeded.
+/// Tier 0 (Bronze) always applies. Falls back to DEFAULT_TIER_THRESHOLDS without a TierConfig.
+fn compute_tier(metric: u64, tier_config: Option<&TierConfig>) -> u8 {
+    let exceeds = |i: usize, threshold: u64| i == 0 || metric > threshold;
+
+    let tier = match tier_config {
+        Some(config) => config
+            .tiers
+            .iter()
+            .enumerate()
+            .filter(|(i, t)| exceeds(*i, t.threshold))
+            .count(),
+        None => DEFAULT_TIER_THRESHOLDS
+            .iter()
+            .enumerate()
+            .filter(|(i, t)| exceeds(*i, **t))
+            .count(),
+    };
+
+    tier.saturating_sub(1) as u8
+}
+
+/// Tier tables must start at 0 (Bronze), ascend strictly, and fit in MAX_TIERS
+fn validate_tiers(tiers: &[TierDefinition]) -> Result<()> {
+    require!(
+        !tiers.is_empty() && tiers.len() <= MAX_TIERS,
+        WGBError::InvalidTierConfig
+    );
+    require!(tiers[0].threshold == 0, WGBError::InvalidTierConfig);
+    require!(
+        tiers.windows(2).all(|w| w[0].threshold < w[1].threshold),
+        WGBError::InvalidTierConfig
+    );
+    require!(
+        tiers.iter().all(|t| t.discount_bps <= 10_000),
+        WGBError::InvalidTierConfig
+    );
+    Ok(())
+}
+
+/// Shared burn path for burn_wgb and burn_with_serial
+fn process_burn(
+    accounts: &mut BurnWGB,
+    request_bump: u8,
+    amount: u64,
+    request_id: u64,
+    region_code: u16,
+    priority_fee_lamports: u64,
+) -> Result<()> {
+    let state = &mut accounts.protocol_state;
+    require!(!state.is_paused, WGBError::ProtocolPaused);
+    require!(amount > 0, WGBError::ZeroAmount);
+    let requested_amount = amount;
+    let amount = round_redemption(state, amount)?;
+
+    validate_optional_user_profile(&accounts.user_profile, &accounts.user.key())?;
+    ensure_not_compliance_locked(&accounts.user_profile)?;
+    let block = &accounts.redemption_block;
+    require!(
+        block.owner != &crate::ID || block.data_is_empty(),
+        WGBError::RedemptionsBlocked
+    );
+
+    // 1. Burn Tokens
+    log_compute_units!("burn_wgb before burn");
+    token_2022::burn(
+        CpiContext::new(
+            accounts.token_program.to_account_info(),
+            Burn {
+                mint: accounts.wgb_mint.to_account_info(),
+                from: accounts.user_token_account.to_account_info(),
+                authority: accounts.user.to_account_info(),
+            },
+        ),
+        amount,
+    )?;
+    log_compute_units!("burn_wgb after burn");
+
+    // 2. Update Protocol Stats
+    state.total_supply = state.total_supply.checked_sub(amount).ok_or(WGBError::MathOverflow)?;
+    state.total_burned = state.total_burned.checked_add(amount).ok_or(WGBError::MathOverflow)?;
+    update_coverage(state);
+    let new_total_supply = state.total_supply;
+    let new_total_burned = state.total_burned;
+
+    // Fulfillment priority from the tier held at burn time (no profile = Bronze)
+    let tier = accounts.user_profile.as_ref().map_or(0, |p| p.tier) as usize;
+    let priority = state.tier_priority[tier.min(MAX_TIERS - 1)];
+
+    // 3. Create Redemption Request
+    let req = &mut accounts.redemption_request;
+    req.user = accounts.user.key();
+    req.request_id = request_id;
+    req.amount = amount;
+    req.status = 0; // Pending
+    req.created_at = Clock::get()?.unix_timestamp;
+    req.bump = request_bump;
+    req.priority = priority;
+    req.region_code = region_code;
+    bump_redemption_stat(&accounts.redemption_stats, |s| &mut s.created, 1)?;
+
+    // 4. Points & Profile
+    if let Some(profile) = &mut accounts.user_profile {
+        // Redemption multiplier (double points by default)
+        let points_config = load_points_config(&accounts.points_config)?;
+        let points = amount.checked_mul(points_config.redemption_multiplier).ok_or(WGBError::MathOverflow)?;
+        credit_points(profile, points, accounts.protocol_state.max_user_points)?;
+        profile.total_redeemed = profile.total_redeemed.checked_add(amount).ok_or(WGBError::MathOverflow)?;
+    }
+
+    if event_enabled(&accounts.protocol_state, EVENTS_MINIMAL) {
+        emit!(TokensBurned {
+            user: accounts.user.key(),
+            amount,
+            request_id,
+            timestamp: req.created_at,
+            new_total_supply,
+            new_total_burned,
+            priority,
+            requested_amount,
+            region_code,
+            decimals: accounts.wgb_mint.decimals,
+        });
+    }
+
+    // 5. Optional priority fee, escrowed in the request for whoever fulfils it
+    if priority_fee_lamports > 0 {
+        escrow_priority_fee(
+            &accounts.system_program,
+            &accounts.user,
+            &accounts.redemption_request,
+            priority_fee_lamports,
+        )?;
+    }
+
+    msg!("Redemption Request #{} created for {} WGB", request_id, amount);
+    Ok(())
+}
+
+/// Shared mint_wgb logic (also run by mint_and_sell)
+fn process_mint(accounts: &mut MintWGB, amount: u64) -> Result<()> {
+    note_backup_operator(&accounts.protocol_state, &accounts.operator.key());
+    let state = &accounts.protocol_state;
+    require!(!state.is_paused, WGBError::ProtocolPaused);
+    require!(amount > 0, WGBError::ZeroAmount);
+    
+    // 1. Staleness Check — a fresh deployment has no proof at all, which gets its own error
+    let now = Clock::get()?.unix_timestamp;
+    require!(state.last_proof_timestamp > 0, WGBError::NoProofYet);
+    require!(proof_is_fresh(state, now), WGBError::StaleMerkleRoot);
+
+    // 1a. A new proof must sit for mint_delay_secs so it can be challenged first (0 disables it),
+    // and nothing is minted while a challenge against it is open
+    require!(!in_challenge_window(state, now), WGBError::ProofChallengeWindow);
+    require!(!state.proof_challenged, WGBError::ProofUnderChallenge);
+
+    // 1b. Cool-down between mints (0 disables it)
+    require!(
+        state.min_mint_interval_secs == 0 || now - state.last_mint_at >= state.min_mint_interval_secs,
+        WGBError::MintTooSoon
+    );
+
+    // 2. Reserve Check
+    let new_supply = state.total_supply.checked_add(amount).ok_or(WGBError::MathOverflow)?;
+    require!(new_supply <= backing_reserves(state), WGBError::InsufficientReserves);
+
+    // 2b. Rate Limit (max_mint_per_period == 0 disables it)
+    let (period_start, minted_in_period) = if state.max_mint_per_period > 0 {
+        let (start, minted) = current_mint_window(state, now);
+        let minted = minted.checked_add(amount).ok_or(WGBError::MathOverflow)?;
+        require!(minted <= state.max_mint_per_period, WGBError::MintRateLimitExceeded);
+        (start, minted)
+    } else {
+        (state.mint_period_start, state.minted_in_period)
+    };
+
+    // 3. CPI Mint (checked against WGB_DECIMALS, so amounts can't be minted into a mint
+    //    whose decimals differ from the ones caps and pricing assume)
+    let seeds = &[b"protocol_state".as_ref(), &[state.bump]];
+    let signer = &[&seeds[..]];
+
+    log_compute_units!("mint_wgb before mint");
+    token_2022::mint_to_checked(
+        CpiContext::new_with_signer(
+            accounts.token_program.to_account_info(),
+            MintToChecked {
+                mint: accounts.wgb_mint.to_account_info(),
+                to: accounts.treasury.to_account_info(),
+                authority: accounts.protocol_state.to_account_info(),
+            },
+            signer,
+        ),
+        amount,
+        WGB_DECIMALS,
+    )?;
+    log_compute_units!("mint_wgb after mint");
+
+    // 4. Update State
+    let state_mut = &mut accounts.protocol_state;
+    state_mut.total_supply = new_supply;
+    update_coverage(state_mut);
+    state_mut.mint_period_start = period_start;
+    state_mut.minted_in_period = minted_in_period;
+    state_mut.last_mint_at = now;
+    
+    emit!(TokensMinted {
+        amount,
+        new_total_supply: new_supply,
+        actor: accounts.operator.key(),
+        timestamp: now,
+        decimals: accounts.wgb_mint.decimals,
+    });
+    Ok(())
+}
+
+/// Shared buy path for buy_wgb, onboard_and_buy and mint_and_sell
+/// `split_receivers` are the SolSplit receivers (remaining_accounts); unused without a split.
+fn process_buy<'info>(
+    accounts: &mut BuyWGB<'info>,
+    amount: u64,
+    split_receivers: &[AccountInfo<'info>],
+) -> Result<()> {
+    let state = &accounts.protocol_state;
+    require!(!state.is_paused, WGBError::ProtocolPaused);
+    require!(state.wgb_price_lamports > 0, WGBError::PriceNotSet);
+
+    // Rate limiting: max 1000 W3B per transaction, and no dust buys below min_buy_amount
+    require!(amount > 0, WGBError::ZeroAmount);
+    require!(amount >= state.min_buy_amount, WGBError::BelowMinBuy);
+    require!(amount <= MAX_TOKENS_PER_BUY, WGBError::ExceedsTransactionCap);
+
+    ensure_cpi_allowed(state, &accounts.instructions_sysvar, &accounts.cpi_allowlist)?;
+    validate_optional_user_profile(&accounts.user_profile, &accounts.buyer.key())?;
+    ensure_not_compliance_locked(&accounts.user_profile)?;
+    if state.require_kyc {
+        ensure_kyc(&accounts.kyc_attestation, state.min_kyc_level)?;
+    }
+    if state.buy_cooldown_secs > 0 {
+        let profile = accounts.user_profile.as_ref().ok_or(WGBError::ProfileRequired)?;
+        require!(
+            Clock::get()?.unix_timestamp - profile.last_buy_at >= state.buy_cooldown_secs,
+            WGBError::BuyCooldown
+        );
+    }
+
+    // Tier discount is based on the tier held *before* this purchase
+    let tier_config = load_tier_config(&accounts.tier_config)?;
+    let discount_bps = match (&accounts.user_profile, &tier_config) {
+        (Some(profile), Some(config)) => config.discount_for(profile.tier),
+        _ => 0,
+    };
+
+    // A quote lock fixes the price for exactly the locked amount until it expires
+    let price = match &accounts.quote_lock {
+        Some(lock) => {
+            require!(Clock::get()?.unix_timestamp <= lock.expires_at, WGBError::QuoteExpired);
+            require!(amount == lock.amount, WGBError::QuoteMismatch);
+            lock.price_lamports
+        }
+        None => state.wgb_price_lamports,
+    };
+
+    let cost = compute_cost(
+        price,
+        amount,
+        accounts.wgb_mint.decimals,
+        discount_bps,
+        state.round_up_cost,
+    )?;
+
+    // Absolute lamport ceiling per buy (0 = uncapped)
+    require!(
+        state.max_sol_per_buy == 0 || cost <= state.max_sol_per_buy,
+        WGBError::ExceedsSolCap
+    );
+
+    // 1. Treasury sufficiency. Under the Transfer Fee Extension the fee is withheld on the
+    // receiving side, so the gross debited from the treasury is exactly `amount`.
+    require!(accounts.treasury.amount >= amount, WGBError::InsufficientTreasury);
+
+    // 2. Transfer WGB first (must use transfer_checked for Token-2022 Transfer Fee Extension)
+    // so a token-side failure aborts before any SOL moves. A PDA-owned treasury is signed
+    // for with the protocol seeds; an authority-owned one needs the owner's signature.
+    let seeds = &[b"protocol_state".as_ref(), &[state.bump]];
+    let signer = &[&seeds[..]];
+    let pda_owned = state.treasury_owner_mode == TREASURY_OWNER_PDA;
+    let treasury_authority = if pda_owned {
+        accounts.protocol_state.to_account_info()
+    } else {
+        let owner = accounts.treasury_signer.as_ref().ok_or(WGBError::TreasurySignerRequired)?;
+        require_keys_eq!(owner.key(), accounts.treasury.owner, WGBError::TreasurySignerRequired);
+        owner.to_account_info()
+    };
+
+    log_compute_units!("buy_wgb before transfers");
+    let transfer = CpiContext::new(
+        accounts.token_program.to_account_info(),
+        TransferChecked {
+            from: accounts.treasury.to_account_info(),
+            to: accounts.buyer_token_account.to_account_info(),
+            mint: accounts.wgb_mint.to_account_info(),
+            authority: treasury_authority,
+        },
+    );
+    token_2022::transfer_checked(
+        if pda_owned { transfer.with_signer(signer) } else { transfer },
+        amount,
+        accounts.wgb_mint.decimals,
+    )?;
+
+    // 3. Transfer SOL (exactly `cost` — any overpayment is returned before we finish).
+    // insurance_fee_bps of it goes to the insurance fund, the rest to the receiver(s).
+    // A cut the fund PDA couldn't hold rent-exempt would fail the transfer; the receiver keeps it.
+    let mut insurance_cut = (cost as u128 * accounts.protocol_state.insurance_fee_bps as u128 / 10_000) as u64;
+    let fund_min_balance = Rent::get()?.minimum_balance(0);
+    if accounts.insurance_fund.lamports().saturating_add(insurance_cut) < fund_min_balance {
+        insurance_cut = 0;
+    }
+    let cost = cost - insurance_cut;
+    let buyer_lamports_before = accounts.buyer.lamports();
+    let split = load_sol_split(&accounts.sol_split)?;
+    let lamports_paid = if split.is_empty() {
+        system_program::transfer(
+            CpiContext::new(
+                accounts.system_program.to_account_info(),
+                system_program::Transfer {
+                    from: accounts.buyer.to_account_info(),
+                    to: accounts.sol_receiver.to_account_info(),
+                },
+            ),
+            cost,
+        )?;
+        verify_exact_payment(
+            &accounts.buyer,
+            &accounts.sol_receiver,
+            buyer_lamports_before,
+            cost,
+        )?
+    } else {
+        pay_sol_split(accounts, &split, split_receivers, buyer_lamports_before, cost)?
+    };
+    if insurance_cut > 0 {
+        system_program::transfer(
+            CpiContext::new(
+                accounts.system_program.to_account_info(),
+                system_program::Transfer {
+                    from: accounts.buyer.to_account_info(),
+                    to: accounts.insurance_fund.to_account_info(),
+                },
+            ),
+            insurance_cut,
+        )?;
+        let state = &mut accounts.protocol_state;
+        state.insurance_balance = state.insurance_balance.checked_add(insurance_cut).ok_or(WGBError::MathOverflow)?;
+    }
+    let lamports_paid = lamports_paid + insurance_cut;
+    if let Some(lock) = &accounts.quote_lock {
+        lock.close(accounts.buyer.to_account_info())?;
+    }
+    log_compute_units!("buy_wgb after transfers");
+
+    // 4. Award Points (Check if profile exists)
+    if let Some(profile) = &mut accounts.user_profile {
+        let points_config = load_points_config(&accounts.points_config)?;
+        let points = buy_points(profile, amount, &points_config, accounts.protocol_state.buy_points_bps)?;
+        let first_purchase = profile.total_volume == 0 && amount > 0;
+        credit_points(profile, points, accounts.protocol_state.max_user_points)?;
+        profile.total_volume = profile.total_volume.checked_add(amount).ok_or(WGBError::MathOverflow)?;
+        profile.last_buy_at = Clock::get()?.unix_timestamp;
+
+        // Daily purchase streak: the first buy of each day earns that streak length's bonus
+        let day = (Clock::get()?.unix_timestamp / 86_400) as u32;
+        if advance_streak(profile, day) {
+            let bonus_points = streak_bonus(&accounts.protocol_state, profile.streak_days);
+            let granted = credit_points(profile, bonus_points, accounts.protocol_state.max_user_points)?;
+            if event_enabled(&accounts.protocol_state, EVENTS_REDUCED) {
+                emit!(StreakUpdated {
+                    user: profile.user,
+                    streak_days: profile.streak_days,
+                    bonus_points: granted,
+                    timestamp: Clock::get()?.unix_timestamp,
+                });
+            }
+        }
+
+        // Tier follows points or lifetime volume per tier_basis. A first purchase that would
+        // land on Bronze is granted welcome_tier instead (0 = no grant); the granted tier is
+        // recorded on the profile and stays a floor on later recomputes, so the boost is
+        // never lost and never handed out again after the first buy.
+        let basis = accounts.protocol_state.tier_basis;
+        let metric = if basis == TIER_BASIS_VOLUME { profile.total_volume } else { profile.points };
+        let earned_tier = compute_tier(metric, tier_config.as_ref());
+        let welcome_tier = accounts.protocol_state.welcome_tier;
+        let welcome_granted = first_purchase && earned_tier == 0 && welcome_tier > 0;
+        if welcome_granted {
+            profile.welcome_tier_floor = welcome_tier;
+        }
+        let old_tier = profile.tier;
+        profile.tier = earned_tier.max(profile.welcome_tier_floor);
+
+        if profile.tier != old_tier && event_enabled(&accounts.protocol_state, EVENTS_REDUCED) {
+            emit!(TierChanged {
+                user: profile.user,
+                old_tier,
+                new_tier: profile.tier,
+                basis,
+                timestamp: Clock::get()?.unix_timestamp,
+            });
+        }
+
+        if welcome_granted && event_enabled(&accounts.protocol_state, EVENTS_REDUCED) {
+            emit!(WelcomeTierGranted {
+                user: profile.user,
+                tier: welcome_tier,
+                timestamp: Clock::get()?.unix_timestamp,
+            });
+        }
+    }
+
+    let now = Clock::get()?.unix_timestamp;
+
+    // 5. Low-treasury signal for the operator bot (replenish_threshold == 0 disables it)
+    let state = &accounts.protocol_state;
+    if state.replenish_threshold > 0 {
+        accounts.treasury.reload()?;
+        let treasury_balance = accounts.treasury.amount;
+        if treasury_balance < state.replenish_threshold {
+            let reserve_headroom = backing_reserves(state).saturating_sub(state.total_supply);
+            emit!(ReplenishNeeded {
+                treasury_balance,
+                threshold: state.replenish_threshold,
+                suggested_mint: state.replenish_amount.min(reserve_headroom),
+                auto_replenish: state.auto_replenish,
+                timestamp: now,
+            });
+        }
+    }
+
+    if event_enabled(state, EVENTS_REDUCED) {
+        emit!(TokensPurchased {
+            buyer: accounts.buyer.key(),
+            amount,
+            lamports_paid,
+            timestamp: now,
+            decimals: accounts.wgb_mint.decimals,
+        });
+    }
+
+    Ok(())
+}
+
+/// Confirms the buyer was debited exactly `cost` lamports by the SOL leg of a buy.
+/// The SOL receiver is not program-owned, so an overpayment cannot be pulled back
+/// afterwards — instead we refuse to complete a buy whose debit differs from the quote.
+fn verify_exact_payment<'info>(
+    buyer: &Signer<'info>,
+    sol_receiver: &AccountInfo<'info>,
+    buyer_lamports_before: u64,
+    cost: u64,
+) -> Result<u64> {
+    // A self-paying buyer (buyer == sol_receiver) nets zero by construction
+    if buyer.key() == sol_receiver.key() {
+        return Ok(cost);
+    }
+
+    let paid = buyer_lamports_before
+        .checked_sub(buyer.lamports())
+        .ok_or(WGBError::MathOverflow)?;
+    require!(paid == cost, WGBError::PaymentMismatch);
+
+    Ok(paid)
+}
+
+/// Enforce cpi_gate on a buy. Top-level calls always pass. Introspection only shows the
+/// top-level instruction, so under CPI_GATE_ALLOWLIST a listed program must be the direct
+/// caller (stack height 2); deeper nesting is refused.
+fn ensure_cpi_allowed(
+    state: &ProtocolState,
+    instructions_sysvar: &AccountInfo,
+    allowlist: &AccountInfo,
+) -> Result<()> {
+    let height = get_stack_height();
+    if state.cpi_gate == CPI_GATE_OPEN || height == TRANSACTION_LEVEL_STACK_HEIGHT {
+        return Ok(());
+    }
+    require!(
+        state.cpi_gate == CPI_GATE_ALLOWLIST && height == TRANSACTION_LEVEL_STACK_HEIGHT + 1,
+        WGBError::CallerNotAllowed
+    );
+    require!(
+        allowlist.owner == &crate::ID && !allowlist.data_is_empty(),
+        WGBError::CallerNotAllowed
+    );
+
+    let current = load_current_index_checked(instructions_sysvar)?;
+    let caller = load_instruction_at_checked(current as usize, instructions_sysvar)?.program_id;
+    let data = allowlist.try_borrow_data()?;
+    let listed = CpiAllowlist::try_deserialize(&mut &data[..])?.programs.contains(&caller);
+    require!(listed, WGBError::CallerNotAllowed);
+    Ok(())
+}
+
+/// Configured SolSplit shares; empty when the PDA does not exist or was cleared
+fn load_sol_split(info: &AccountInfo) -> Result<Vec<SolShare>> {
+    if info.owner != &crate::ID || info.data_is_empty() {
+        return Ok(Vec::new());
+    }
+    let data = info.try_borrow_data()?;
+    Ok(SolSplit::try_deserialize(&mut &data[..])?.receivers)
+}
+
+/// Pay `cost` across the split receivers by bps; the last receiver takes the rounding dust.
+/// Like verify_exact_payment, the buyer must be debited exactly the sum of the other shares.
+fn pay_sol_split<'info>(
+    accounts: &BuyWGB<'info>,
+    split: &[SolShare],
+    receivers: &[AccountInfo<'info>],
+    buyer_lamports_before: u64,
+    cost: u64,
+) -> Result<u64> {
+    require!(receivers.len() == split.len(), WGBError::InvalidSplit);
+
+    let mut remaining = cost;
+    let mut self_paid: u64 = 0;
+    for (i, (share, receiver)) in split.iter().zip(receivers).enumerate() {
+        require_keys_eq!(receiver.key(), share.receiver, WGBError::InvalidSplit);
+        require!(receiver.is_writable, WGBError::InvalidSplit);
+
+        let portion = if i + 1 == split.len() {
+            remaining
+        } else {
+            (cost as u128 * share.bps as u128 / 10_000) as u64
+        };
+        remaining = remaining.checked_sub(portion).ok_or(WGBError::MathOverflow)?;
+        if portion == 0 {
+            continue;
+        }
+        if receiver.key() == accounts.buyer.key() {
+            self_paid += portion;
+            continue;
+        }
+        system_program::transfer(
+            CpiContext::new(
+                accounts.system_program.to_account_info(),
+                system_program::Transfer {
+                    from: accounts.buyer.to_account_info(),
+                    to: receiver.clone(),
+                },
+            ),
+            portion,
+        )?;
+    }
+
+    let paid = buyer_lamports_before
+        .checked_sub(accounts.buyer.lamports())
+        .ok_or(WGBError::MathOverflow)?;
+    require!(paid + self_paid == cost, WGBError::PaymentMismatch);
+    Ok(cost)
+}
+
+// ==================== RESERVED BLOCK ====================
+//
+// Features that must not change the ProtocolState layout store their data in
+// `_reserved` through these helpers. Every slot is registered in RESERVED_SLOTS;
+// the const assertions below fail the build if two slots overlap or a slot
+// runs past the block, so features cannot stomp each other's bytes.
+//
+// Offset | Len | Slot            | Since schema
+// -------|-----|-----------------|-------------
+//      0 |   1 | SCHEMA_VERSION  | 1
+
+/// A byte range inside `ProtocolState._reserved`
+#[derive(Clone, Copy)]
+pub struct ReservedSlot {
+    pub offset: usize,
+    pub len: usize,
+}
+
+impl ReservedSlot {
+    pub const SCHEMA_VERSION: ReservedSlot = ReservedSlot { offset: 0, len: 1 };
+}
+
+/// Every assigned slot, in offset order
+pub const RESERVED_SLOTS: [ReservedSlot; 1] = [ReservedSlot::SCHEMA_VERSION];
+
+const fn reserved_slots_are_disjoint(slots: &[ReservedSlot], block_len: usize) -> bool {
+    let mut i = 0;
+    while i < slots.len() {
+        if slots[i].len == 0 || slots[i].offset + slots[i].len > block_len {
+            return false;
+        }
+        if i > 0 && slots[i - 1].offset + slots[i - 1].len > slots[i].offset {
+            return false;
+        }
+        i += 1;
+    }
+    true
+}
+
+// The packed state (discriminator included) must fit the allocation
+const _: () = assert!(8 + ProtocolState::INIT_SPACE <= PROTOCOL_STATE_SIZE);
+const _: () = assert!(reserved_slots_are_disjoint(&RESERVED_SLOTS, ProtocolState::RESERVED_LEN));
+
+fn read_reserved_field(state: &ProtocolState, slot: ReservedSlot) -> &[u8] {
+    &state._reserved[slot.offset..slot.offset + slot.len]
+}
+
+fn write_reserved_field(state: &mut ProtocolState, slot: ReservedSlot, value: &[u8]) {
+    state._reserved[slot.offset..slot.offset + slot.len].copy_from_slice(value);
+}
+
+impl ProtocolState {
+    pub const RESERVED_LEN: usize = 114;
+
+    /// 0 on accounts that predate the reserved schema
+    pub fn reserved_schema_version(&self) -> u8 {
+        read_reserved_field(self, ReservedSlot::SCHEMA_VERSION)[0]
+    }
+
+    /// The primary operator, or the backup operator once one is set
+    pub fn is_operator(&self, key: &Pubkey) -> bool {
+        *key == self.operator || (self.backup_operator != Pubkey::default() && *key == self.backup_operator)
+    }
+
+    /// Per-stage redemption deadlines read by expire_pending, expire_claim and auto_confirm
+    pub fn redemption_timeouts(&self) -> RedemptionTimeouts {
+        RedemptionTimeouts {
+            pending_expiry_secs: self.pending_expiry_secs,
+            claim_to_ship_secs: self.claim_timeout_secs,
+            ship_to_confirm_secs: self.auto_confirm_secs,
+        }
+    }
+
+    /// Every commitment window, as validated and written by apply_time_windows
+    pub fn time_windows(&self) -> TimeWindows {
+        TimeWindows {
+            quote_lock_secs: self.quote_lock_secs,
+            redemption: self.redemption_timeouts(),
+            reward_holdback_secs: self.reward_holdback_secs,
+        }
+    }
+}
+
+// ==================== STRUCTS & ACCOUNTS ====================
+
+#[account]
+#[derive(InitSpace)]
+pub struct ProtocolState {
+    pub authority: Pubkey,
+    pub operator: Pubkey,       // NEW: Hot wallet for auto-ops
+    pub wgb_mint: Pubkey,
+    pub treasury: Pubkey,
+    pub total_supply: u64,
+    pub total_burned: u64,      // NEW: Track burns
+    
+    pub current_merkle_root: [u8; 32],
+    pub proven_reserves: u64,
+    pub last_root_update: i64,
+    pub last_proof_timestamp: i64,
+    
+    pub wgb_price_lamports: u64,
+    pub sol_receiver: Pubkey,
+    
+    // Yield & Future
+    pub yield_apy_bps: u16,             // APY in basis points (350 = 3.5%)
+    pub total_yield_distributed: u64,   // Total W3B distributed as yield
+    pub last_yield_distribution: i64,   // Timestamp of last yield distribution
+    
+    pub is_paused: bool,
+    pub bump: u8,
+
+    pub guardian: Pubkey,       // Pause-only emergency key (carved from _reserved)
+    pub round_up_cost: bool,    // Ceiling (true) vs floor (false) rounding on buy cost
+    pub legacy_imported: bool,  // Set once import_legacy_stats has run
+
+    // Latest attestation (mirrors the last ProofSubmitted event)
+    pub last_proof_hash: [u8; 32],      // submit_proof payload, or its sha256 when not 32 bytes
+    pub last_claimed_reserves: u64,
+
+    // Fulfiller accountability
+    pub claim_timeout_secs: i64,        // 0 = claims never expire
+    pub fulfiller_slash_points: u64,    // Deducted on expired claims
+
+    pub max_sol_per_buy: u64,           // Lamport cap per buy (0 = uncapped)
+
+    pub airdrop_root: [u8; 32],         // Points airdrop Merkle root (zero = inactive)
+    
+    pub _reserved: [u8; ProtocolState::RESERVED_LEN], // Fills the V2 allocation; see RESERVED_SLOTS
+
+    // ---- V3 region (beyond 512 bytes; requires resize_protocol_state on old deployments) ----
+    pub auto_confirm_secs: i64,         // Shipped orders auto-confirm after this long (0 = never)
+    pub oracle: Pubkey,                 // Signs reserve attestations for submit_signed_proof (default = disabled)
+    pub max_mint_per_period: u64,       // Mint budget per window (0 = unlimited)
+    pub mint_period_secs: i64,          // Window length for max_mint_per_period
+    pub mint_period_start: i64,         // Start of the current window
+    pub minted_in_period: u64,          // Minted so far in the current window
+    pub min_referrer_tier: u8,          // Referrers below this tier earn no bonus (0 = open)
+    pub pause_reason: u8,               // PauseReason while is_paused (Unspecified otherwise)
+    pub max_proof_age_secs: i64,        // Proof staleness limit (0 = DEFAULT_MAX_PROOF_AGE_SECS)
+    pub min_mint_interval_secs: i64,    // Minimum spacing between mint_wgb calls (0 = none)
+    pub last_mint_at: i64,              // Timestamp of the last mint_wgb
+    pub replenish_threshold: u64,       // buy_wgb emits ReplenishNeeded below this treasury balance (0 = off)
+    pub replenish_amount: u64,          // Suggested top-up carried in ReplenishNeeded
+    pub auto_replenish: bool,           // Operator bot may mint the suggestion without review
+    pub grams_per_token_bps: u64,       // Gold per W3B in 1/10_000 g (0 = not configured)
+    pub welcome_tier: u8,               // Granted on a Bronze first purchase (0 = Bronze, no boost)
+    pub event_verbosity: u8,            // EVENTS_FULL / EVENTS_REDUCED / EVENTS_MINIMAL
+    pub buyback_budget_lamports: u64,   // Remaining SOL trigger_buyback may spend
+    pub max_active_claims_per_fulfiller: u32, // Open claims one fulfiller may hold (0 = unlimited)
+    pub tier_priority: [u8; MAX_TIERS], // Redemption priority per tier (all zero = flat ordering)
+    pub operator_rebate_lamports: u64,  // Paid to the operator per proof (0 = off)
+    pub total_operator_rebates: u64,    // Lifetime rebates paid
+    pub operator_price_band_bps: u16,   // Max set_wgb_price swing for the operator (0 = 20%)
+    pub authority_price_band_bps: u16,  // Max set_wgb_price swing for the authority (0 = 20%)
+    pub max_user_points: u64,           // Per-profile points ceiling (0 = unlimited)
+    pub mint_delay_secs: i64,           // Wait after a proof before minting against it (0 = none)
+    pub points_transfers_enabled: bool, // Allows transfer_points between users
+    pub tier_basis: u8,                 // TIER_BASIS_POINTS or TIER_BASIS_VOLUME
+    pub proof_challenged: bool,         // A ProofChallenge is open; minting is halted
+    pub min_challenge_bond: u64,        // Lamports challenge_proof must post
+    pub operator_penalties: u32,        // Upheld challenges against the operator's attestations
+    pub buy_points_bps: u64,            // Buy points per token in bps (0 = PointsConfig whole-point rate)
+    pub min_buy_amount: u64,            // Smallest buy accepted (0 = any non-zero amount)
+    pub backup_operator: Pubkey,        // Fallback operator key (default = none)
+    pub reserve_unit: u8,               // RESERVE_UNIT_SERIALS (existing deployments) or RESERVE_UNIT_WEIGHT
+    pub reserve_weight: u64,            // Attested reserve weight in 1/10_000 g (weight basis only)
+    pub require_kyc: bool,              // Buys need a KycAttestation of min_kyc_level
+    pub min_kyc_level: u8,
+    pub cancel_penalty_bps: u16,        // Burn kept on cancel_own_redemption (0 = full refund)
+    pub yield_min_coverage_bps: u16,    // Reserve coverage yield needs, in bps of supply (0 = 1:1)
+    pub operator_rotation_cooldown_secs: i64, // Minimum gap between operator_self_rotate calls
+    pub last_operator_change: i64,      // Unix time the operator key last changed (0 = never recorded)
+    pub wgb_sell_price_lamports: u64,   // Buyback price per token (0 = wgb_price_lamports)
+    pub maintain_spread: bool,          // Derive the sell price from the buy price on every update
+    pub spread_bps: u16,                // Sell discount to the buy price while maintain_spread is on
+    pub pending_expiry_secs: i64,       // Unclaimed orders can be refunded after this long (0 = never)
+    pub lineage_hash: [u8; 32],         // Hash chain over every root set (see extend_lineage)
+    pub reward_holdback_secs: i64,      // Dispute window before fulfiller rewards are paid (0 = immediately)
+    pub pending_fulfiller_rewards: u64, // Reward points currently held back across all orders
+    pub tier_fulfiller_multiplier_bps: [u16; MAX_TIERS], // Fulfiller reward multiplier per tier (0 = 1x)
+    pub coverage_bps: u16,              // Cached backing / supply in bps (see update_coverage)
+    pub redemption_rounding: u8,        // REDEMPTION_ROUND_* applied by burn_wgb
+    pub redemption_bar_unit: u64,       // Tokens per physical bar (0 = no rounding)
+    pub insurance_fee_bps: u16,         // Share of buy proceeds routed to the insurance fund
+    pub insurance_balance: u64,         // Lamports routed in minus cover_loss payouts
+    pub cpi_gate: u8,                   // CPI_GATE_* applied to the buy path
+    pub streak_bonus_points: [u32; STREAK_BONUS_DAYS], // Daily-streak bonus by streak length (0 = none)
+    pub multi_fulfiller_threshold: u64, // Orders this large need several fulfillers (0 = off)
+    pub min_distinct_fulfillers: u8,    // Contributors such orders need before confirming
+    pub quote_lock_secs: i64,           // How long lock_quote holds a price (0 = 60s)
+    pub treasury_owner_mode: u8,        // TREASURY_OWNER_PDA or TREASURY_OWNER_AUTHORITY
+    pub paused_until: i64,              // Scheduled maintenance end for try_auto_unpause (0 = none)
+    pub allow_reserve_shrink: bool,     // adjust_reserves may take reserves below total_supply
+    pub buy_cooldown_secs: i64,         // Minimum gap between one buyer's purchases (0 = off)
+}
+
+/// Points and stat counters are updated with checked math — an overflow fails the
+/// instruction with MathOverflow rather than silently clamping a balance.
+#[account]
+pub struct UserProfile {
+    pub user: Pubkey,
+    pub total_volume: u64,
+    pub points: u64,
+    pub tier: u8,              // 0=Bronze, 1=Silver, 2=Gold, 3=Platinum
+    pub total_redeemed: u64,
+    pub total_fulfilled: u64,
+    pub fulfiller_rewards: u64,
+    pub bump: u8,
+    pub last_award_nonce: u64, // Highest award_points nonce applied (carved from _reserved)
+    pub failed_fulfillments: u64,
+    pub compliance_locked: bool, // Admin hold on buys/burns
+    pub active_claims: u32,    // Redemptions currently claimed as fulfiller
+    pub points_dust: u64,      // Sub-point remainder of buy_points_bps awards, in 1/10_000 points
+    pub welcome_tier_floor: u8, // Tier granted on the first purchase; later recomputes never drop below it
+    pub _reserved: [u8; 2],   // Future expansion without migration
+    pub streak_days: u16,      // Consecutive days with a buy (appended within the 128-byte allocation)
+    pub last_streak_day: u32,  // Unix day (timestamp / 86_400) of the last counted buy
+    pub last_buy_at: i64,      // Timestamp of the last buy, for buy_cooldown_secs
+}
+
+#[account]
+pub struct TierConfig {
+    pub tiers: Vec<TierDefinition>, // Ascending thresholds, index = tier id
+    pub bump: u8,
+}
+
+impl UserProfile {
+    /// Whether the profile carries any standing that a migration must not overwrite
+    pub fn is_populated(&self) -> bool {
+        self.points > 0
+            || self.total_volume > 0
+            || self.total_redeemed > 0
+            || self.total_fulfilled > 0
+            || self.fulfiller_rewards > 0
+            || self.failed_fulfillments > 0
+            || self.points_dust > 0
+            || self.last_award_nonce > 0
+            || self.compliance_locked
+            || self.streak_days > 0
+            || self.last_buy_at != 0
+            || self.welcome_tier_floor > 0
+    }
+}
+
+impl TierConfig {
+    pub const SPACE: usize = 4 + MAX_TIERS * (8 + 2) + 1;
+
+    /// Buy discount for a tier id; unknown tiers get none
+    pub fn discount_for(&self, tier: u8) -> u16 {
+        self.tiers.get(tier as usize).map_or(0, |t| t.discount_bps)
+    }
+}
+
+/// Why the protocol is paused — stored as u8 in ProtocolState.pause_reason
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq)]
+#[repr(u8)]
+pub enum PauseReason {
+    Unspecified = 0,
+    Maintenance = 1,
+    Emergency = 2,
+    ReserveAudit = 3,
+}
+
+#[account]
+pub struct AdminMultisig {
+    pub signers: Vec<Pubkey>, // At most MAX_MULTISIG_SIGNERS, no duplicates
+    pub threshold: u8,        // Distinct listed signers required (0 = disabled)
+    pub bump: u8,
+}
+
+impl AdminMultisig {
+    pub const SPACE: usize = 4 + 32 * MAX_MULTISIG_SIGNERS + 1 + 1;
+
+    /// Count distinct listed keys that signed — the authority plus any signer remaining_accounts
+    pub fn is_satisfied(&self, authority: &Pubkey, remaining: &[AccountInfo]) -> bool {
+        if self.threshold == 0 {
+            return true;
+        }
+        let approvals = self
+            .signers
+            .iter()
+            .filter(|key| {
+                *key == authority || remaining.iter().any(|a| a.is_signer && a.key == *key)
+            })
+            .count();
+        approvals >= self.threshold as usize
+    }
+}
+
+/// Redemption lifecycle deadlines in seconds (0 = that stage never times out)
+/// Stored in ProtocolState; claim_to_ship and ship_to_confirm are claim_timeout_secs
+/// and auto_confirm_secs.
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy)]
+pub struct RedemptionTimeouts {
+    pub pending_expiry_secs: i64,  // Pending → refunded by expire_pending
+    pub claim_to_ship_secs: i64,   // Claimed → back to Pending by expire_claim
+    pub ship_to_confirm_secs: i64, // Shipped → Confirmed by auto_confirm
+}
+
+/// Every time-bounded commitment in one place (0 = default/off, as for each field's setter).
+/// Bounds: quote_lock_secs <= MAX_QUOTE_LOCK_SECS, the rest <= MAX_TIME_WINDOW_SECS, and
+/// with both on the reward holdback is at least the auto-confirm window, so a redeemer who
+/// missed the confirm window still gets as long again to dispute.
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy)]
+pub struct TimeWindows {
+    pub quote_lock_secs: i64,           // lock_quote price hold (0 = DEFAULT_QUOTE_LOCK_SECS)
+    pub redemption: RedemptionTimeouts, // Redemption stage deadlines
+    pub reward_holdback_secs: i64,      // Post-confirm dispute window before fulfiller rewards pay
+}
+
+/// Buy proceeds split across receivers (optional singleton) — see set_sol_split
+#[account]
+pub struct SolSplit {
+    pub receivers: Vec<SolShare>, // At most MAX_SOL_RECEIVERS; bps sum to 10_000 (empty = off)
+    pub bump: u8,
+}
+
+impl SolSplit {
+    pub const SPACE: usize = 4 + MAX_SOL_RECEIVERS * (32 + 2) + 1;
+}
+
+/// Rolling outflow cap (optional singleton) — see set_withdrawal_throttle
+#[account]
+pub struct WithdrawalThrottle {
+    pub window_secs: i64,      // Period length (0 = throttle off)
+    pub sol_limit: u64,        // Lamports allowed out per window (0 = uncapped)
+    pub token_limit: u64,      // Token base units allowed out per window (0 = uncapped)
+    pub paused_limit_bps: u16, // Share of each cap still available while paused
+    pub window_start: i64,     // Unix time the current window opened
+    pub sol_out: u64,          // Lamports moved out in the current window
+    pub tokens_out: u64,       // Token base units moved out in the current window
+    pub bump: u8,
+}
+
+impl WithdrawalThrottle {
+    pub const SPACE: usize = 8 + 8 + 8 + 2 + 8 + 8 + 8 + 1;
+
+    pub fn effective_sol_limit(&self) -> u64 {
+        if self.sol_limit == 0 { u64::MAX } else { self.sol_limit }
+    }
+
+    pub fn effective_token_limit(&self) -> u64 {
+        if self.token_limit == 0 { u64::MAX } else { self.token_limit }
+    }
+
+    /// `limit` scaled down to paused_limit_bps while the protocol is paused
+    pub fn limit_now(&self, limit: u64, paused: bool) -> u64 {
+        if paused {
+            (limit as u128 * self.paused_limit_bps as u128 / 10_000) as u64
+        } else {
+            limit
+        }
+    }
+}
+
+/// Partner programs allowed to CPI into buys (optional singleton) — see set_cpi_allowlist
+#[account]
+pub struct CpiAllowlist {
+    pub programs: Vec<Pubkey>, // At most MAX_CPI_ALLOWLIST, no duplicates
+    pub bump: u8,
+}
+
+impl CpiAllowlist {
+    pub const SPACE: usize = 4 + 32 * MAX_CPI_ALLOWLIST + 1;
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy)]
+pub struct SolShare {
+    pub receiver: Pubkey,
+    pub bps: u16,
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy)]
+pub struct TierDefinition {
+    pub threshold: u64,    // Points strictly above this reach the tier
+    pub discount_bps: u16, // Buy discount for holders of the tier
+}
+
+#[account]
+pub struct PointsConfig {
+    pub buy_points_per_token: u64,  // Points per WGB bought
+    pub redemption_multiplier: u64, // Points per WGB burned for redemption
+    pub fulfiller_reward: u64,      // Points per confirmed delivery
+    pub referral_bonus: u64,        // Points per successful referral
+    pub bump: u8,
+}
+
+impl PointsConfig {
+    pub const SPACE: usize = 8 * 4 + 1;
+}
+
+/// Rates in effect before a PointsConfig has been created
+impl Default for PointsConfig {
+    fn default() -> Self {
+        Self {
+            buy_points_per_token: 1,
+            redemption_multiplier: 2,
+            fulfiller_reward: 5,
+            referral_bonus: 0,
+            bump: 0,
+        }
+    }
+}
+
+#[account]
+pub struct AirdropClaim {
+    pub user: Pubkey,
+    pub root: [u8; 32],
+    pub amount: u64,
+    pub claimed_at: i64,
+    pub bump: u8,
+}
+
+#[account]
+pub struct Referral {
+    pub referee: Pubkey,
+    pub referrer: Pubkey,
+    pub bonus_paid: u64, // 0 when the referrer was below min_referrer_tier
+    pub created_at: i64,
+    pub bump: u8,
+}
+
+#[account]
+pub struct RedemptionRequest {
+    pub user: Pubkey,
+    pub request_id: u64,
+    pub amount: u64,
+    pub status: u8, // 0=Pending, 1=Claimed, 2=Shipped, 3=Confirmed, 4=Cancelled, 5=Disputed
+    pub fulfiller: Pubkey,
+    pub created_at: i64,
+    pub claimed_at: i64,
+    pub confirmed_at: i64,
+    pub bump: u8,
+    pub shipped_at: i64,
+    pub serials_recorded: u64, // RedeemedSerial markers created for this order (<= bars redeemed)
+    pub priority: u8,          // Higher = fulfil sooner; from ProtocolState.tier_priority
+    pub held_reward: u16,      // Nonzero while a reward is held; saturated copy of HeldReward.amount
+    pub region_code: u16,      // Coarse delivery region from burn_wgb (0 = unspecified); fixed at offset 133 for memcmp
+}
+
+/// A buyer's price quote from lock_quote, consumed (closed) by the buy that uses it
+#[account]
+pub struct QuoteLock {
+    pub buyer: Pubkey,
+    pub amount: u64,         // The buy must be for exactly this many tokens
+    pub price_lamports: u64, // wgb_price_lamports when locked
+    pub expires_at: i64,
+    pub bump: u8,
+}
+
+impl QuoteLock {
+    pub const SPACE: usize = 32 + 8 + 8 + 8 + 1;
+}
+
+/// Fulfillers sharing a large redemption — see record_contribution
+#[account]
+pub struct FulfillerContributions {
+    pub redemption_request: Pubkey,
+    pub contributors: Vec<Contribution>, // Distinct fulfillers, at most MAX_ORDER_FULFILLERS
+    pub bump: u8,
+}
+
+impl FulfillerContributions {
+    pub const SPACE: usize = 32 + 4 + MAX_ORDER_FULFILLERS * (32 + 8) + 1;
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy)]
+pub struct Contribution {
+    pub fulfiller: Pubkey,
+    pub amount: u64, // Tokens' worth of the order this fulfiller delivered
+}
+
+#[account]
+pub struct RedemptionAddress {
+    pub redemption_request: Pubkey,
+    pub address_commitment: [u8; 32], // Hash of the off-chain shipping address
+    pub updated_at: i64,
+    pub bump: u8,
+}
+
+/// A confirmed order's fulfiller reward held back for reward_holdback_secs, in full
+#[account]
+pub struct HeldReward {
+    pub redemption_request: Pubkey,
+    pub amount: u64, // Points awaiting release_fulfiller_rewards (0 = none or settled)
+    pub bump: u8,
+}
+
+/// Proof-of-delivery commitments for a redemption (hashes of off-chain receipts, no PII)
+#[account]
+pub struct DeliveryProof {
+    pub redemption_request: Pubkey,
+    pub shipment_proof_hash: [u8; 32], // Set by mark_shipped (courier hand-off receipt)
+    pub delivery_proof_hash: [u8; 32], // Set by confirm_delivery (signed POD)
+    pub updated_at: i64,
+    pub bump: u8,
+}
+
+/// Protocol-wide redemption lifecycle counters (singleton)
+/// `claimed` counts claims, so an order that expires and is re-claimed counts twice.
+#[account]
+pub struct RedemptionStats {
+    pub created: u64,
+    pub claimed: u64,
+    pub shipped: u64,
+    pub disputed: u64,
+    pub confirmed: u64,
+    pub cancelled: u64,
+    pub expired: u64,
+    pub bump: u8,
+}
+
+impl RedemptionStats {
+    pub const SPACE: usize = 8 * 7 + 1;
+}
+
+/// Last PROOF_HISTORY_LEN proof attestations (singleton ring buffer), for clients that read
+/// account state instead of transaction logs. Slot = total_recorded % PROOF_HISTORY_LEN.
+#[account]
+pub struct ProofHistory {
+    pub entries: [ProofRecord; PROOF_HISTORY_LEN],
+    pub total_recorded: u64, // Proofs ever recorded; entries past this count are empty
+    pub bump: u8,
+}
+
+impl ProofHistory {
+    pub const SPACE: usize = PROOF_HISTORY_LEN * (32 + 8) + 8 + 1;
+
+    /// Filled entries, oldest first
+    pub fn ordered(&self) -> Vec<ProofRecord> {
+        let len = PROOF_HISTORY_LEN as u64;
+        let start = self.total_recorded.saturating_sub(len);
+        (start..self.total_recorded)
+            .map(|i| self.entries[(i % len) as usize])
+            .collect()
+    }
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Default)]
+pub struct ProofRecord {
+    pub proof_hash: [u8; 32],
+    pub timestamp: i64,
+}
+
+/// Open challenge against a reserve attestation; holds the challenger's bond until resolved
+#[account]
+pub struct ProofChallenge {
+    pub challenger: Pubkey,
+    pub merkle_root: [u8; 32],
+    pub proof_timestamp: i64, // last_proof_timestamp of the challenged attestation
+    pub bond: u64,
+    pub created_at: i64,
+    pub bump: u8,
+}
+
+impl ProofChallenge {
+    pub const SPACE: usize = 32 + 32 + 8 + 8 + 8 + 1;
+}
+
+/// Compliance key's attestation of a user's KYC level (no personal data)
+#[account]
+pub struct KycAttestation {
+    pub user: Pubkey,
+    pub level: u8,           // 0 = revoked
+    pub expires_at: i64,     // 0 = never lapses
+    pub attested_at: i64,
+    pub bump: u8,
+}
+
+/// Present while a user's redemptions are blocked; closed on unblock
+#[account]
+pub struct RedemptionBlock {
+    pub user: Pubkey,
+    pub blocked_at: i64,
+    pub bump: u8,
+}
+
+/// Marks a redemption whose loss cover_loss has paid, so it can't be paid twice
+#[account]
+pub struct CoveredLoss {
+    pub redemption_request: Pubkey,
+    pub amount: u64, // Lamports paid from the insurance fund
+    pub covered_at: i64,
+    pub bump: u8,
+}
+
+#[account]
+pub struct RedeemedSerial {
+    pub serial_leaf: [u8; 32], // sha256(serial)
+    pub user: Pubkey,
+    pub request_id: u64,
+    pub redeemed_at: i64,
+    pub bump: u8,
+}
+
+// ==================== CONTEXTS ====================
+
+#[derive(Accounts)]
+pub struct InitializeV2<'info> {
+    #[account(init, payer = authority, space = PROTOCOL_STATE_SIZE, seeds = [b"protocol_state"], bump)]
+    pub protocol_state: Account<'info, ProtocolState>,
+    /// Token-2022 mint (validated as a real mint account)
+    pub wgb_mint: InterfaceAccount<'info, Mint>,
+    /// Treasury token account (validated as a real token account)
+    pub treasury: InterfaceAccount<'info, TokenAccount>,
+    #[account(mut)]
+    pub authority: Signer<'info>,
+    pub system_program: Program<'info, System>,
+    pub token_program: Program<'info, Token2022>,
+}
+
+#[derive(Accounts)]
+pub struct MigrateV2<'info> {
+    /// CHECK: Manual resize — AccountInfo used because deserialization may fail mid-migration.
+    /// Authority is validated inside the instruction body by reading raw bytes.
+    #[account(mut, seeds = [b"protocol_state"], bump)]
+    pub protocol_state: AccountInfo<'info>,
+    #[account(mut)]
+    pub authority: Signer<'info>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct ChallengeProof<'info> {
+    #[account(mut, seeds = [b"protocol_state"], bump = protocol_state.bump)]
+    pub protocol_state: Account<'info, ProtocolState>,
+    #[account(
+        init,
+        payer = challenger,
+        space = 8 + ProofChallenge::SPACE,
+        seeds = [b"proof_challenge"],
+        bump
+    )]
+    pub proof_challenge: Account<'info, ProofChallenge>,
+    #[account(mut)]
+    pub challenger: Signer<'info>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct ResolveChallenge<'info> {
+    #[account(mut, seeds = [b"protocol_state"], bump = protocol_state.bump, has_one = authority)]
+    pub protocol_state: Account<'info, ProtocolState>,
+    #[account(mut, seeds = [b"proof_challenge"], bump = proof_challenge.bump)]
+    pub proof_challenge: Account<'info, ProofChallenge>,
+    /// CHECK: Bond refund destination when upheld
+    #[account(mut, address = proof_challenge.challenger @ WGBError::Unauthorized)]
+    pub challenger: UncheckedAccount<'info>,
+    /// CHECK: Validated via protocol_state.sol_receiver — receives forfeited bonds
+    #[account(mut, address = protocol_state.sol_receiver @ WGBError::Unauthorized)]
+    pub sol_receiver: UncheckedAccount<'info>,
+    /// CHECK: WithdrawalThrottle PDA — may not exist yet; enforced by consume_withdrawal
+    #[account(mut, seeds = [b"withdrawal_throttle"], bump)]
+    pub withdrawal_throttle: UncheckedAccount<'info>,
+    pub authority: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct EnsureInitialized<'info> {
+    /// CHECK: ProtocolState PDA — may not exist yet; inspected in the instruction body
+    #[account(seeds = [b"protocol_state"], bump)]
+    pub protocol_state: UncheckedAccount<'info>,
+}
+
+#[derive(Accounts)]
+pub struct FixBump<'info> {
+    /// Seeds checked against the canonical bump — the stored one is what's being repaired
+    #[account(mut, seeds = [b"protocol_state"], bump, has_one = authority)]
+    pub protocol_state: Account<'info, ProtocolState>,
+    pub authority: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct ImportLegacy<'info> {
+    #[account(mut, seeds = [b"protocol_state"], bump = protocol_state.bump, has_one = authority)]
+    pub protocol_state: Account<'info, ProtocolState>,
+    /// CHECK: Legacy program PDA — owner and address are pinned here, layout is validated in the instruction body
+    #[account(
+        seeds = [b"protocol_state"],
+        bump,
+        seeds::program = LEGACY_PROGRAM_ID,
+        owner = LEGACY_PROGRAM_ID @ WGBError::InvalidLegacyState
+    )]
+    pub legacy_state: UncheckedAccount<'info>,
+    pub authority: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct OperatorOnly<'info> {
+    #[account(mut, seeds = [b"protocol_state"], bump = protocol_state.bump)]
+    pub protocol_state: Account<'info, ProtocolState>,
+    #[account(
+        constraint = protocol_state.is_operator(&operator.key())
+                  || operator.key() == protocol_state.authority
+                  @ WGBError::Unauthorized
+    )]
+    pub operator: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct SubmitProof<'info> {
+    #[account(mut, seeds = [b"protocol_state"], bump = protocol_state.bump)]
+    pub protocol_state: Account<'info, ProtocolState>,
+    /// Writable so the operator rebate can be paid
+    #[account(
+        mut,
+        constraint = protocol_state.is_operator(&operator.key())
+                  || operator.key() == protocol_state.authority
+                  @ WGBError::Unauthorized
+    )]
+    pub operator: Signer<'info>,
+    /// CHECK: ProofHistory PDA — appended once initialized; see record_proof
+    #[account(mut, seeds = [b"proof_history"], bump)]
+    pub proof_history: UncheckedAccount<'info>,
+    /// CHECK: WithdrawalThrottle PDA — may not exist yet; the rebate counts against it
+    #[account(mut, seeds = [b"withdrawal_throttle"], bump)]
+    pub withdrawal_throttle: UncheckedAccount<'info>,
+}
+
+#[derive(Accounts)]
+pub struct InitProofHistory<'info> {
+    #[account(seeds = [b"protocol_state"], bump = protocol_state.bump, has_one = authority)]
+    pub protocol_state: Account<'info, ProtocolState>,
+    #[account(
+        init,
+        payer = authority,
+        space = 8 + ProofHistory::SPACE,
+        seeds = [b"proof_history"],
+        bump
+    )]
+    pub proof_history: Account<'info, ProofHistory>,
+    #[account(mut)]
+    pub authority: Signer<'info>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct GetProofHistory<'info> {
+    #[account(seeds = [b"proof_history"], bump = proof_history.bump)]
+    pub proof_history: Account<'info, ProofHistory>,
+}
+
+#[derive(Accounts)]
+pub struct VerifyRootLineage<'info> {
+    #[account(seeds = [b"protocol_state"], bump = protocol_state.bump)]
+    pub protocol_state: Account<'info, ProtocolState>,
+}
+
+#[derive(Accounts)]
+pub struct OperatorSelfRotate<'info> {
+    #[account(mut, seeds = [b"protocol_state"], bump = protocol_state.bump)]
+    pub protocol_state: Account<'info, ProtocolState>,
+    /// Only the primary operator — the backup key cannot rotate it away
+    #[account(constraint = operator.key() == protocol_state.operator @ WGBError::Unauthorized)]
+    pub operator: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct SubmitSignedProof<'info> {
+    #[account(mut, seeds = [b"protocol_state"], bump = protocol_state.bump)]
+    pub protocol_state: Account<'info, ProtocolState>,
+
+    /// CHECK: Instructions sysvar, read to find the ed25519 verification
+    #[account(address = solana_instructions_sysvar::ID)]
+    pub instructions_sysvar: UncheckedAccount<'info>,
+
+    /// CHECK: ProofHistory PDA — appended once initialized; see record_proof
+    #[account(mut, seeds = [b"proof_history"], bump)]
+    pub proof_history: UncheckedAccount<'info>,
+
+    /// Anyone may relay — the oracle signature is what authorizes the update
+    pub submitter: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct AdminOnly<'info> {
+    #[account(mut, seeds = [b"protocol_state"], bump = protocol_state.bump, has_one = authority)]
+    pub protocol_state: Account<'info, ProtocolState>,
+    pub authority: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct MultisigAdminOnly<'info> {
+    #[account(mut, seeds = [b"protocol_state"], bump = protocol_state.bump, has_one = authority)]
+    pub protocol_state: Account<'info, ProtocolState>,
+    /// CHECK: AdminMultisig PDA — may not exist yet; enforced by require_multisig
+    #[account(seeds = [b"admin_multisig"], bump)]
+    pub admin_multisig: UncheckedAccount<'info>,
+    pub authority: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct SetTreasury<'info> {
+    #[account(mut, seeds = [b"protocol_state"], bump = protocol_state.bump, has_one = authority)]
+    pub protocol_state: Account<'info, ProtocolState>,
+    /// CHECK: AdminMultisig PDA — may not exist yet; enforced by require_multisig
+    #[account(seeds = [b"admin_multisig"], bump)]
+    pub admin_multisig: UncheckedAccount<'info>,
+    #[account(token::mint = protocol_state.wgb_mint)]
+    pub treasury: InterfaceAccount<'info, TokenAccount>,
+    pub authority: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct SetAdminMultisig<'info> {
+    #[account(seeds = [b"protocol_state"], bump = protocol_state.bump, has_one = authority)]
+    pub protocol_state: Account<'info, ProtocolState>,
+    #[account(
+        init_if_needed,
+        payer = authority,
+        space = 8 + AdminMultisig::SPACE,
+        seeds = [b"admin_multisig"],
+        bump
+    )]
+    pub admin_multisig: Account<'info, AdminMultisig>,
+    #[account(mut)]
+    pub authority: Signer<'info>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct SetSolSplit<'info> {
+    #[account(seeds = [b"protocol_state"], bump = protocol_state.bump, has_one = authority)]
+    pub protocol_state: Account<'info, ProtocolState>,
+    #[account(
+        init_if_needed,
+        payer = authority,
+        space = 8 + SolSplit::SPACE,
+        seeds = [b"sol_split"],
+        bump
+    )]
+    pub sol_split: Account<'info, SolSplit>,
+    #[account(mut)]
+    pub authority: Signer<'info>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct SetWithdrawalThrottle<'info> {
+    #[account(seeds = [b"protocol_state"], bump = protocol_state.bump, has_one = authority)]
+    pub protocol_state: Account<'info, ProtocolState>,
+    /// CHECK: AdminMultisig PDA — may not exist yet; enforced by require_multisig
+    #[account(seeds = [b"admin_multisig"], bump)]
+    pub admin_multisig: UncheckedAccount<'info>,
+    #[account(
+        init_if_needed,
+        payer = authority,
+        space = 8 + WithdrawalThrottle::SPACE,
+        seeds = [b"withdrawal_throttle"],
+        bump
+    )]
+    pub withdrawal_throttle: Account<'info, WithdrawalThrottle>,
+    #[account(mut)]
+    pub authority: Signer<'info>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct TightenWithdrawalThrottle<'info> {
+    #[account(seeds = [b"protocol_state"], bump = protocol_state.bump)]
+    pub protocol_state: Account<'info, ProtocolState>,
+    #[account(mut, seeds = [b"withdrawal_throttle"], bump = withdrawal_throttle.bump)]
+    pub withdrawal_throttle: Account<'info, WithdrawalThrottle>,
+    #[account(
+        constraint = signer.key() == protocol_state.guardian
+                  || protocol_state.is_operator(&signer.key())
+                  || signer.key() == protocol_state.authority
+                  @ WGBError::Unauthorized
+    )]
+    pub signer: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct CoverLoss<'info> {
+    #[account(mut, seeds = [b"protocol_state"], bump = protocol_state.bump, has_one = authority)]
+    pub protocol_state: Account<'info, ProtocolState>,
+
+    /// Only disputed orders can be made whole
+    #[account(
+        has_one = user,
+        constraint = redemption_request.status == 5 @ WGBError::InvalidRedemptionStatus
+    )]
+    pub redemption_request: Account<'info, RedemptionRequest>,
+
+    /// One payout per order — init fails if this order was already covered
+    #[account(
+        init,
+        payer = authority,
+        space = 8 + 32 + 8 + 8 + 1,
+        seeds = [b"covered_loss", redemption_request.key().as_ref()],
+        bump
+    )]
+    pub covered_loss: Account<'info, CoveredLoss>,
+
+    /// CHECK: Redeemer being paid; matched by has_one on redemption_request
+    #[account(mut)]
+    pub user: UncheckedAccount<'info>,
+
+    /// CHECK: System-owned PDA holding insurance SOL; only this program can sign for it
+    #[account(mut, seeds = [b"insurance_fund"], bump)]
+    pub insurance_fund: UncheckedAccount<'info>,
+
+    /// CHECK: WithdrawalThrottle PDA — may not exist yet; enforced by consume_withdrawal
+    #[account(mut, seeds = [b"withdrawal_throttle"], bump)]
+    pub withdrawal_throttle: UncheckedAccount<'info>,
+
+    #[account(mut)]
+    pub authority: Signer<'info>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct TryAutoUnpause<'info> {
+    #[account(mut, seeds = [b"protocol_state"], bump = protocol_state.bump)]
+    pub protocol_state: Account<'info, ProtocolState>,
+    pub signer: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct RefreshCoverage<'info> {
+    #[account(mut, seeds = [b"protocol_state"], bump = protocol_state.bump)]
+    pub protocol_state: Account<'info, ProtocolState>,
+}
+
+#[derive(Accounts)]
+pub struct SetCpiAllowlist<'info> {
+    #[account(seeds = [b"protocol_state"], bump = protocol_state.bump, has_one = authority)]
+    pub protocol_state: Account<'info, ProtocolState>,
+    #[account(
+        init_if_needed,
+        payer = authority,
+        space = 8 + CpiAllowlist::SPACE,
+        seeds = [b"cpi_allowlist"],
+        bump
+    )]
+    pub cpi_allowlist: Account<'info, CpiAllowlist>,
+    #[account(mut)]
+    pub authority: Signer<'info>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct GuardianPause<'info> {
+    #[account(mut, seeds = [b"protocol_state"], bump = protocol_state.bump)]
+    pub protocol_state: Account<'info, ProtocolState>,
+    #[account(
+        constraint = signer.key() == protocol_state.guardian
+                  || protocol_state.is_operator(&signer.key())
+                  || signer.key() == protocol_state.authority
+                  @ WGBError::Unauthorized
+    )]
+    pub signer: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct UpdateTransferFee<'info> {
+    #[account(
+        seeds = [b"protocol_state"],
+        bump = protocol_state.bump,
+        has_one = authority,
+    )]
+    pub protocol_state: Account<'info, ProtocolState>,
+    pub authority: Signer<'info>,
+    #[account(
+        mut,
+        constraint = wgb_mint.key() == protocol_state.wgb_mint
+    )]
+    pub wgb_mint: InterfaceAccount<'info, Mint>,
+    pub token_program: Program<'info, Token2022>,
+}
+
+#[derive(Accounts)]
+pub struct CloseProtocolState<'info> {
+    #[account(
+        mut,
+        seeds = [b"protocol_state"],
+        bump = protocol_state.bump,
+        has_one = authority,
+        close = authority
+    )]
+    pub protocol_state: Account<'info, ProtocolState>,
+    /// CHECK: AdminMultisig PDA — may not exist yet; enforced by require_multisig
+    #[account(seeds = [b"admin_multisig"], bump)]
+    pub admin_multisig: UncheckedAccount<'info>,
+    #[account(mut)]
+    pub authority: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct MintWGB<'info> {
+    #[account(
+        mut, 
+        seeds = [b"protocol_state"], 
+        bump = protocol_state.bump,
+        has_one = wgb_mint,
+        has_one = treasury
+    )]
+    pub protocol_state: Account<'info, ProtocolState>,
+    
+    #[account(mut)] 
+    pub wgb_mint: InterfaceAccount<'info, Mint>,
+    
+    #[account(
+        mut,
+        token::mint = protocol_state.wgb_mint,
+        constraint = treasury.owner == expected_treasury_owner(&protocol_state)
+    )] 
+    pub treasury: InterfaceAccount<'info, TokenAccount>,
+    
+    pub token_program: Program<'info, Token2022>,
+
+    /// Operator or authority signs
+    #[account(
+        constraint = protocol_state.is_operator(&operator.key())
+                  || operator.key() == protocol_state.authority
+                  @ WGBError::Unauthorized
+    )]
+    pub operator: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct BuyWGB<'info> {
+    #[account(
+        mut, 
+        seeds = [b"protocol_state"], 
+        bump = protocol_state.bump,
+        has_one = treasury, // matches protocol_state.treasury == treasury.key()
+        has_one = sol_receiver
+    )]
+    pub protocol_state: Account<'info, ProtocolState>,
+    
+    #[account(mut)]
+    pub buyer: Signer<'info>,
+    
+    #[account(
+        mut,
+        token::mint = protocol_state.wgb_mint,
+        token::authority = buyer
+    )]
+    pub buyer_token_account: InterfaceAccount<'info, TokenAccount>,
+    
+    #[account(
+        mut,
+        constraint = treasury.owner == expected_treasury_owner(&protocol_state),
+        token::mint = protocol_state.wgb_mint
+    )]
+    pub treasury: InterfaceAccount<'info, TokenAccount>,
+
+    /// Treasury owner — required to co-sign under TREASURY_OWNER_AUTHORITY
+    pub treasury_signer: Option<Signer<'info>>,
+    
+    /// CHECK: Validated via protocol_state.sol_receiver
+    #[account(mut)]
+    pub sol_receiver: AccountInfo<'info>,
+
+    #[account(
+        mut,
+        constraint = wgb_mint.key() == protocol_state.wgb_mint
+    )]
+    pub wgb_mint: InterfaceAccount<'info, Mint>,
+    
+    pub system_program: Program<'info, System>,
+    pub token_program: Program<'info, Token2022>,
+    
+    // Optional Points
+    #[account(mut)]
+    pub user_profile: Option<Account<'info, UserProfile>>,
+
+    /// CHECK: TierConfig PDA — may not exist yet; loaded by load_tier_config
+    #[account(seeds = [b"tier_config"], bump)]
+    pub tier_config: UncheckedAccount<'info>,
+
+    /// CHECK: PointsConfig PDA — may not exist yet; loaded by load_points_config
+    #[account(seeds = [b"points_config"], bump)]
+    pub points_config: UncheckedAccount<'info>,
+
+    /// CHECK: KycAttestation PDA — only read when require_kyc is on; see ensure_kyc
+    #[account(seeds = [b"kyc_attestation", buyer.key().as_ref()], bump)]
+    pub kyc_attestation: UncheckedAccount<'info>,
+
+    /// CHECK: SolSplit PDA — may not exist yet; loaded by load_sol_split
+    #[account(seeds = [b"sol_split"], bump)]
+    pub sol_split: UncheckedAccount<'info>,
+
+    /// CHECK: System-owned PDA receiving insurance_fee_bps of each buy
+    #[account(mut, seeds = [b"insurance_fund"], bump)]
+    pub insurance_fund: UncheckedAccount<'info>,
+
+    /// CHECK: Instructions sysvar, read to identify a CPI caller
+    #[account(address = solana_instructions_sysvar::ID)]
+    pub instructions_sysvar: UncheckedAccount<'info>,
+
+    /// CHECK: CpiAllowlist PDA — may not exist yet; read by ensure_cpi_allowed
+    #[account(seeds = [b"cpi_allowlist"], bump)]
+    pub cpi_allowlist: UncheckedAccount<'info>,
+
+    // Optional price lock from lock_quote; buys at its price and is closed to the buyer
+    #[account(mut, seeds = [b"quote_lock", buyer.key().as_ref()], bump = quote_lock.bump)]
+    pub quote_lock: Option<Account<'info, QuoteLock>>,
+}
+
+#[derive(Accounts)]
+pub struct MintAndSell<'info> {
+    #[account(
+        mut,
+        seeds = [b"protocol_state"],
+        bump = protocol_state.bump,
+        has_one = wgb_mint,
+        has_one = treasury,
+        has_one = sol_receiver
+    )]
+    pub protocol_state: Account<'info, ProtocolState>,
+
+    #[account(
+        constraint = protocol_state.is_operator(&operator.key())
+                  || operator.key() == protocol_state.authority
+                  @ WGBError::Unauthorized
+    )]
+    pub operator: Signer<'info>,
+
+    #[account(mut)]
+    pub buyer: Signer<'info>,
+
+    #[account(
+        mut,
+        token::mint = protocol_state.wgb_mint,
+        token::authority = buyer
+    )]
+    pub buyer_token_account: InterfaceAccount<'info, TokenAccount>,
+
+    #[account(
+        mut,
+        constraint = treasury.owner == expected_treasury_owner(&protocol_state),
+        token::mint = protocol_state.wgb_mint
+    )]
+    pub treasury: InterfaceAccount<'info, TokenAccount>,
+
+    /// Treasury owner — required to co-sign under TREASURY_OWNER_AUTHORITY
+    pub treasury_signer: Option<Signer<'info>>,
+
+    /// CHECK: Validated via protocol_state.sol_receiver
+    #[account(mut)]
+    pub sol_receiver: AccountInfo<'info>,
+
+    #[account(mut)]
+    pub wgb_mint: InterfaceAccount<'info, Mint>,
+
+    pub system_program: Program<'info, System>,
+    pub token_program: Program<'info, Token2022>,
+
+    // Optional Points
+    #[account(mut)]
+    pub user_profile: Option<Account<'info, UserProfile>>,
+
+    /// CHECK: TierConfig PDA — may not exist yet; loaded by load_tier_config
+    #[account(seeds = [b"tier_config"], bump)]
+    pub tier_config: UncheckedAccount<'info>,
+
+    /// CHECK: PointsConfig PDA — may not exist yet; loaded by load_points_config
+    #[account(seeds = [b"points_config"], bump)]
+    pub points_config: UncheckedAccount<'info>,
+
+    /// CHECK: KycAttestation PDA — only read when require_kyc is on; see ensure_kyc
+    #[account(seeds = [b"kyc_attestation", buyer.key().as_ref()], bump)]
+    pub kyc_attestation: UncheckedAccount<'info>,
+
+    /// CHECK: SolSplit PDA — may not exist yet; loaded by load_sol_split
+    #[account(seeds = [b"sol_split"], bump)]
+    pub sol_split: UncheckedAccount<'info>,
+
+    /// CHECK: System-owned PDA receiving insurance_fee_bps of each buy
+    #[account(mut, seeds = [b"insurance_fund"], bump)]
+    pub insurance_fund: UncheckedAccount<'info>,
+
+    /// CHECK: Instructions sysvar, read to identify a CPI caller
+    #[account(address = solana_instructions_sysvar::ID)]
+    pub instructions_sysvar: UncheckedAccount<'info>,
+
+    /// CHECK: CpiAllowlist PDA — may not exist yet; read by ensure_cpi_allowed
+    #[account(seeds = [b"cpi_allowlist"], bump)]
+    pub cpi_allowlist: UncheckedAccount<'info>,
+}
+
+#[derive(Accounts)]
+pub struct OnboardAndBuy<'info> {
+    #[account(
+        mut,
+        seeds = [b"protocol_state"],
+        bump = protocol_state.bump,
+        has_one = treasury,
+        has_one = sol_receiver
+    )]
+    pub protocol_state: Account<'info, ProtocolState>,
+
+    #[account(mut)]
+    pub buyer: Signer<'info>,
+
+    #[account(
+        mut,
+        token::mint = protocol_state.wgb_mint,
+        token::authority = buyer
+    )]
+    pub buyer_token_account: InterfaceAccount<'info, TokenAccount>,
+
+    #[account(
+        mut,
+        constraint = treasury.owner == expected_treasury_owner(&protocol_state),
+        token::mint = protocol_state.wgb_mint
+    )]
+    pub treasury: InterfaceAccount<'info, TokenAccount>,
+
+    /// Treasury owner — required to co-sign under TREASURY_OWNER_AUTHORITY
+    pub treasury_signer: Option<Signer<'info>>,
+
+    /// CHECK: Validated via protocol_state.sol_receiver
+    #[account(mut)]
+    pub sol_receiver: AccountInfo<'info>,
+
+    #[account(
+        mut,
+        constraint = wgb_mint.key() == protocol_state.wgb_mint
+    )]
+    pub wgb_mint: InterfaceAccount<'info, Mint>,
+
+    pub system_program: Program<'info, System>,
+    pub token_program: Program<'info, Token2022>,
+
+    // Created here — the buyer pays rent
+    #[account(
+        init,
+        payer = buyer,
+        space = 8 + 128,
+        seeds = [b"user_profile", buyer.key().as_ref()],
+        bump
+    )]
+    pub user_profile: Account<'info, UserProfile>,
+
+    /// CHECK: TierConfig PDA — may not exist yet; loaded by load_tier_config
+    #[account(seeds = [b"tier_config"], bump)]
+    pub tier_config: UncheckedAccount<'info>,
+
+    /// CHECK: PointsConfig PDA — may not exist yet; loaded by load_points_config
+    #[account(seeds = [b"points_config"], bump)]
+    pub points_config: UncheckedAccount<'info>,
+
+    /// CHECK: KycAttestation PDA — only read when require_kyc is on; see ensure_kyc
+    #[account(seeds = [b"kyc_attestation", buyer.key().as_ref()], bump)]
+    pub kyc_attestation: UncheckedAccount<'info>,
+
+    /// CHECK: SolSplit PDA — may not exist yet; loaded by load_sol_split
+    #[account(seeds = [b"sol_split"], bump)]
+    pub sol_split: UncheckedAccount<'info>,
+
+    /// CHECK: System-owned PDA receiving insurance_fee_bps of each buy
+    #[account(mut, seeds = [b"insurance_fund"], bump)]
+    pub insurance_fund: UncheckedAccount<'info>,
+
+    /// CHECK: Instructions sysvar, read to identify a CPI caller
+    #[account(address = solana_instructions_sysvar::ID)]
+    pub instructions_sysvar: UncheckedAccount<'info>,
+
+    /// CHECK: CpiAllowlist PDA — may not exist yet; read by ensure_cpi_allowed
+    #[account(seeds = [b"cpi_allowlist"], bump)]
+    pub cpi_allowlist: UncheckedAccount<'info>,
+}
+
+#[derive(Accounts)]
+pub struct TriggerBuyback<'info> {
+    #[account(mut, seeds = [b"protocol_state"], bump = protocol_state.bump, has_one = wgb_mint)]
+    pub protocol_state: Account<'info, ProtocolState>,
+
+    /// CHECK: System-owned PDA holding buyback SOL; only this program can sign for it
+    #[account(mut, seeds = [b"buyback_vault"], bump)]
+    pub buyback_vault: UncheckedAccount<'info>,
+
+    #[account(mut)]
+    pub seller: Signer<'info>,
+    #[account(
+        mut,
+        token::mint = wgb_mint,
+        token::authority = seller
+    )]
+    pub seller_token_account: InterfaceAccount<'info, TokenAccount>,
+    #[account(mut)]
+    pub wgb_mint: InterfaceAccount<'info, Mint>,
+
+    #[account(
+        constraint = protocol_state.is_operator(&operator.key())
+                  || operator.key() == protocol_state.authority
+                  @ WGBError::Unauthorized
+    )]
+    pub operator: Signer<'info>,
+
+    /// CHECK: WithdrawalThrottle PDA — may not exist yet; enforced by consume_withdrawal
+    #[account(mut, seeds = [b"withdrawal_throttle"], bump)]
+    pub withdrawal_throttle: UncheckedAccount<'info>,
+
+    pub system_program: Program<'info, System>,
+    pub token_program: Program<'info, Token2022>,
+}
+
+#[derive(Accounts)]
+pub struct InitUserProfile<'info> {
+    #[account(
+        init, 
+        payer = user, 
+        space = 8 + 128, 
+        seeds = [b"user_profile", user.key().as_ref()], 
+        bump
+    )]
+    pub user_profile: Account<'info, UserProfile>,
+    #[account(mut)]
+    pub user: Signer<'info>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+#[instruction(amount: u64, request_id: u64)]
+pub struct BurnWGB<'info> {
+    #[account(mut, seeds = [b"protocol_state"], bump = protocol_state.bump)]
+    pub protocol_state: Account<'info, ProtocolState>,
+    
+    #[account(mut)]
+    pub user: Signer<'info>,
+    #[account(
+        mut,
+        token::mint = wgb_mint,
+        token::authority = user
+    )]
+    pub user_token_account: InterfaceAccount<'info, TokenAccount>,
+    #[account(mut, constraint = wgb_mint.key() == protocol_state.wgb_mint @ WGBError::Unauthorized)]
+    pub wgb_mint: InterfaceAccount<'info, Mint>,
+    
+    #[account(
+        init,
+        payer = user,
+        space = 8 + 128,
+        seeds = [b"redemption", user.key().as_ref(), request_id.to_le_bytes().as_ref()],
+        bump
+    )]
+    pub redemption_request: Account<'info, RedemptionRequest>,
+    
+    pub system_program: Program<'info, System>,
+    pub token_program: Program<'info, Token2022>,
+    
+    #[account(mut)]
+    pub user_profile: Option<Account<'info, UserProfile>>,
+
+    /// CHECK: PointsConfig PDA — may not exist yet; loaded by load_points_config
+    #[account(seeds = [b"points_config"], bump)]
+    pub points_config: UncheckedAccount<'info>,
+
+    /// CHECK: RedemptionBlock PDA — its existence is what blocks the burn
+    #[account(seeds = [b"redemption_block", user.key().as_ref()], bump)]
+    pub redemption_block: UncheckedAccount<'info>,
+
+    /// CHECK: RedemptionStats PDA — counted once initialized; see bump_redemption_stat
+    #[account(mut, seeds = [b"redemption_stats"], bump)]
+    pub redemption_stats: UncheckedAccount<'info>,
+}
+
+#[derive(Accounts)]
+#[instruction(amount: u64, request_id: u64, serial_leaf: [u8; 32])]
+pub struct BurnWithSerial<'info> {
+    #[account(mut, seeds = [b"protocol_state"], bump = protocol_state.bump)]
+    pub protocol_state: Account<'info, ProtocolState>,
+
+    #[account(mut)]
+    pub user: Signer<'info>,
+    #[account(
+        mut,
+        token::mint = wgb_mint,
+        token::authority = user
+    )]
+    pub user_token_account: InterfaceAccount<'info, TokenAccount>,
+    #[account(mut, constraint = wgb_mint.key() == protocol_state.wgb_mint @ WGBError::Unauthorized)]
+    pub wgb_mint: InterfaceAccount<'info, Mint>,
+
+    #[account(
+        init,
+        payer = user,
+        space = 8 + 128,
+        seeds = [b"redemption", user.key().as_ref(), request_id.to_le_bytes().as_ref()],
+        bump
+    )]
+    pub redemption_request: Account<'info, RedemptionRequest>,
+
+    /// Existence of this marker is what stops the serial being assigned twice
+    #[account(
+        init_if_needed,
+        payer = user,
+        space = 8 + 32 + 32 + 8 + 8 + 1,
+        seeds = [b"redeemed_serial", serial_leaf.as_ref()],
+        bump
+    )]
+    pub redeemed_serial: Account<'info, RedeemedSerial>,
+
+    pub system_program: Program<'info, System>,
+    pub token_program: Program<'info, Token2022>,
+
+    #[account(mut)]
+    pub user_profile: Option<Account<'info, UserProfile>>,
+
+    /// CHECK: PointsConfig PDA — may not exist yet; loaded by load_points_config
+    #[account(seeds = [b"points_config"], bump)]
+    pub points_config: UncheckedAccount<'info>,
+
+    /// CHECK: RedemptionBlock PDA — its existence is what blocks the burn
+    #[account(seeds = [b"redemption_block", user.key().as_ref()], bump)]
+    pub redemption_block: UncheckedAccount<'info>,
+
+    /// CHECK: RedemptionStats PDA — counted once initialized; see bump_redemption_stat
+    #[account(mut, seeds = [b"redemption_stats"], bump)]
+    pub redemption_stats: UncheckedAccount<'info>,
+}
+
+#[derive(Accounts)]
+pub struct ReleaseSerial<'info> {
+    #[account(seeds = [b"protocol_state"], bump = protocol_state.bump)]
+    pub protocol_state: Account<'info, ProtocolState>,
+
+    #[account(
+        seeds = [b"redemption", redemption_request.user.as_ref(), redemption_request.request_id.to_le_bytes().as_ref()],
+        bump = redemption_request.bump,
+        constraint = redemption_request.status == 4 @ WGBError::InvalidRedemptionStatus
+    )]
+    pub redemption_request: Account<'info, RedemptionRequest>,
+
+    #[account(
+        mut,
+        seeds = [b"redeemed_serial", redeemed_serial.serial_leaf.as_ref()],
+        bump = redeemed_serial.bump,
+        constraint = redeemed_serial.user == redemption_request.user
+                  && redeemed_serial.request_id == redemption_request.request_id
+                  @ WGBError::InvalidRedemptionAccount,
+        close = user
+    )]
+    pub redeemed_serial: Account<'info, RedeemedSerial>,
+
+    /// CHECK: The redeemer's wallet — refunded the marker's rent
+    #[account(mut, address = redemption_request.user @ WGBError::Unauthorized)]
+    pub user: UncheckedAccount<'info>,
+
+    #[account(
+        constraint = protocol_state.is_operator(&signer.key())
+                  || signer.key() == protocol_state.authority
+                  @ WGBError::Unauthorized
+    )]
+    pub signer: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct RaisePriorityFee<'info> {
+    #[account(seeds = [b"protocol_state"], bump = protocol_state.bump)]
+    pub protocol_state: Account<'info, ProtocolState>,
+
+    #[account(
+        mut,
+        seeds = [b"redemption", redemption_request.user.as_ref(), redemption_request.request_id.to_le_bytes().as_ref()],
+        bump = redemption_request.bump,
+        has_one = user @ WGBError::Unauthorized,
+        constraint = redemption_request.status <= 1 @ WGBError::InvalidRedemptionStatus
+    )]
+    pub redemption_request: Account<'info, RedemptionRequest>,
+
+    #[account(mut)]
+    pub user: Signer<'info>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct AwardPoints<'info> {
+    #[account(seeds = [b"protocol_state"], bump = protocol_state.bump)]
+    pub protocol_state: Account<'info, ProtocolState>,
+    #[account(mut, seeds = [b"user_profile", user.key().as_ref()], bump = user_profile.bump)]
+    pub user_profile: Account<'info, UserProfile>,
+    /// CHECK: User only needed for seed derivation
+    pub user: UncheckedAccount<'info>,
+    
+    // Operator can award points
+    #[account(
+        constraint = protocol_state.is_operator(&operator.key())
+                  || operator.key() == protocol_state.authority
+    )]
+    pub operator: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct GetCirculating<'info> {
+    #[account(seeds = [b"protocol_state"], bump = protocol_state.bump, has_one = wgb_mint)]
+    pub protocol_state: Account<'info, ProtocolState>,
+    pub wgb_mint: InterfaceAccount<'info, Mint>,
+}
+
+#[derive(Accounts)]
+pub struct RecoverStranded<'info> {
+    #[account(seeds = [b"protocol_state"], bump = protocol_state.bump, has_one = authority)]
+    pub protocol_state: Account<'info, ProtocolState>,
+
+    #[account(
+        mint::token_program = token_program,
+        constraint = stranded_mint.key() != protocol_state.wgb_mint @ WGBError::CannotRecoverProtocolToken
+    )]
+    pub stranded_mint: InterfaceAccount<'info, Mint>,
+
+    /// Token account holding the stranded tokens, owned by the protocol PDA
+    #[account(
+        mut,
+        token::mint = stranded_mint,
+        token::authority = protocol_state,
+        token::token_program = token_program
+    )]
+    pub source: InterfaceAccount<'info, TokenAccount>,
+
+    #[account(
+        mut,
+        token::mint = stranded_mint,
+        token::token_program = token_program
+    )]
+    pub destination: InterfaceAccount<'info, TokenAccount>,
+
+    /// CHECK: WithdrawalThrottle PDA — may not exist yet; enforced by consume_withdrawal
+    #[account(mut, seeds = [b"withdrawal_throttle"], bump)]
+    pub withdrawal_throttle: UncheckedAccount<'info>,
+
+    pub authority: Signer<'info>,
+    pub token_program: Interface<'info, TokenInterface>,
+}
+
+#[derive(Accounts)]
+pub struct UpdateMetadata<'info> {
+    #[account(seeds = [b"protocol_state"], bump = protocol_state.bump, has_one = authority, has_one = wgb_mint)]
+    pub protocol_state: Account<'info, ProtocolState>,
+    /// Metadata lives in the mint itself (metadata pointer → mint)
+    #[account(mut)]
+    pub wgb_mint: InterfaceAccount<'info, Mint>,
+    #[account(mut)]
+    pub authority: Signer<'info>,
+    pub token_program: Program<'info, Token2022>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct EmitProfile<'info> {
+    #[account(seeds = [b"user_profile", user.key().as_ref()], bump = user_profile.bump)]
+    pub user_profile: Account<'info, UserProfile>,
+    /// CHECK: User only needed for seed derivation
+    pub user: UncheckedAccount<'info>,
+}
+
+#[derive(Accounts)]
+pub struct GetGoldEquivalent<'info> {
+    #[account(seeds = [b"protocol_state"], bump = protocol_state.bump)]
+    pub protocol_state: Account<'info, ProtocolState>,
+}
+
+#[derive(Accounts)]
+pub struct VerifyMintAuthority<'info> {
+    #[account(seeds = [b"protocol_state"], bump = protocol_state.bump, has_one = wgb_mint)]
+    pub protocol_state: Account<'info, ProtocolState>,
+    pub wgb_mint: InterfaceAccount<'info, Mint>,
+}
+
+#[derive(Accounts)]
+pub struct CanMint<'info> {
+    #[account(seeds = [b"protocol_state"], bump = protocol_state.bump)]
+    pub protocol_state: Account<'info, ProtocolState>,
+}
+
+#[derive(Accounts)]
+pub struct ReconcileSupply<'info> {
+    #[account(
+        mut,
+        seeds = [b"protocol_state"],
+        bump = protocol_state.bump,
+        has_one = authority,
+        has_one = wgb_mint
+    )]
+    pub protocol_state: Account<'info, ProtocolState>,
+    pub wgb_mint: InterfaceAccount<'info, Mint>,
+    pub authority: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct SetPointsConfig<'info> {
+    #[account(seeds = [b"protocol_state"], bump = protocol_state.bump, has_one = authority)]
+    pub protocol_state: Account<'info, ProtocolState>,
+    #[account(
+        init_if_needed,
+        payer = authority,
+        space = 8 + PointsConfig::SPACE,
+        seeds = [b"points_config"],
+        bump
+    )]
+    pub points_config: Account<'info, PointsConfig>,
+    #[account(mut)]
+    pub authority: Signer<'info>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct InitTierConfig<'info> {
+    #[account(seeds = [b"protocol_state"], bump = protocol_state.bump, has_one = authority)]
+    pub protocol_state: Account<'info, ProtocolState>,
+    #[account(
+        init,
+        payer = authority,
+        space = 8 + TierConfig::SPACE,
+        seeds = [b"tier_config"],
+        bump
+    )]
+    pub tier_config: Account<'info, TierConfig>,
+    #[account(mut)]
+    pub authority: Signer<'info>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct UpdateTierConfig<'info> {
+    #[account(seeds = [b"protocol_state"], bump = protocol_state.bump, has_one = authority)]
+    pub protocol_state: Account<'info, ProtocolState>,
+    #[account(mut, seeds = [b"tier_config"], bump = tier_config.bump)]
+    pub tier_config: Account<'info, TierConfig>,
+    pub authority: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct TransferPoints<'info> {
+    #[account(seeds = [b"protocol_state"], bump = protocol_state.bump)]
+    pub protocol_state: Account<'info, ProtocolState>,
+    #[account(mut, seeds = [b"user_profile", sender.key().as_ref()], bump = sender_profile.bump)]
+    pub sender_profile: Account<'info, UserProfile>,
+    #[account(mut, seeds = [b"user_profile", recipient.key().as_ref()], bump = recipient_profile.bump)]
+    pub recipient_profile: Account<'info, UserProfile>,
+    /// CHECK: Recipient only needed for seed derivation
+    pub recipient: UncheckedAccount<'info>,
+    pub sender: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct MigrateProfile<'info> {
+    #[account(
+        mut,
+        seeds = [b"user_profile", user.key().as_ref()],
+        bump = old_profile.bump,
+        close = user
+    )]
+    pub old_profile: Account<'info, UserProfile>,
+    // Created for the new wallet unless an empty profile already exists there
+    #[account(
+        init_if_needed,
+        payer = user,
+        space = 8 + 128,
+        seeds = [b"user_profile", new_user.key().as_ref()],
+        bump
+    )]
+    pub new_profile: Account<'info, UserProfile>,
+    #[account(mut)]
+    pub user: Signer<'info>,
+    /// The receiving wallet co-signs, so standing can't be pushed onto a wallet without its consent
+    pub new_user: Signer<'info>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct ClaimAirdrop<'info> {
+    #[account(seeds = [b"protocol_state"], bump = protocol_state.bump)]
+    pub protocol_state: Account<'info, ProtocolState>,
+    #[account(mut, seeds = [b"user_profile", user.key().as_ref()], bump = user_profile.bump)]
+    pub user_profile: Account<'info, UserProfile>,
+    /// Existence of this marker is what prevents a second claim under the same root
+    #[account(
+        init,
+        payer = user,
+        space = 8 + 32 + 32 + 8 + 8 + 1,
+        seeds = [b"airdrop_claim", protocol_state.airdrop_root.as_ref(), user.key().as_ref()],
+        bump
+    )]
+    pub airdrop_claim: Account<'info, AirdropClaim>,
+    #[account(mut)]
+    pub user: Signer<'info>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+#[instruction(referrer: Pubkey)]
+pub struct RegisterReferral<'info> {
+    #[account(seeds = [b"protocol_state"], bump = protocol_state.bump)]
+    pub protocol_state: Account<'info, ProtocolState>,
+    #[account(mut, seeds = [b"user_profile", referrer.as_ref()], bump = referrer_profile.bump)]
+    pub referrer_profile: Account<'info, UserProfile>,
+    /// One referral per user — init fails if the signer was already referred
+    #[account(
+        init,
+        payer = user,
+        space = 8 + 32 + 32 + 8 + 8 + 1,
+        seeds = [b"referral", user.key().as_ref()],
+        bump
+    )]
+    pub referral: Account<'info, Referral>,
+    /// The referee's own profile — must show a purchase before the referral pays out
+    #[account(seeds = [b"user_profile", user.key().as_ref()], bump = user_profile.bump)]
+    pub user_profile: Account<'info, UserProfile>,
+    /// CHECK: PointsConfig PDA — may not exist yet; loaded by load_points_config
+    #[account(seeds = [b"points_config"], bump)]
+    pub points_config: UncheckedAccount<'info>,
+    #[account(mut)]
+    pub user: Signer<'info>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+#[instruction(user: Pubkey)]
+pub struct SetComplianceLock<'info> {
+    #[account(seeds = [b"protocol_state"], bump = protocol_state.bump, has_one = authority)]
+    pub protocol_state: Account<'info, ProtocolState>,
+    #[account(mut, seeds = [b"user_profile", user.as_ref()], bump = user_profile.bump)]
+    pub user_profile: Account<'info, UserProfile>,
+    pub authority: Signer<'info>,
+}
+
+#[derive(Accounts)]
+#[instruction(user: Pubkey)]
+pub struct BlockRedemptions<'info> {
+    #[account(seeds = [b"protocol_state"], bump = protocol_state.bump, has_one = authority)]
+    pub protocol_state: Account<'info, ProtocolState>,
+    #[account(
+        init,
+        payer = authority,
+        space = 8 + 32 + 8 + 1,
+        seeds = [b"redemption_block", user.as_ref()],
+        bump
+    )]
+    pub redemption_block: Account<'info, RedemptionBlock>,
+    #[account(mut)]
+    pub authority: Signer<'info>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+#[instruction(user: Pubkey)]
+pub struct AttestKyc<'info> {
+    #[account(seeds = [b"protocol_state"], bump = protocol_state.bump)]
+    pub protocol_state: Account<'info, ProtocolState>,
+    #[account(
+        init_if_needed,
+        payer = attester,
+        space = 8 + 32 + 1 + 8 + 8 + 1,
+        seeds = [b"kyc_attestation", user.as_ref()],
+        bump
+    )]
+    pub kyc_attestation: Account<'info, KycAttestation>,
+    #[account(
+        mut,
+        constraint = protocol_state.is_operator(&attester.key())
+                  || attester.key() == protocol_state.authority
+                  @ WGBError::Unauthorized
+    )]
+    pub attester: Signer<'info>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+#[instruction(user: Pubkey)]
+pub struct UnblockRedemptions<'info> {
+    #[account(seeds = [b"protocol_state"], bump = protocol_state.bump, has_one = authority)]
+    pub protocol_state: Account<'info, ProtocolState>,
+    #[account(
+        mut,
+        close = authority,
+        seeds = [b"redemption_block", user.as_ref()],
+        bump = redemption_block.bump
+    )]
+    pub redemption_block: Account<'info, RedemptionBlock>,
+    #[account(mut)]
+    pub authority: Signer<'info>,
+}
+
+// ==================== P2P FULFILLMENT CONTEXTS ====================
+
+#[derive(Accounts)]
+pub struct ClaimRedemption<'info> {
+    #[account(seeds = [b"protocol_state"], bump = protocol_state.bump)]
+    pub protocol_state: Account<'info, ProtocolState>,
+
+    #[account(
+        mut,
+        seeds = [b"redemption", redemption_request.user.as_ref(), redemption_request.request_id.to_le_bytes().as_ref()],
+        bump = redemption_request.bump,
+        constraint = redemption_request.status == 0 @ WGBError::InvalidRedemptionStatus
+    )]
+    pub redemption_request: Account<'info, RedemptionRequest>,
+
+    /// The fulfiller claiming this order
+    #[account(mut)]
+    pub fulfiller: Signer<'info>,
+
+    /// CHECK: Fulfiller's profile PDA — tracks active_claims; loaded by take_claim_slot
+    #[account(mut, seeds = [b"user_profile", fulfiller.key().as_ref()], bump)]
+    pub fulfiller_profile: UncheckedAccount<'info>,
+
+    /// CHECK: RedemptionStats PDA — counted once initialized; see bump_redemption_stat
+    #[account(mut, seeds = [b"redemption_stats"], bump)]
+    pub redemption_stats: UncheckedAccount<'info>,
+}
+
+#[derive(Accounts)]
+pub struct ClaimRedemptionBatch<'info> {
+    #[account(seeds = [b"protocol_state"], bump = protocol_state.bump)]
+    pub protocol_state: Account<'info, ProtocolState>,
+
+    /// The fulfiller claiming every order in remaining_accounts
+    #[account(mut)]
+    pub fulfiller: Signer<'info>,
+
+    /// CHECK: Fulfiller's profile PDA — tracks active_claims; loaded by take_claim_slot
+    #[account(mut, seeds = [b"user_profile", fulfiller.key().as_ref()], bump)]
+    pub fulfiller_profile: UncheckedAccount<'info>,
+
+    /// CHECK: RedemptionStats PDA — counted once initialized; see bump_redemption_stat
+    #[account(mut, seeds = [b"redemption_stats"], bump)]
+    pub redemption_stats: UncheckedAccount<'info>,
+}
+
+#[derive(Accounts)]
+pub struct ConfirmDelivery<'info> {
+    #[account(
+        mut,
+        seeds = [b"protocol_state"],
+        bump = protocol_state.bump,
+    )]
+    pub protocol_state: Account<'info, ProtocolState>,
+
+    #[account(
+        mut,
+        seeds = [b"redemption", redemption_request.user.as_ref(), redemption_request.request_id.to_le_bytes().as_ref()],
+        bump = redemption_request.bump,
+    )]
+    pub redemption_request: Account<'info, RedemptionRequest>,
+
+    /// CHECK: Fulfiller's profile PDA (if any) — credited the reward and freed a claim slot
+    #[account(mut, seeds = [b"user_profile", redemption_request.fulfiller.as_ref()], bump)]
+    pub fulfiller_profile: UncheckedAccount<'info>,
+
+    /// Admin, Operator, or the redeemer (role checked in the instruction body)
+    #[account(mut)]
+    pub signer: Signer<'info>,
+
+    /// CHECK: PointsConfig PDA — may not exist yet; loaded by load_points_config
+    #[account(seeds = [b"points_config"], bump)]
+    pub points_config: UncheckedAccount<'info>,
+
+    /// CHECK: RedemptionStats PDA — counted once initialized; see bump_redemption_stat
+    #[account(mut, seeds = [b"redemption_stats"], bump)]
+    pub redemption_stats: UncheckedAccount<'info>,
+
+    #[account(
+        init_if_needed,
+        payer = signer,
+        space = 8 + 32 + 32 + 32 + 8 + 1,
+        seeds = [b"delivery_proof", redemption_request.key().as_ref()],
+        bump
+    )]
+    pub delivery_proof: Account<'info, DeliveryProof>,
+
+    #[account(
+        init_if_needed,
+        payer = signer,
+        space = 8 + 32 + 8 + 1,
+        seeds = [b"held_reward", redemption_request.key().as_ref()],
+        bump
+    )]
+    pub held_reward: Account<'info, HeldReward>,
+
+    /// CHECK: FulfillerContributions PDA — may not exist yet; read by ensure_multi_fulfilled
+    #[account(seeds = [b"fulfiller_contributions", redemption_request.key().as_ref()], bump)]
+    pub fulfiller_contributions: UncheckedAccount<'info>,
+
+    /// CHECK: The order's fulfiller — receives the escrowed priority fee
+    #[account(mut, address = redemption_request.fulfiller @ WGBError::Unauthorized)]
+    pub fulfiller: UncheckedAccount<'info>,
+
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct LockQuote<'info> {
+    #[account(seeds = [b"protocol_state"], bump = protocol_state.bump)]
+    pub protocol_state: Account<'info, ProtocolState>,
+    #[account(
+        init_if_needed,
+        payer = buyer,
+        space = 8 + QuoteLock::SPACE,
+        seeds = [b"quote_lock", buyer.key().as_ref()],
+        bump
+    )]
+    pub quote_lock: Account<'info, QuoteLock>,
+    #[account(mut)]
+    pub buyer: Signer<'info>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct RecordContribution<'info> {
+    #[account(seeds = [b"protocol_state"], bump = protocol_state.bump)]
+    pub protocol_state: Account<'info, ProtocolState>,
+
+    #[account(
+        seeds = [b"redemption", redemption_request.user.as_ref(), redemption_request.request_id.to_le_bytes().as_ref()],
+        bump = redemption_request.bump,
+        constraint = redemption_request.status == 1 || redemption_request.status == 2
+            @ WGBError::InvalidRedemptionStatus
+    )]
+    pub redemption_request: Account<'info, RedemptionRequest>,
+
+    #[account(
+        init_if_needed,
+        payer = fulfiller,
+        space = 8 + FulfillerContributions::SPACE,
+        seeds = [b"fulfiller_contributions", redemption_request.key().as_ref()],
+        bump
+    )]
+    pub fulfiller_contributions: Account<'info, FulfillerContributions>,
+
+    #[account(mut)]
+    pub fulfiller: Signer<'info>,
+
+    /// Contributors must hold a profile, so failed deliveries can be slashed against it
+    #[account(seeds = [b"user_profile", fulfiller.key().as_ref()], bump = fulfiller_profile.bump)]
+    pub fulfiller_profile: Account<'info, UserProfile>,
+
+    /// The claiming fulfiller, an operator or the admin (checked in the instruction body)
+    pub approver: Signer<'info>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct MarkShipped<'info> {
+    #[account(
+        mut,
+        seeds = [b"redemption", redemption_request.user.as_ref(), redemption_request.request_id.to_le_bytes().as_ref()],
+        bump = redemption_request.bump,
+        has_one = fulfiller @ WGBError::Unauthorized,
+        constraint = redemption_request.status == 1 @ WGBError::InvalidRedemptionStatus
+    )]
+    pub redemption_request: Account<'info, RedemptionRequest>,
+
+    #[account(mut)]
+    pub fulfiller: Signer<'info>,
+
+    /// CHECK: RedemptionStats PDA — counted once initialized; see bump_redemption_stat
+    #[account(mut, seeds = [b"redemption_stats"], bump)]
+    pub redemption_stats: UncheckedAccount<'info>,
+
+    #[account(
+        init_if_needed,
+        payer = fulfiller,
+        space = 8 + 32 + 32 + 32 + 8 + 1,
+        seeds = [b"delivery_proof", redemption_request.key().as_ref()],
+        bump
+    )]
+    pub delivery_proof: Account<'info, DeliveryProof>,
+
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct DisputeDelivery<'info> {
+    #[account(mut, seeds = [b"protocol_state"], bump = protocol_state.bump)]
+    pub protocol_state: Account<'info, ProtocolState>,
+
+    #[account(
+        mut,
+        seeds = [b"redemption", redemption_request.user.as_ref(), redemption_request.request_id.to_le_bytes().as_ref()],
+        bump = redemption_request.bump,
+        has_one = user @ WGBError::Unauthorized,
+        constraint = redemption_request.status == 2 || redemption_request.status == 3
+                  @ WGBError::InvalidRedemptionStatus
+    )]
+    pub redemption_request: Account<'info, RedemptionRequest>,
+
+    pub user: Signer<'info>,
+
+    /// CHECK: Fulfiller's profile PDA (if any) — retakes a claim slot when a confirmed order is disputed
+    #[account(mut, seeds = [b"user_profile", redemption_request.fulfiller.as_ref()], bump)]
+    pub fulfiller_profile: UncheckedAccount<'info>,
+
+    /// CHECK: HeldReward PDA — forfeited on a post-confirm dispute; see take_held_reward
+    #[account(mut, seeds = [b"held_reward", redemption_request.key().as_ref()], bump)]
+    pub held_reward: UncheckedAccount<'info>,
+
+    /// CHECK: RedemptionStats PDA — counted once initialized; see bump_redemption_stat
+    #[account(mut, seeds = [b"redemption_stats"], bump)]
+    pub redemption_stats: UncheckedAccount<'info>,
+}
+
+#[derive(Accounts)]
+pub struct AmendRedemption<'info> {
+    #[account(
+        seeds = [b"redemption", redemption_request.user.as_ref(), redemption_request.request_id.to_le_bytes().as_ref()],
+        bump = redemption_request.bump,
+        has_one = user @ WGBError::Unauthorized
+    )]
+    pub redemption_request: Account<'info, RedemptionRequest>,
+
+    #[account(
+        init_if_needed,
+        payer = user,
+        space = 8 + 32 + 32 + 8 + 1,
+        seeds = [b"redemption_address", redemption_request.key().as_ref()],
+        bump
+    )]
+    pub redemption_address: Account<'info, RedemptionAddress>,
+
+    #[account(mut)]
+    pub user: Signer<'info>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct ReleaseFulfillerRewards<'info> {
+    #[account(mut, seeds = [b"protocol_state"], bump = protocol_state.bump)]
+    pub protocol_state: Account<'info, ProtocolState>,
+
+    #[account(
+        mut,
+        seeds = [b"redemption", redemption_request.user.as_ref(), redemption_request.request_id.to_le_bytes().as_ref()],
+        bump = redemption_request.bump,
+        constraint = redemption_request.status == 3 && redemption_request.held_reward > 0
+                  @ WGBError::InvalidRedemptionStatus
+    )]
+    pub redemption_request: Account<'info, RedemptionRequest>,
+
+    #[account(
+        mut,
+        seeds = [b"user_profile", redemption_request.fulfiller.as_ref()],
+        bump = fulfiller_profile.bump
+    )]
+    pub fulfiller_profile: Account<'info, UserProfile>,
+
+    /// CHECK: HeldReward PDA — absent for rewards held before it existed; see take_held_reward
+    #[account(mut, seeds = [b"held_reward", redemption_request.key().as_ref()], bump)]
+    pub held_reward: UncheckedAccount<'info>,
+}
+
+#[derive(Accounts)]
+pub struct AutoConfirm<'info> {
+    #[account(mut, seeds = [b"protocol_state"], bump = protocol_state.bump)]
+    pub protocol_state: Account<'info, ProtocolState>,
+
+    #[account(
+        mut,
+        seeds = [b"redemption", redemption_request.user.as_ref(), redemption_request.request_id.to_le_bytes().as_ref()],
+        bump = redemption_request.bump,
+        constraint = redemption_request.status == 2 @ WGBError::InvalidRedemptionStatus
+    )]
+    pub redemption_request: Account<'info, RedemptionRequest>,
+
+    /// CHECK: Fulfiller's profile PDA (if any) — credited the reward and freed a claim slot
+    #[account(mut, seeds = [b"user_profile", redemption_request.fulfiller.as_ref()], bump)]
+    pub fulfiller_profile: UncheckedAccount<'info>,
+
+    /// CHECK: PointsConfig PDA — may not exist yet; loaded by load_points_config
+    #[account(seeds = [b"points_config"], bump)]
+    pub points_config: UncheckedAccount<'info>,
+
+    /// CHECK: RedemptionStats PDA — counted once initialized; see bump_redemption_stat
+    #[account(mut, seeds = [b"redemption_stats"], bump)]
+    pub redemption_stats: UncheckedAccount<'info>,
+
+    /// CHECK: FulfillerContributions PDA — may not exist yet; read by ensure_multi_fulfilled
+    #[account(seeds = [b"fulfiller_contributions", redemption_request.key().as_ref()], bump)]
+    pub fulfiller_contributions: UncheckedAccount<'info>,
+
+    /// CHECK: The order's fulfiller — receives the escrowed priority fee
+    #[account(mut, address = redemption_request.fulfiller @ WGBError::Unauthorized)]
+    pub fulfiller: UncheckedAccount<'info>,
+
+    #[account(
+        init_if_needed,
+        payer = payer,
+        space = 8 + 32 + 8 + 1,
+        seeds = [b"held_reward", redemption_request.key().as_ref()],
+        bump
+    )]
+    pub held_reward: Account<'info, HeldReward>,
+
+    /// Whoever cranks the confirmation; pays for the HeldReward account
+    #[account(mut)]
+    pub payer: Signer<'info>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+#[instruction(serial_leaf: [u8; 32])]
+pub struct RecordSerialRedemption<'info> {
+    #[account(seeds = [b"protocol_state"], bump = protocol_state.bump)]
+    pub protocol_state: Account<'info, ProtocolState>,
+
+    #[account(
+        mut,
+        seeds = [b"redemption", redemption_request.user.as_ref(), redemption_request.request_id.to_le_bytes().as_ref()],
+        bump = redemption_request.bump,
+        constraint = redemption_request.status == 3 @ WGBError::InvalidRedemptionStatus
+    )]
+    pub redemption_request: Account<'info, RedemptionRequest>,
+
+    /// Existence of this marker is what stops the serial being redeemed again
+    #[account(
+        init,
+        payer = signer,
+        space = 8 + 32 + 32 + 8 + 8 + 1,
+        seeds = [b"redeemed_serial", serial_leaf.as_ref()],
+        bump
+    )]
+    pub redeemed_serial: Account<'info, RedeemedSerial>,
+
+    #[account(
+        mut,
+        constraint = protocol_state.is_operator(&signer.key())
+                  || signer.key() == protocol_state.authority
+                  @ WGBError::Unauthorized
+    )]
+    pub signer: Signer<'info>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct InitRedemptionStats<'info> {
+    #[account(seeds = [b"protocol_state"], bump = protocol_state.bump, has_one = authority)]
+    pub protocol_state: Account<'info, ProtocolState>,
+    #[account(
+        init,
+        payer = authority,
+        space = 8 + RedemptionStats::SPACE,
+        seeds = [b"redemption_stats"],
+        bump
+    )]
+    pub redemption_stats: Account<'info, RedemptionStats>,
+    #[account(mut)]
+    pub authority: Signer<'info>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct GetRedemptionStats<'info> {
+    #[account(seeds = [b"redemption_stats"], bump = redemption_stats.bump)]
+    pub redemption_stats: Account<'info, RedemptionStats>,
+}
+
+#[derive(Accounts)]
+#[instruction(serial_leaf: [u8; 32])]
+pub struct CheckSerialRedeemed<'info> {
+    /// CHECK: RedeemedSerial PDA — absent when the serial is still available
+    #[account(seeds = [b"redeemed_serial", serial_leaf.as_ref()], bump)]
+    pub redeemed_serial: UncheckedAccount<'info>,
+}
+
+#[derive(Accounts)]
+pub struct ExpireClaim<'info> {
+    #[account(seeds = [b"protocol_state"], bump = protocol_state.bump)]
+    pub protocol_state: Account<'info, ProtocolState>,
+
+    #[account(
+        mut,
+        seeds = [b"redemption", redemption_request.user.as_ref(), redemption_request.request_id.to_le_bytes().as_ref()],
+        bump = redemption_request.bump,
+        constraint = redemption_request.status == 1 @ WGBError::InvalidRedemptionStatus
+    )]
+    pub redemption_request: Account<'info, RedemptionRequest>,
+
+    /// CHECK: Fulfiller's profile PDA — may not exist; deserialized in the body when it does
+    #[account(mut, seeds = [b"user_profile", redemption_request.fulfiller.as_ref()], bump)]
+    pub fulfiller_profile: UncheckedAccount<'info>,
+
+    /// CHECK: RedemptionStats PDA — counted once initialized; see bump_redemption_stat
+    #[account(mut, seeds = [b"redemption_stats"], bump)]
+    pub redemption_stats: UncheckedAccount<'info>,
+}
+
+#[derive(Accounts)]
+pub struct ExpirePending<'info> {
+    #[account(
+        mut,
+        seeds = [b"protocol_state"],
+        bump = protocol_state.bump,
+        has_one = wgb_mint
+    )]
+    pub protocol_state: Account<'info, ProtocolState>,
+
+    #[account(
+        mut,
+        seeds = [b"redemption", redemption_request.user.as_ref(), redemption_request.request_id.to_le_bytes().as_ref()],
+        bump = redemption_request.bump,
+        constraint = redemption_request.status == 0 @ WGBError::InvalidRedemptionStatus
+    )]
+    pub redemption_request: Account<'info, RedemptionRequest>,
+
+    #[account(mut)]
+    pub wgb_mint: InterfaceAccount<'info, Mint>,
+
+    /// Refund destination — must belong to the redeemer
+    #[account(
+        mut,
+        token::mint = protocol_state.wgb_mint,
+        constraint = user_token_account.owner == redemption_request.user @ WGBError::Unauthorized
+    )]
+    pub user_token_account: InterfaceAccount<'info, TokenAccount>,
+
+    /// CHECK: Redeemer's profile PDA (if any) — burn points are clawed back from it
+    #[account(mut, seeds = [b"user_profile", redemption_request.user.as_ref()], bump)]
+    pub user_profile: UncheckedAccount<'info>,
+
+    /// CHECK: PointsConfig PDA — may not exist yet; loaded by load_points_config
+    #[account(seeds = [b"points_config"], bump)]
+    pub points_config: UncheckedAccount<'info>,
+
+    /// CHECK: RedemptionStats PDA — counted once initialized; see bump_redemption_stat
+    #[account(mut, seeds = [b"redemption_stats"], bump)]
+    pub redemption_stats: UncheckedAccount<'info>,
+
+    /// Anyone may crank an expired order
+    pub caller: Signer<'info>,
+
+    /// CHECK: The redeemer's wallet — refunded any escrowed priority fee
+    #[account(mut, address = redemption_request.user @ WGBError::Unauthorized)]
+    pub user: UncheckedAccount<'info>,
+
+    pub token_program: Program<'info, Token2022>,
+}
+
+#[derive(Accounts)]
+pub struct AdminSetStatus<'info> {
+    #[account(seeds = [b"protocol_state"], bump = protocol_state.bump, has_one = authority)]
+    pub protocol_state: Account<'info, ProtocolState>,
+
+    #[account(
+        mut,
+        seeds = [b"redemption", redemption_request.user.as_ref(), redemption_request.request_id.to_le_bytes().as_ref()],
+        bump = redemption_request.bump,
+    )]
+    pub redemption_request: Account<'info, RedemptionRequest>,
+
+    /// CHECK: Fulfiller's profile PDA (if any) — frees their claim slot, slashed on a lost dispute
+    #[account(mut, seeds = [b"user_profile", redemption_request.fulfiller.as_ref()], bump)]
+    pub fulfiller_profile: UncheckedAccount<'info>,
+
+    pub authority: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct CancelRedemption<'info> {
+    #[account(
+        mut,
+        seeds = [b"protocol_state"],
+        bump = protocol_state.bump,
+        has_one = authority,
+        has_one = wgb_mint
+    )]
+    pub protocol_state: Account<'info, ProtocolState>,
+
+    #[account(
+        mut,
+        seeds = [b"redemption", redemption_request.user.as_ref(), redemption_request.request_id.to_le_bytes().as_ref()],
+        bump = redemption_request.bump,
+    )]
+    pub redemption_request: Account<'info, RedemptionRequest>,
+
+    #[account(mut)]
+    pub wgb_mint: InterfaceAccount<'info, Mint>,
+
+    /// Refund destination — must belong to the redeemer
+    #[account(
+        mut,
+        token::mint = protocol_state.wgb_mint,
+        constraint = user_token_account.owner == redemption_request.user @ WGBError::Unauthorized
+    )]
+    pub user_token_account: InterfaceAccount<'info, TokenAccount>,
+
+    pub token_program: Program<'info, Token2022>,
+
+    /// CHECK: Fulfiller's profile PDA (if any) — frees their claim slot, slashed on a lost dispute
+    #[account(mut, seeds = [b"user_profile", redemption_request.fulfiller.as_ref()], bump)]
+    pub fulfiller_profile: UncheckedAccount<'info>,
+
+    /// Only admin can cancel
+    pub authority: Signer<'info>,
+
+    /// CHECK: The redeemer's wallet — refunded any escrowed priority fee
+    #[account(mut, address = redemption_request.user @ WGBError::Unauthorized)]
+    pub user: UncheckedAccount<'info>,
+
+    /// CHECK: RedemptionStats PDA — counted once initialized; see bump_redemption_stat
+    #[account(mut, seeds = [b"redemption_stats"], bump)]
+    pub redemption_stats: UncheckedAccount<'info>,
+}
+
+#[derive(Accounts)]
+pub struct CancelOwnRedemption<'info> {
+    #[account(
+        mut,
+        seeds = [b"protocol_state"],
+        bump = protocol_state.bump,
+        has_one = wgb_mint
+    )]
+    pub protocol_state: Account<'info, ProtocolState>,
+
+    #[account(
+        mut,
+        seeds = [b"redemption", redemption_request.user.as_ref(), redemption_request.request_id.to_le_bytes().as_ref()],
+        bump = redemption_request.bump,
+        has_one = user @ WGBError::Unauthorized,
+        constraint = redemption_request.status == 0 @ WGBError::InvalidRedemptionStatus
+    )]
+    pub redemption_request: Account<'info, RedemptionRequest>,
+
+    #[account(mut)]
+    pub wgb_mint: InterfaceAccount<'info, Mint>,
+
+    #[account(
+        mut,
+        token::mint = protocol_state.wgb_mint,
+        token::authority = user
+    )]
+    pub user_token_account: InterfaceAccount<'info, TokenAccount>,
+
+    #[account(mut)]
+    pub user: Signer<'info>,
+
+    /// CHECK: Redeemer's profile PDA (if any) — burn points are clawed back from it
+    #[account(mut, seeds = [b"user_profile", user.key().as_ref()], bump)]
+    pub user_profile: UncheckedAccount<'info>,
+
+    /// CHECK: PointsConfig PDA — may not exist yet; loaded by load_points_config
+    #[account(seeds = [b"points_config"], bump)]
+    pub points_config: UncheckedAccount<'info>,
+
+    /// CHECK: RedemptionStats PDA — counted once initialized; see bump_redemption_stat
+    #[account(mut, seeds = [b"redemption_stats"], bump)]
+    pub redemption_stats: UncheckedAccount<'info>,
+
+    pub token_program: Program<'info, Token2022>,
+}
+
+// ==================== EVENTS & ERRORS ====================
+
+#[event]
+pub struct LegacyStatsImported {
+    pub legacy_state: Pubkey,
+    pub total_minted: u64,
+    pub total_burned: u64,
+    pub actor: Pubkey, // Signer that triggered it
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct OracleProofSubmitted {
+    pub oracle: Pubkey,
+    pub merkle_root: [u8; 32],
+    pub reserves: u64,
+    pub attested_at: i64,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct MerkleRootUpdated {
+    pub root: [u8; 32],
+    pub total_serials: u64,
+    pub lineage_hash: [u8; 32], // Lineage after this root — a commitment verify_root_lineage accepts
+    pub actor: Pubkey, // Signer that triggered it
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct ProofHistoryView {
+    pub entries: Vec<ProofRecord>, // Oldest first, at most PROOF_HISTORY_LEN
+    pub total_recorded: u64,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct CommitmentAnchored {
+    pub lineage_hash: [u8; 32],
+    pub merkle_root: [u8; 32],
+    pub proven_reserves: u64,
+    pub actor: Pubkey, // Signer that triggered it
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct LineageVerified {
+    pub historical_commitment: [u8; 32],
+    pub lineage_hash: [u8; 32],
+    pub merkle_root: [u8; 32],
+    pub roots_replayed: u32,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct ReservesAdjusted {
+    pub delta: i64,            // Requested change, before clamping
+    pub old_reserves: u64,
+    pub new_reserves: u64,
+    pub reason_hash: [u8; 32], // Commitment to the off-chain reason
+    pub actor: Pubkey,         // Signer that triggered it
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct ReserveWeightAttested {
+    pub reserve_weight: u64,
+    pub backing_reserves: u64, // Tokens the reserves back under the active reserve_unit
+    pub actor: Pubkey, // Signer that triggered it
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct BackupOperatorUsed {
+    pub backup_operator: Pubkey,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct OperatorChanged {
+    pub previous_operator: Pubkey,
+    pub new_operator: Pubkey,
+    pub actor: Pubkey, // Signer that triggered it
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct BackupOperatorPromoted {
+    pub previous_operator: Pubkey,
+    pub new_operator: Pubkey,
+    pub actor: Pubkey, // Signer that triggered it
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct ProofSubmitted {
+    pub merkle_root: [u8; 32],
+    pub claimed_reserves: u64,
+    pub proof_hash: Vec<u8>,
+    pub actor: Pubkey, // Signer that triggered it
+    pub timestamp: i64,
+    pub rebate_lamports: u64, // Paid to the operator for this attestation (0 if none)
+}
+
+#[event]
+pub struct TokensMinted {
+    pub amount: u64,
+    pub new_total_supply: u64,
+    pub actor: Pubkey, // Signer that triggered it
+    pub timestamp: i64,
+    pub decimals: u8,  // Mint decimals — amounts are in base units
+}
+
+#[event]
+pub struct TokensPurchased {
+    pub buyer: Pubkey,
+    pub amount: u64,
+    pub lamports_paid: u64,
+    pub timestamp: i64,
+    pub decimals: u8, // Mint decimals — amount is in base units
+}
+
+#[event]
+pub struct BuybackExecuted {
+    pub seller: Pubkey,
+    pub amount: u64,
+    pub lamports_paid: u64,
+    pub remaining_budget: u64,
+    pub new_total_supply: u64,
+    pub actor: Pubkey, // Signer that triggered it
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct ReplenishNeeded {
+    pub treasury_balance: u64,
+    pub threshold: u64,
+    pub suggested_mint: u64, // replenish_amount, capped by reserve headroom
+    pub auto_replenish: bool,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct TokensBurned {
+    pub user: Pubkey,
+    pub amount: u64,
+    pub request_id: u64,
+    pub timestamp: i64,
+    pub new_total_supply: u64, // Post-burn state (appended so older decoders keep working)
+    pub new_total_burned: u64,
+    pub priority: u8,          // Fulfillment priority assigned from the user's tier
+    pub requested_amount: u64, // Before whole-bar rounding; equals amount when none applied
+    pub region_code: u16,      // Bucketed delivery region fulfillers can filter on (0 = unspecified)
+    pub decimals: u8,          // Mint decimals — amounts are in base units
+}
+
+#[event]
+pub struct PriorityFeeSet {
+    pub request_id: u64,
+    pub user: Pubkey,
+    pub added_lamports: u64,
+    pub priority_fee_lamports: u64, // Total now escrowed in the request
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct PriorityFeeSettled {
+    pub request_id: u64,
+    pub recipient: Pubkey, // Fulfiller on confirmation, redeemer on cancel/expiry
+    pub lamports: u64,
+    pub refunded: bool,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct PointsTransferred {
+    pub from: Pubkey,
+    pub to: Pubkey,
+    pub amount: u64,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct AirdropClaimed {
+    pub user: Pubkey,
+    pub root: [u8; 32],
+    pub amount: u64,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct ComplianceLockChanged {
+    pub user: Pubkey,
+    pub locked: bool,
+    pub actor: Pubkey, // Signer that triggered it
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct RedemptionBlockChanged {
+    pub user: Pubkey,
+    pub blocked: bool,
+    pub actor: Pubkey, // Signer that triggered it
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct KycAttested {
+    pub user: Pubkey,
+    pub level: u8,
+    pub expires_at: i64,
+    pub attested_by: Pubkey,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct RedemptionClaimed {
+    pub request_id: u64,
+    pub fulfiller: Pubkey,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct BatchClaimed {
+    pub fulfiller: Pubkey,
+    pub request_ids: Vec<u64>, // Only the orders actually claimed; skipped ones are omitted
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct RedemptionConfirmed {
+    pub request_id: u64,
+    pub fulfiller: Pubkey,
+    pub proof_hash: [u8; 32], // All zeroes = no proof committed
+    pub reward_multiplier_bps: u16, // Tier multiplier applied to the reward (0 = no fulfiller profile)
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct RedemptionShipped {
+    pub request_id: u64,
+    pub fulfiller: Pubkey,
+    pub proof_hash: [u8; 32], // All zeroes = no proof committed
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct DeliveryDisputed {
+    pub request_id: u64,
+    pub user: Pubkey,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct QuoteLocked {
+    pub buyer: Pubkey,
+    pub amount: u64,
+    pub price_lamports: u64,
+    pub expires_at: i64,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct ContributionRecorded {
+    pub request_id: u64,
+    pub fulfiller: Pubkey,
+    pub amount: u64,
+    pub distinct_fulfillers: u8, // Contributors listed after this one
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct AutoConfirmed {
+    pub request_id: u64,
+    pub fulfiller: Pubkey,
+    pub shipped_at: i64,
+    pub reward_multiplier_bps: u16, // Tier multiplier applied to the reward (0 = no fulfiller profile)
+    pub timestamp: i64,
+}
 
-        invoke_signed(
-            &ix,
-            &[
-                ctx.accounts.wgb_mint.to_account_info(),
-                ctx.accounts.protocol_state.to_account_info(),
-            ],
-            signer,
-        )?;
+#[event]
+pub struct PendingExpired {
+    pub request_id: u64,
+    pub user: Pubkey,
+    pub created_at: i64,
+    pub points_clawed_back: u64,
+    pub timestamp: i64,
+}
 
-        msg!("Transfer fee updated: {} bps, max {}", new_fee_bps, new_max_fee);
-        Ok(())
-    }
+#[event]
+pub struct FulfillerRewardsReleased {
+    pub request_id: u64,
+    pub fulfiller: Pubkey,
+    pub points: u64, // Granted after the max_user_points cap
+    pub timestamp: i64,
 }
 
-fn validate_optional_user_profile<'info>(
-    user_profile: &Option<Account<'info, UserProfile>>,
-    expected_user: &Pubkey,
-) -> Result<()> {
-    if let Some(profile) = user_profile {
-        let (expected_profile_pda, _) = Pubkey::find_program_address(
-            &[b"user_profile", expected_user.as_ref()],
-            &crate::ID,
-        );
+#[event]
+pub struct ClaimExpired {
+    pub request_id: u64,
+    pub fulfiller: Pubkey,
+    pub timestamp: i64,
+}
 
-        require_keys_eq!(
-            profile.key(),
-            expected_profile_pda,
-            WGBError::InvalidUserProfileAccount
-        );
-        require_keys_eq!(
-            profile.user,
-            *expected_user,
-            WGBError::InvalidUserProfileAccount
-        );
-    }
+#[event]
+pub struct FulfillerSlashed {
+    pub request_id: u64,
+    pub fulfiller: Pubkey,
+    pub points_slashed: u64,
+    pub failed_fulfillments: u64,
+    pub timestamp: i64,
+}
 
-    Ok(())
+#[event]
+pub struct RedemptionAmended {
+    pub request_id: u64,
+    pub user: Pubkey,
+    pub previous_commitment: [u8; 32], // Zero on the first commitment
+    pub address_commitment: [u8; 32],
+    pub timestamp: i64,
 }
 
-// ==================== STRUCTS & ACCOUNTS ====================
+#[event]
+pub struct SerialRedemptionRequested {
+    pub serial_leaf: [u8; 32],
+    pub user: Pubkey,
+    pub request_id: u64,
+    pub amount: u64,
+    pub timestamp: i64,
+}
 
-#[account]
-pub struct ProtocolState {
-    pub authority: Pubkey,
-    pub operator: Pubkey,       // NEW: Hot wallet for auto-ops
-    pub wgb_mint: Pubkey,
-    pub treasury: Pubkey,
-    pub total_supply: u64,
-    pub total_burned: u64,      // NEW: Track burns
-    
-    pub current_merkle_root: [u8; 32],
-    pub proven_reserves: u64,
-    pub last_root_update: i64,
-    pub last_proof_timestamp: i64,
-    
-    pub wgb_price_lamports: u64,
-    pub sol_receiver: Pubkey,
-    
-    // Yield & Future
-    pub yield_apy_bps: u16,             // APY in basis points (350 = 3.5%)
-    pub total_yield_distributed: u64,   // Total W3B distributed as yield
-    pub last_yield_distribution: i64,   // Timestamp of last yield distribution
-    
-    pub is_paused: bool,
-    pub bump: u8,
-    
-    pub _reserved: [u8; 64],    // Padding for V3
+#[event]
+pub struct SerialReleased {
+    pub serial_leaf: [u8; 32],
+    pub user: Pubkey,
+    pub request_id: u64,
+    pub actor: Pubkey, // Signer that triggered it
+    pub timestamp: i64,
 }
 
-#[account]
-pub struct UserProfile {
+#[event]
+pub struct SerialRedeemed {
+    pub serial_leaf: [u8; 32],
     pub user: Pubkey,
-    pub total_volume: u64,
-    pub points: u64,
-    pub tier: u8,              // 0=Bronze, 1=Silver, 2=Gold, 3=Platinum
-    pub total_redeemed: u64,
-    pub total_fulfilled: u64,
-    pub fulfiller_rewards: u64,
-    pub bump: u8,
-    pub _reserved: [u8; 32],  // Future expansion without migration
+    pub request_id: u64,
+    pub actor: Pubkey, // Signer that triggered it
+    pub timestamp: i64,
 }
 
-#[account]
-pub struct RedemptionRequest {
+#[event]
+pub struct SerialRedemptionStatus {
+    pub serial_leaf: [u8; 32],
+    pub redeemed: bool,
+    pub request_id: u64, // 0 when not redeemed
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct RedemptionStatsView {
+    pub created: u64,
+    pub claimed: u64,
+    pub shipped: u64,
+    pub disputed: u64,
+    pub confirmed: u64,
+    pub cancelled: u64,
+    pub expired: u64,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct AdminStatusOverride {
+    pub request_id: u64,
     pub user: Pubkey,
+    pub old_status: u8,
+    pub new_status: u8,
+    pub reason_code: u8,
+    pub authority: Pubkey,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct RedemptionRefunded {
     pub request_id: u64,
+    pub user: Pubkey,
     pub amount: u64,
-    pub status: u8, // 0=Pending, 1=Claimed, 2=Shipped, 3=Confirmed
-    pub fulfiller: Pubkey,
-    pub created_at: i64,
-    pub claimed_at: i64,
-    pub confirmed_at: i64,
-    pub bump: u8,
+    pub new_total_supply: u64,
+    pub actor: Pubkey, // Signer that triggered it
+    pub timestamp: i64,
 }
 
-// ==================== CONTEXTS ====================
-
-#[derive(Accounts)]
-pub struct InitializeV2<'info> {
-    #[account(init, payer = authority, space = 8 + 512, seeds = [b"protocol_state"], bump)]
-    pub protocol_state: Account<'info, ProtocolState>,
-    /// Token-2022 mint (validated as a real mint account)
-    pub wgb_mint: InterfaceAccount<'info, Mint>,
-    /// Treasury token account (validated as a real token account)
-    pub treasury: InterfaceAccount<'info, TokenAccount>,
-    #[account(mut)]
-    pub authority: Signer<'info>,
-    pub system_program: Program<'info, System>,
-    pub token_program: Program<'info, Token2022>,
+#[event]
+pub struct RedemptionCancelled {
+    pub request_id: u64,
+    pub actor: Pubkey, // Signer that triggered it
+    pub timestamp: i64,
 }
 
-#[derive(Accounts)]
-pub struct MigrateV2<'info> {
-    /// CHECK: Manual resize — AccountInfo used because deserialization may fail mid-migration.
-    /// Authority is validated inside the instruction body by reading raw bytes.
-    #[account(mut, seeds = [b"protocol_state"], bump)]
-    pub protocol_state: AccountInfo<'info>,
-    #[account(mut)]
-    pub authority: Signer<'info>,
-    pub system_program: Program<'info, System>,
+#[event]
+pub struct CancelPenaltyApplied {
+    pub request_id: u64,
+    pub user: Pubkey,
+    pub penalty: u64,            // W3B kept burned
+    pub points_clawed_back: u64,
+    pub timestamp: i64,
 }
 
-#[derive(Accounts)]
-pub struct OperatorOnly<'info> {
-    #[account(mut, seeds = [b"protocol_state"], bump = protocol_state.bump)]
-    pub protocol_state: Account<'info, ProtocolState>,
-    #[account(
-        constraint = operator.key() == protocol_state.operator 
-                  || operator.key() == protocol_state.authority
-                  @ WGBError::Unauthorized
-    )]
-    pub operator: Signer<'info>,
+#[event]
+pub struct AdminMultisigUpdated {
+    pub signers: Vec<Pubkey>,
+    pub threshold: u8,
+    pub actor: Pubkey, // Signer that triggered it
+    pub timestamp: i64,
 }
 
-#[derive(Accounts)]
-pub struct AdminOnly<'info> {
-    #[account(mut, seeds = [b"protocol_state"], bump = protocol_state.bump, has_one = authority)]
-    pub protocol_state: Account<'info, ProtocolState>,
-    pub authority: Signer<'info>,
+#[event]
+pub struct SolSplitUpdated {
+    pub receivers: Vec<SolShare>,
+    pub actor: Pubkey, // Signer that triggered it
+    pub timestamp: i64,
 }
 
-#[derive(Accounts)]
-pub struct UpdateTransferFee<'info> {
-    #[account(
-        seeds = [b"protocol_state"],
-        bump = protocol_state.bump,
-        has_one = authority,
-    )]
-    pub protocol_state: Account<'info, ProtocolState>,
-    pub authority: Signer<'info>,
-    #[account(
-        mut,
-        constraint = wgb_mint.key() == protocol_state.wgb_mint
-    )]
-    pub wgb_mint: InterfaceAccount<'info, Mint>,
-    pub token_program: Program<'info, Token2022>,
+#[event]
+pub struct LossCovered {
+    pub request_id: u64,
+    pub user: Pubkey,
+    pub amount: u64,            // Lamports paid from the insurance fund
+    pub insurance_balance: u64, // Remaining after the payout
+    pub actor: Pubkey,          // Signer that triggered it
+    pub timestamp: i64,
 }
 
-#[derive(Accounts)]
-pub struct CloseProtocolState<'info> {
-    #[account(
-        mut,
-        seeds = [b"protocol_state"],
-        bump = protocol_state.bump,
-        has_one = authority,
-        close = authority
-    )]
-    pub protocol_state: Account<'info, ProtocolState>,
-    #[account(mut)]
-    pub authority: Signer<'info>,
+#[event]
+pub struct WithdrawalThrottleUpdated {
+    pub window_secs: i64,
+    pub sol_limit: u64,
+    pub token_limit: u64,
+    pub paused_limit_bps: u16,
+    pub actor: Pubkey, // Signer that triggered it
+    pub timestamp: i64,
 }
 
-#[derive(Accounts)]
-pub struct MintWGB<'info> {
-    #[account(
-        mut, 
-        seeds = [b"protocol_state"], 
-        bump = protocol_state.bump,
-        has_one = wgb_mint,
-        has_one = treasury
-    )]
-    pub protocol_state: Account<'info, ProtocolState>,
-    
-    #[account(mut)] 
-    pub wgb_mint: InterfaceAccount<'info, Mint>,
-    
-    #[account(
-        mut,
-        token::mint = protocol_state.wgb_mint,
-        constraint = treasury.owner == protocol_state.key()
-    )] 
-    pub treasury: InterfaceAccount<'info, TokenAccount>,
-    
-    pub token_program: Program<'info, Token2022>,
+#[event]
+pub struct ConfigUpdated {
+    pub setting: String, // Setter that ran, without its set_ prefix (e.g. "min_buy_amount")
+    pub actor: Pubkey,   // Signer that triggered it
+    pub timestamp: i64,
+}
 
-    /// Operator or authority signs
-    #[account(
-        constraint = operator.key() == protocol_state.operator
-                  || operator.key() == protocol_state.authority
-                  @ WGBError::Unauthorized
-    )]
-    pub operator: Signer<'info>,
+#[event]
+pub struct RoleKeyUpdated {
+    pub role: String, // "oracle", "guardian", "backup_operator", "sol_receiver" or "treasury"
+    pub previous: Pubkey,
+    pub new_key: Pubkey,
+    pub actor: Pubkey, // Signer that triggered it
+    pub timestamp: i64,
 }
 
-#[derive(Accounts)]
-pub struct BuyWGB<'info> {
-    #[account(
-        mut, 
-        seeds = [b"protocol_state"], 
-        bump = protocol_state.bump,
-        has_one = treasury, // matches protocol_state.treasury == treasury.key()
-        has_one = sol_receiver
-    )]
-    pub protocol_state: Account<'info, ProtocolState>,
-    
-    #[account(mut)]
-    pub buyer: Signer<'info>,
-    
-    #[account(
-        mut,
-        token::mint = protocol_state.wgb_mint,
-        token::authority = buyer
-    )]
-    pub buyer_token_account: InterfaceAccount<'info, TokenAccount>,
-    
-    #[account(
-        mut,
-        constraint = treasury.owner == protocol_state.key(),
-        token::mint = protocol_state.wgb_mint
-    )]
-    pub treasury: InterfaceAccount<'info, TokenAccount>,
-    
-    /// CHECK: Validated via protocol_state.sol_receiver
-    #[account(mut)]
-    pub sol_receiver: AccountInfo<'info>,
+#[event]
+pub struct CpiAllowlistUpdated {
+    pub programs: Vec<Pubkey>,
+    pub actor: Pubkey, // Signer that triggered it
+    pub timestamp: i64,
+}
 
-    #[account(
-        mut,
-        constraint = wgb_mint.key() == protocol_state.wgb_mint
-    )]
-    pub wgb_mint: InterfaceAccount<'info, Mint>,
-    
-    pub system_program: Program<'info, System>,
-    pub token_program: Program<'info, Token2022>,
-    
-    // Optional Points
-    #[account(mut)]
-    pub user_profile: Option<Account<'info, UserProfile>>,
+#[event]
+pub struct ProtocolSnapshot {
+    pub schema_version: u8,         // SNAPSHOT_SCHEMA_VERSION of the build that emitted it
+    pub state: Box<ProtocolState>,  // Every field, in account order
+    pub live_supply: u64,           // Mint supply at the same slot
+    pub slot: u64,
+    pub timestamp: i64,
 }
 
-#[derive(Accounts)]
-pub struct InitUserProfile<'info> {
-    #[account(
-        init, 
-        payer = user, 
-        space = 8 + 128, 
-        seeds = [b"user_profile", user.key().as_ref()], 
-        bump
-    )]
-    pub user_profile: Account<'info, UserProfile>,
-    #[account(mut)]
-    pub user: Signer<'info>,
-    pub system_program: Program<'info, System>,
+#[event]
+pub struct PauseStateChanged {
+    pub paused: bool,
+    pub reason: u8,    // PauseReason
+    pub actor: Pubkey, // Signer that triggered it
+    pub timestamp: i64,
 }
 
-#[derive(Accounts)]
-#[instruction(amount: u64, request_id: u64)]
-pub struct BurnWGB<'info> {
-    #[account(mut, seeds = [b"protocol_state"], bump = protocol_state.bump)]
-    pub protocol_state: Account<'info, ProtocolState>,
-    
-    #[account(mut)]
-    pub user: Signer<'info>,
-    #[account(
-        mut,
-        token::mint = wgb_mint,
-        token::authority = user
-    )]
-    pub user_token_account: InterfaceAccount<'info, TokenAccount>,
-    #[account(mut, constraint = wgb_mint.key() == protocol_state.wgb_mint @ WGBError::Unauthorized)]
-    pub wgb_mint: InterfaceAccount<'info, Mint>,
-    
-    #[account(
-        init,
-        payer = user,
-        space = 8 + 128,
-        seeds = [b"redemption", user.key().as_ref(), request_id.to_le_bytes().as_ref()],
-        bump
-    )]
-    pub redemption_request: Account<'info, RedemptionRequest>,
-    
-    pub system_program: Program<'info, System>,
-    pub token_program: Program<'info, Token2022>,
-    
-    #[account(mut)]
-    pub user_profile: Option<Account<'info, UserProfile>>,
+#[event]
+pub struct AutoUnpaused {
+    pub paused_until: i64, // Scheduled end that was reached
+    pub actor: Pubkey,     // Signer that triggered it
+    pub timestamp: i64,
 }
 
-#[derive(Accounts)]
-pub struct AwardPoints<'info> {
-    #[account(seeds = [b"protocol_state"], bump = protocol_state.bump)]
-    pub protocol_state: Account<'info, ProtocolState>,
-    #[account(mut, seeds = [b"user_profile", user.key().as_ref()], bump = user_profile.bump)]
-    pub user_profile: Account<'info, UserProfile>,
-    /// CHECK: User only needed for seed derivation
-    pub user: UncheckedAccount<'info>,
-    
-    // Operator can award points
-    #[account(
-        constraint = operator.key() == protocol_state.operator 
-                  || operator.key() == protocol_state.authority
-    )]
-    pub operator: Signer<'info>,
+#[event]
+pub struct EmergencyPaused {
+    pub actor: Pubkey, // Signer that triggered it
+    pub timestamp: i64,
 }
 
-// ==================== P2P FULFILLMENT CONTEXTS ====================
+#[event]
+pub struct StrandedTokensRecovered {
+    pub mint: Pubkey,
+    pub source: Pubkey,
+    pub destination: Pubkey,
+    pub amount: u64,
+    pub actor: Pubkey, // Signer that triggered it
+    pub timestamp: i64,
+}
 
-#[derive(Accounts)]
-pub struct ClaimRedemption<'info> {
-    #[account(seeds = [b"protocol_state"], bump = protocol_state.bump)]
-    pub protocol_state: Account<'info, ProtocolState>,
+#[event]
+pub struct MetadataUpdated {
+    pub field: String,
+    pub value: String,
+    pub actor: Pubkey, // Signer that triggered it
+    pub timestamp: i64,
+}
 
-    #[account(
-        mut,
-        seeds = [b"redemption", redemption_request.user.as_ref(), redemption_request.request_id.to_le_bytes().as_ref()],
-        bump = redemption_request.bump,
-        constraint = redemption_request.status == 0 @ WGBError::InvalidRedemptionStatus
-    )]
-    pub redemption_request: Account<'info, RedemptionRequest>,
+#[event]
+pub struct GoldEquivalent {
+    pub amount: u64,
+    pub grams_bps: u64,          // Weights in 1/10_000 g
+    pub reserves_grams_bps: u64,
+    pub supply_grams_bps: u64,
+    pub grams_per_token_bps: u64,
+    pub timestamp: i64,
+}
 
-    /// The fulfiller claiming this order
-    #[account(mut)]
-    pub fulfiller: Signer<'info>,
+#[event]
+pub struct MintableHeadroom {
+    pub headroom: u64,         // What mint_wgb accepts now (0 while paused, stale, cooling down or challengeable)
+    pub reserve_headroom: u64, // backing_reserves - total_supply
+    pub period_remaining: u64, // Rate-limit budget left (u64::MAX when unlimited)
+    pub proof_fresh: bool,
+    pub paused: bool,
+    pub timestamp: i64,
 }
 
-#[derive(Accounts)]
-pub struct ConfirmDelivery<'info> {
-    #[account(
-        seeds = [b"protocol_state"],
-        bump = protocol_state.bump,
-    )]
-    pub protocol_state: Account<'info, ProtocolState>,
+#[event]
+pub struct AccountingView {
+    pub live_supply: u64,
+    pub total_supply: u64,
+    pub supply_mismatch: bool,
+    pub total_burned: u64,
+    pub total_yield_distributed: u64,
+    pub proven_reserves: u64,
+    pub coverage_bps: u16, // Cached backing / supply (u16::MAX = saturated or no supply)
+    pub insurance_balance: u64,
+    pub timestamp: i64,
+}
 
-    #[account(
-        mut,
-        seeds = [b"redemption", redemption_request.user.as_ref(), redemption_request.request_id.to_le_bytes().as_ref()],
-        bump = redemption_request.bump,
-        constraint = redemption_request.status == 1 @ WGBError::InvalidRedemptionStatus
-    )]
-    pub redemption_request: Account<'info, RedemptionRequest>,
+#[event]
+pub struct MintAuthorityVerified {
+    pub wgb_mint: Pubkey,
+    pub expected_authority: Pubkey, // protocol_state PDA
+    pub mint_authority: Option<Pubkey>, // None = authority revoked
+    pub matches: bool,
+    pub timestamp: i64,
+}
 
-    /// Fulfiller's profile (optional — for reward points)
-    #[account(
-        mut,
-        seeds = [b"user_profile", redemption_request.fulfiller.as_ref()],
-        bump = fulfiller_profile.bump
-    )]
-    pub fulfiller_profile: Option<Account<'info, UserProfile>>,
+/// Every tunable ProtocolState parameter — see emit_config
+#[event]
+pub struct ConfigView {
+    // Role keys
+    pub authority: Pubkey,
+    pub operator: Pubkey,
+    pub backup_operator: Pubkey,
+    pub operator_rotation_cooldown_secs: i64,
+    pub guardian: Pubkey,
+    pub oracle: Pubkey,
+    pub treasury: Pubkey,
+    pub treasury_owner_mode: u8,
+    pub sol_receiver: Pubkey,
+    // Pricing & buys
+    pub wgb_price_lamports: u64,
+    pub wgb_sell_price_lamports: u64,
+    pub maintain_spread: bool,
+    pub spread_bps: u16,
+    pub round_up_cost: bool,
+    pub max_sol_per_buy: u64,
+    pub min_buy_amount: u64,
+    pub require_kyc: bool,
+    pub min_kyc_level: u8,
+    pub operator_price_band_bps: u16,
+    pub authority_price_band_bps: u16,
+    pub insurance_fee_bps: u16,
+    pub cpi_gate: u8,
+    pub quote_lock_secs: i64,
+    pub buy_cooldown_secs: i64,
+    // Pause
+    pub is_paused: bool,
+    pub pause_reason: u8,
+    pub paused_until: i64,
+    // Minting & proofs
+    pub max_mint_per_period: u64,
+    pub mint_period_secs: i64,
+    pub min_mint_interval_secs: i64,
+    pub max_proof_age_secs: i64,
+    pub mint_delay_secs: i64,
+    pub min_challenge_bond: u64,
+    pub allow_reserve_shrink: bool,
+    pub operator_rebate_lamports: u64,
+    pub replenish_threshold: u64,
+    pub replenish_amount: u64,
+    pub auto_replenish: bool,
+    pub buyback_budget_lamports: u64,
+    pub grams_per_token_bps: u64,
+    pub reserve_unit: u8,
+    pub yield_apy_bps: u16,
+    pub yield_min_coverage_bps: u16,
+    // Redemptions & fulfillment
+    pub pending_expiry_secs: i64,
+    pub reward_holdback_secs: i64,
+    pub redemption_rounding: u8,
+    pub redemption_bar_unit: u64,
+    pub claim_timeout_secs: i64,
+    pub auto_confirm_secs: i64,
+    pub fulfiller_slash_points: u64,
+    pub cancel_penalty_bps: u16,
+    pub max_active_claims_per_fulfiller: u32,
+    pub tier_priority: [u8; MAX_TIERS],
+    pub tier_fulfiller_multiplier_bps: [u16; MAX_TIERS],
+    pub multi_fulfiller_threshold: u64,
+    pub min_distinct_fulfillers: u8,
+    // Points & tiers
+    pub airdrop_root: [u8; 32],
+    pub min_referrer_tier: u8,
+    pub welcome_tier: u8,
+    pub max_user_points: u64,
+    pub points_transfers_enabled: bool,
+    pub tier_basis: u8,
+    pub buy_points_bps: u64,
+    pub streak_bonus_points: [u32; STREAK_BONUS_DAYS],
+    // Events
+    pub event_verbosity: u8,
+    pub timestamp: i64,
+}
 
-    /// Admin or Operator signs
-    #[account(
-        constraint = signer.key() == protocol_state.authority
-                  || signer.key() == protocol_state.operator
-                  @ WGBError::Unauthorized
-    )]
-    pub signer: Signer<'info>,
+#[event]
+pub struct SupplyReconciled {
+    pub previous_total_supply: u64,
+    pub new_total_supply: u64,
+    pub actor: Pubkey, // Signer that triggered it
+    pub timestamp: i64,
 }
 
-#[derive(Accounts)]
-pub struct CancelRedemption<'info> {
-    #[account(
-        seeds = [b"protocol_state"],
-        bump = protocol_state.bump,
-        has_one = authority
-    )]
-    pub protocol_state: Account<'info, ProtocolState>,
+#[event]
+pub struct PointsConfigUpdated {
+    pub buy_points_per_token: u64,
+    pub redemption_multiplier: u64,
+    pub fulfiller_reward: u64,
+    pub referral_bonus: u64,
+    pub actor: Pubkey, // Signer that triggered it
+    pub timestamp: i64,
+}
 
-    #[account(
-        mut,
-        seeds = [b"redemption", redemption_request.user.as_ref(), redemption_request.request_id.to_le_bytes().as_ref()],
-        bump = redemption_request.bump,
-    )]
-    pub redemption_request: Account<'info, RedemptionRequest>,
+#[event]
+pub struct ProofChallenged {
+    pub challenger: Pubkey,
+    pub merkle_root: [u8; 32],
+    pub bond: u64,
+    pub timestamp: i64,
+}
 
-    /// Only admin can cancel
-    pub authority: Signer<'info>,
+#[event]
+pub struct ChallengeResolved {
+    pub challenger: Pubkey,
+    pub merkle_root: [u8; 32],
+    pub bond: u64,
+    pub upheld: bool,
+    pub operator_penalties: u32,
+    pub actor: Pubkey, // Signer that triggered it
+    pub timestamp: i64,
 }
 
-// ==================== EVENTS & ERRORS ====================
+#[event]
+pub struct PointsCapReached {
+    pub user: Pubkey,
+    pub requested: u64,
+    pub granted: u64, // What fit under the cap (0 if the profile was already at it)
+    pub cap: u64,
+    pub timestamp: i64,
+}
 
 #[event]
-pub struct MerkleRootUpdated {
-    pub root: [u8; 32],
-    pub total_serials: u64,
+pub struct ProfileView {
+    pub user: Pubkey,
+    pub points: u64,
+    pub tier: u8,
+    pub total_volume: u64,
+    pub total_redeemed: u64,
+    pub total_fulfilled: u64,
+    pub fulfiller_rewards: u64,
+    pub last_award_nonce: u64,
+    pub failed_fulfillments: u64,
+    pub compliance_locked: bool,
+    pub active_claims: u32,
+    pub points_dust: u64,
+    pub streak_days: u16,
+    pub last_streak_day: u32,
+    pub last_buy_at: i64,
+    pub welcome_tier_floor: u8,
     pub timestamp: i64,
 }
 
 #[event]
-pub struct ProofSubmitted {
-    pub merkle_root: [u8; 32],
-    pub claimed_reserves: u64,
-    pub proof_hash: Vec<u8>,
+pub struct StreakUpdated {
+    pub user: Pubkey,
+    pub streak_days: u16,
+    pub bonus_points: u64, // Credited for this streak length (0 = no bonus configured)
     pub timestamp: i64,
 }
 
 #[event]
-pub struct TokensMinted {
-    pub amount: u64,
-    pub new_total_supply: u64,
+pub struct UserProfileCreated {
+    pub user: Pubkey,
     pub timestamp: i64,
 }
 
 #[event]
-pub struct TokensPurchased {
-    pub buyer: Pubkey,
-    pub amount: u64,
-    pub lamports_paid: u64,
+pub struct ProfileMigrated {
+    pub old_user: Pubkey,
+    pub new_user: Pubkey,
+    pub points: u64,
+    pub tier: u8,
     pub timestamp: i64,
 }
 
 #[event]
-pub struct TokensBurned {
+pub struct TierChanged {
     pub user: Pubkey,
-    pub amount: u64,
-    pub request_id: u64,
+    pub old_tier: u8,
+    pub new_tier: u8,
+    pub basis: u8, // TIER_BASIS_POINTS or TIER_BASIS_VOLUME
     pub timestamp: i64,
 }
 
 #[event]
-pub struct RedemptionClaimed {
-    pub request_id: u64,
-    pub fulfiller: Pubkey,
+pub struct WelcomeTierGranted {
+    pub user: Pubkey,
+    pub tier: u8,
     pub timestamp: i64,
 }
 
 #[event]
-pub struct RedemptionConfirmed {
-    pub request_id: u64,
-    pub fulfiller: Pubkey,
+pub struct ReferralRegistered {
+    pub referrer: Pubkey,
+    pub referee: Pubkey,
+    pub bonus_paid: u64,
     pub timestamp: i64,
 }
 
 #[event]
-pub struct RedemptionCancelled {
-    pub request_id: u64,
+pub struct ReferralIneligible {
+    pub referrer: Pubkey,
+    pub referee: Pubkey,
+    pub referrer_tier: u8,
+    pub min_referrer_tier: u8,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct TierConfigUpdated {
+    pub tiers: Vec<TierDefinition>,
+    pub actor: Pubkey, // Signer that triggered it
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct PriceUpdated {
+    pub buy_price_lamports: u64,
+    pub sell_price_lamports: u64, // Effective buyback price
+    pub actor: Pubkey,            // Signer that triggered it
     pub timestamp: i64,
 }
 
 #[event]
 pub struct YieldRateUpdated {
     pub apy_bps: u16,
+    pub actor: Pubkey, // Signer that triggered it
     pub timestamp: i64,
 }
 
@@ -1074,6 +8091,7 @@ pub struct YieldRateUpdated {
 pub struct YieldDistributed {
     pub amount: u64,
     pub new_total: u64,
+    pub actor: Pubkey, // Signer that triggered it
     pub timestamp: i64,
 }
 
@@ -1089,7 +8107,7 @@ pub enum WGBError {
     InsufficientReserves,
     #[msg("Invalid price")]
     InvalidPrice,
-    #[msg("Price change exceeds 20% limit")]
+    #[msg("Price change exceeds the allowed band")]
     PriceChangeExceedsLimit,
     #[msg("Unauthorized")]
     Unauthorized,
@@ -1103,4 +8121,136 @@ pub enum WGBError {
     ExceedsTransactionCap,
     #[msg("Invalid user profile account supplied")]
     InvalidUserProfileAccount,
+    #[msg("SOL debited from buyer does not match the quoted cost")]
+    PaymentMismatch,
+    #[msg("Legacy protocol state is missing or malformed")]
+    InvalidLegacyState,
+    #[msg("Legacy stats have already been imported")]
+    LegacyAlreadyImported,
+    #[msg("Tier table must start at 0, ascend strictly and hold at most MAX_TIERS entries")]
+    InvalidTierConfig,
+    #[msg("Award nonce has already been used for this profile")]
+    NonceReused,
+    #[msg("Proof hash must be exactly 32 bytes")]
+    InvalidProofHash,
+    #[msg("Claim has not passed its timeout yet")]
+    ClaimNotExpired,
+    #[msg("Invalid configuration value")]
+    InvalidConfig,
+    #[msg("Purchase cost exceeds the per-transaction SOL cap")]
+    ExceedsSolCap,
+    #[msg("No airdrop is currently active")]
+    AirdropNotActive,
+    #[msg("Merkle proof does not match the root")]
+    InvalidMerkleProof,
+    #[msg("Auto-confirm window has not elapsed")]
+    ConfirmWindowOpen,
+    #[msg("User is under a compliance hold")]
+    ComplianceLocked,
+    #[msg("Reserve attestation is not signed by the registered oracle")]
+    InvalidOracleSignature,
+    #[msg("Mint exceeds the current period's budget")]
+    MintRateLimitExceeded,
+    #[msg("Cannot refer yourself")]
+    SelfReferral,
+    #[msg("Batch is empty or its length is out of range")]
+    InvalidBatchSize,
+    #[msg("Account is not a redemption request of this program")]
+    InvalidRedemptionAccount,
+    #[msg("Mint cool-down has not elapsed")]
+    MintTooSoon,
+    #[msg("Not enough multisig signers approved this action")]
+    MultisigThresholdNotMet,
+    #[msg("Treasury balance is too low for this purchase")]
+    InsufficientTreasury,
+    #[msg("Mint decimals do not match the expected value")]
+    DecimalsMismatch,
+    #[msg("Redemption can only be amended while Pending")]
+    CannotAmendActive,
+    #[msg("Supply is fully backed; buyback not allowed")]
+    NotUnderCollateralized,
+    #[msg("Buyback exceeds the remaining budget or vault balance")]
+    BuybackBudgetExceeded,
+    #[msg("Fulfiller already holds the maximum number of active claims")]
+    TooManyActiveClaims,
+    #[msg("The protocol's own token cannot be recovered")]
+    CannotRecoverProtocolToken,
+    #[msg("Redemptions are blocked for this user")]
+    RedemptionsBlocked,
+    #[msg("Protocol state is already initialized")]
+    AlreadyInitialized,
+    #[msg("This build only accepts the mint pinned by WGB_ALLOWED_MINT")]
+    MintNotAllowed,
+    #[msg("The latest proof is still in its challenge window")]
+    ProofChallengeWindow,
+    #[msg("Points transfers are disabled")]
+    PointsTransfersDisabled,
+    #[msg("Cannot transfer points to yourself")]
+    SelfTransfer,
+    #[msg("Not enough points")]
+    InsufficientPoints,
+    #[msg("A reserve-proof challenge is open")]
+    ProofUnderChallenge,
+    #[msg("Challenge bond is below the minimum")]
+    ChallengeBondTooLow,
+    #[msg("Amount must be greater than zero")]
+    ZeroAmount,
+    #[msg("Amount is below the minimum buy")]
+    BelowMinBuy,
+    #[msg("A valid KYC attestation of sufficient level is required")]
+    KycRequired,
+    #[msg("SOL split is invalid or its receiver accounts do not match")]
+    InvalidSplit,
+    #[msg("Reserve coverage is too low to distribute yield")]
+    InsufficientCoverageForYield,
+    #[msg("Operator rotated too recently")]
+    OperatorRotationCooldown,
+    #[msg("Pending redemption has not expired yet")]
+    PendingNotExpired,
+    #[msg("Roots do not link the commitment to the current root")]
+    LineageMismatch,
+    #[msg("Fulfiller reward is still inside its dispute window")]
+    RewardHoldbackActive,
+    #[msg("Operators cannot target their own account")]
+    SelfTarget,
+    #[msg("Outflow exceeds the withdrawal throttle for this window")]
+    WithdrawalThrottled,
+    #[msg("Redemptions must be a whole number of bars")]
+    NonWholeRedemption,
+    #[msg("Insurance fund balance is too low")]
+    InsufficientInsurance,
+    #[msg("No reserve proof has been submitted yet")]
+    NoProofYet,
+    #[msg("Calling program is not allowed to CPI into this instruction")]
+    CallerNotAllowed,
+    #[msg("This redemption needs contributions from more distinct fulfillers")]
+    RequiresMultipleFulfillers,
+    #[msg("The price quote has expired")]
+    QuoteExpired,
+    #[msg("Buy amount does not match the locked quote")]
+    QuoteMismatch,
+    #[msg("The treasury owner must co-sign buys from an authority-owned treasury")]
+    TreasurySignerRequired,
+    #[msg("No scheduled maintenance pause has ended yet")]
+    AutoUnpauseNotDue,
+    #[msg("Supply is under-collateralized; unpause manually")]
+    UnderCollateralized,
+    #[msg("The new wallet already has a profile with points or history")]
+    TargetProfileExists,
+    #[msg("Release or complete open fulfiller claims before migrating")]
+    ProfileHasActiveClaims,
+    #[msg("Batch exceeds MAX_BATCH items")]
+    BatchTooLarge,
+    #[msg("This serial is already bound to a redemption")]
+    SerialAlreadyAssigned,
+    #[msg("A UserProfile is required while a buy cooldown is configured")]
+    ProfileRequired,
+    #[msg("Buy cooldown has not elapsed")]
+    BuyCooldown,
+    #[msg("Serial must be 1-63 bytes and hash to serial_leaf")]
+    InvalidSerial,
+    #[msg("A referral can only be registered after the referee's first purchase")]
+    ReferralNotQualified,
+    #[msg("Loss cover exceeds the redeemed order's value")]
+    LossExceedsOrderValue,
 }
@@ -9,6 +9,7 @@ import {
   sendAndConfirmTransaction,
   LAMPORTS_PER_SOL,
 } from "@solana/web3.js";
+import { createHash } from "crypto";
 import { WgbProtocol } from "../target/types/wgb_protocol";
 import IDL from "../target/idl/wgb_protocol.json";
 
@@ -17,8 +18,12 @@ const {
   ASSOCIATED_TOKEN_PROGRAM_ID,
   getAssociatedTokenAddressSync,
   createInitializeMintInstruction,
+  createInitializeTransferFeeConfigInstruction,
   createAssociatedTokenAccountInstruction,
-  MINT_SIZE,
+  ExtensionType,
+  getMint,
+  getMintLen,
+  getTransferFeeConfig,
 } = require("../../../services/api/node_modules/@solana/spl-token");
 
 describe("programs-wgb-protocol step3 optional profile", () => {
@@ -51,6 +56,61 @@ describe("programs-wgb-protocol step3 optional profile", () => {
     return parseProtocolStateV2(info.data);
   }
 
+  async function readTotalSupply(): Promise<BN> {
+    const info = await connection.getAccountInfo(protocolStatePda);
+    if (!info) throw new Error("ProtocolState not found");
+    return new BN(info.data.subarray(136, 144), "le");
+  }
+
+  // ProofHistory ring buffer submit_proof appends to (a no-op until init_proof_history)
+  const [proofHistoryPda] = PublicKey.findProgramAddressSync([Buffer.from("proof_history")], program.programId);
+  // Optional outflow cap (set_withdrawal_throttle); a no-op until created
+  const [withdrawalThrottlePda] = PublicKey.findProgramAddressSync(
+    [Buffer.from("withdrawal_throttle")],
+    program.programId
+  );
+
+  // Proves enough reserves and mints `amount` WGB into the treasury so paid buys can settle.
+  async function stockTreasury(amount: number): Promise<void> {
+    const totalSerials = (await readTotalSupply()).addn(amount);
+
+    await program.methods
+      .updateMerkleRoot(Array.from(Buffer.alloc(32, 7)), totalSerials)
+      .accountsPartial({ protocolState: protocolStatePda, operator: payer.publicKey })
+      .rpc({ commitment: "confirmed" });
+
+    await program.methods
+      .submitProof(Buffer.alloc(32, 9), totalSerials)
+      .accountsPartial({
+        protocolState: protocolStatePda,
+        operator: payer.publicKey,
+        proofHistory: proofHistoryPda,
+        withdrawalThrottle: withdrawalThrottlePda,
+      })
+      .rpc({ commitment: "confirmed" });
+
+    await program.methods
+      .mintWgb(new BN(amount))
+      .accountsPartial({
+        protocolState: protocolStatePda,
+        wgbMint: wgbMint,
+        treasury: treasuryAta,
+        tokenProgram: TOKEN_2022_PROGRAM_ID,
+        operator: payer.publicKey,
+      } as any)
+      .rpc({ commitment: "confirmed" });
+  }
+
+  async function expectErrorCode(call: () => Promise<unknown>, code: string): Promise<void> {
+    try {
+      await call();
+      expect.fail(`Expected ${code}`);
+    } catch (err) {
+      const maybeCode = (err as any)?.error?.errorCode?.code;
+      expect(maybeCode === code || String(err).includes(code)).to.eq(true);
+    }
+  }
+
   async function fundFromPayer(recipient: PublicKey, sol: number): Promise<void> {
     const tx = new Transaction().add(
       SystemProgram.transfer({
@@ -63,18 +123,29 @@ describe("programs-wgb-protocol step3 optional profile", () => {
     await sendAndConfirmTransaction(connection, tx, [payer], { commitment: "confirmed" });
   }
 
+  // Like the live WGB mint, carries the Transfer Fee Extension with the protocol PDA as its
+  // fee authority (update_transfer_fee). The fee starts at 0 so balances stay exact.
   async function createMintAndTreasury(protocolPda: PublicKey): Promise<{ mint: PublicKey; treasury: PublicKey }> {
     const mint = Keypair.generate();
 
-    const mintRent = await connection.getMinimumBalanceForRentExemption(MINT_SIZE);
+    const mintLen = getMintLen([ExtensionType.TransferFeeConfig]);
+    const mintRent = await connection.getMinimumBalanceForRentExemption(mintLen);
     const createMintTx = new Transaction().add(
       SystemProgram.createAccount({
         fromPubkey: payer.publicKey,
         newAccountPubkey: mint.publicKey,
-        space: MINT_SIZE,
+        space: mintLen,
         lamports: mintRent,
         programId: TOKEN_2022_PROGRAM_ID,
       }),
+      createInitializeTransferFeeConfigInstruction(
+        mint.publicKey,
+        protocolPda,
+        protocolPda,
+        0,
+        BigInt(0),
+        TOKEN_2022_PROGRAM_ID
+      ),
       createInitializeMintInstruction(mint.publicKey, 0, protocolPda, null, TOKEN_2022_PROGRAM_ID)
     );
 
@@ -174,7 +245,7 @@ describe("programs-wgb-protocol step3 optional profile", () => {
       treasuryAta = created.treasury;
 
       await program.methods
-        .initializeV2()
+        .initializeV2(0)
         .accountsPartial({
           protocolState: protocolStatePda,
           wgbMint: wgbMint,
@@ -208,21 +279,236 @@ describe("programs-wgb-protocol step3 optional profile", () => {
     wrongProfilePda = await createUserProfile(wrongProfileUser);
   });
 
+  it("mint_wgb fails with NoProofYet before the first proof", async function () {
+    // Only meaningful on a freshly initialized state; later tests submit proofs
+    const state = await program.account.protocolState.fetch(protocolStatePda);
+    if (!state.lastProofTimestamp.eqn(0)) this.skip();
+
+    await expectErrorCode(
+      () =>
+        program.methods
+          .mintWgb(new BN(1))
+          .accountsPartial({
+            protocolState: protocolStatePda,
+            wgbMint: wgbMint,
+            treasury: treasuryAta,
+            tokenProgram: TOKEN_2022_PROGRAM_ID,
+            operator: payer.publicKey,
+          } as any)
+          .rpc({ commitment: "confirmed" }),
+      "NoProofYet"
+    );
+  });
+
   it("buy_wgb succeeds when user_profile is omitted", async () => {
+    await stockTreasury(1);
+
+    await program.methods
+      .buyWgb(new BN(1))
+      .accountsPartial({
+        protocolState: protocolStatePda,
+        buyer: testUser.publicKey,
+        buyerTokenAccount: testUserTokenAccount,
+        treasury: treasuryAta,
+        solReceiver: payer.publicKey,
+        systemProgram: SystemProgram.programId,
+        tokenProgram: TOKEN_2022_PROGRAM_ID,
+        userProfile: null,
+      } as any)
+      .signers([testUser])
+      .rpc({ commitment: "confirmed" });
+  });
+
+  it("buy_wgb debits the buyer exactly price * amount", async () => {
+    const amount = 2;
+    await stockTreasury(amount);
+
+    const state = await readProtocolState();
+    if (!state) throw new Error("ProtocolState not found");
+    const expectedCost = state.wgbPriceLamports.muln(amount).toNumber();
+
+    const before = await connection.getBalance(testUser.publicKey, "confirmed");
+
     await program.methods
-      .buyWgb(new BN(0))
+      .buyWgb(new BN(amount))
       .accountsPartial({
         protocolState: protocolStatePda,
         buyer: testUser.publicKey,
         buyerTokenAccount: testUserTokenAccount,
         treasury: treasuryAta,
         solReceiver: payer.publicKey,
+        wgbMint: wgbMint,
         systemProgram: SystemProgram.programId,
         tokenProgram: TOKEN_2022_PROGRAM_ID,
         userProfile: null,
       } as any)
       .signers([testUser])
       .rpc({ commitment: "confirmed" });
+
+    // Fees are paid by the provider wallet, so the buyer's delta is the purchase alone
+    const after = await connection.getBalance(testUser.publicKey, "confirmed");
+    expect(before - after).to.eq(expectedCost);
+  });
+
+  it("buy_wgb honors a locked quote for exactly its amount and closes it", async () => {
+    await stockTreasury(1);
+    const [quoteLockPda] = PublicKey.findProgramAddressSync(
+      [Buffer.from("quote_lock"), testUser.publicKey.toBuffer()],
+      program.programId
+    );
+
+    await program.methods
+      .lockQuote(new BN(1))
+      .accountsPartial({ protocolState: protocolStatePda, quoteLock: quoteLockPda, buyer: testUser.publicKey })
+      .signers([testUser])
+      .rpc({ commitment: "confirmed" });
+
+    const buyWithQuote = (amount: number) =>
+      program.methods
+        .buyWgb(new BN(amount))
+        .accountsPartial({
+          protocolState: protocolStatePda,
+          buyer: testUser.publicKey,
+          buyerTokenAccount: testUserTokenAccount,
+          treasury: treasuryAta,
+          solReceiver: payer.publicKey,
+          wgbMint: wgbMint,
+          systemProgram: SystemProgram.programId,
+          tokenProgram: TOKEN_2022_PROGRAM_ID,
+          userProfile: null,
+          quoteLock: quoteLockPda,
+        } as any)
+        .signers([testUser])
+        .rpc({ commitment: "confirmed" });
+
+    await expectErrorCode(() => buyWithQuote(2), "QuoteMismatch");
+    await buyWithQuote(1);
+
+    expect(await connection.getAccountInfo(quoteLockPda, "confirmed")).to.eq(null);
+  });
+
+  it("buy_wgb carries fractional points forward instead of dropping them", async () => {
+    const buyer = Keypair.generate();
+    await fundFromPayer(buyer.publicKey, 0.2);
+    const buyerTokenAccount = await ensureUserTokenAccount(buyer.publicKey);
+    const buyerProfilePda = await createUserProfile(buyer);
+
+    const buys = 7;
+    const bps = 2500; // 0.25 points per token
+    await stockTreasury(buys);
+
+    const setRate = (rate: number) =>
+      program.methods
+        .setBuyPointsBps(new BN(rate))
+        .accountsPartial({ protocolState: protocolStatePda, authority: payer.publicKey })
+        .rpc({ commitment: "confirmed" });
+
+    await setRate(bps);
+    try {
+      for (let i = 0; i < buys; i++) {
+        await program.methods
+          .buyWgb(new BN(1))
+          .accountsPartial({
+            protocolState: protocolStatePda,
+            buyer: buyer.publicKey,
+            buyerTokenAccount,
+            treasury: treasuryAta,
+            solReceiver: payer.publicKey,
+            wgbMint: wgbMint,
+            systemProgram: SystemProgram.programId,
+            tokenProgram: TOKEN_2022_PROGRAM_ID,
+            userProfile: buyerProfilePda,
+          } as any)
+          .signers([buyer])
+          .rpc({ commitment: "confirmed" });
+      }
+    } finally {
+      await setRate(0);
+    }
+
+    // 7 * 0.25 = 1.75: one whole point awarded, 0.75 carried as dust
+    const profile = await program.account.userProfile.fetch(buyerProfilePda);
+    expect(profile.points.toNumber()).to.eq(Math.floor((buys * bps) / 10_000));
+    expect(profile.pointsDust.toNumber()).to.eq((buys * bps) % 10_000);
+  });
+
+  it("buy_wgb enforces buy_cooldown_secs between one buyer's purchases", async () => {
+    const buyer = Keypair.generate();
+    await fundFromPayer(buyer.publicKey, 0.2);
+    const buyerTokenAccount = await ensureUserTokenAccount(buyer.publicKey);
+    const buyerProfilePda = await createUserProfile(buyer);
+    await stockTreasury(5);
+
+    const setCooldown = (secs: number) =>
+      program.methods
+        .setBuyCooldownSecs(new BN(secs))
+        .accountsPartial({ protocolState: protocolStatePda, authority: payer.publicKey })
+        .rpc({ commitment: "confirmed" });
+    const buy = (amount: number) =>
+      program.methods
+        .buyWgb(new BN(amount))
+        .accountsPartial({
+          protocolState: protocolStatePda,
+          buyer: buyer.publicKey,
+          buyerTokenAccount,
+          treasury: treasuryAta,
+          solReceiver: payer.publicKey,
+          wgbMint: wgbMint,
+          systemProgram: SystemProgram.programId,
+          tokenProgram: TOKEN_2022_PROGRAM_ID,
+          userProfile: buyerProfilePda,
+        } as any)
+        .signers([buyer])
+        .rpc({ commitment: "confirmed" });
+
+    await setCooldown(2);
+    try {
+      await buy(1);
+      await expectErrorCode(() => buy(2), "BuyCooldown");
+
+      await new Promise((resolve) => setTimeout(resolve, 3_500));
+      await buy(2);
+    } finally {
+      await setCooldown(0);
+    }
+
+    const profile = await program.account.userProfile.fetch(buyerProfilePda);
+    expect(profile.totalVolume.toNumber()).to.eq(3);
+  });
+
+  it("buy_wgb fails with InsufficientTreasury on a transfer-fee mint and leaves the buyer's SOL untouched", async () => {
+    const mintInfo = await getMint(connection, wgbMint, "confirmed", TOKEN_2022_PROGRAM_ID);
+    expect(getTransferFeeConfig(mintInfo)).to.not.eq(null);
+
+    // stockTreasury only mints what the earlier tests buy, so this stays under the 1000 per-buy cap
+    const treasuryBalance = await connection.getTokenAccountBalance(treasuryAta, "confirmed");
+    const amount = Number(treasuryBalance.value.amount) + 1;
+    expect(amount).to.be.at.most(1000);
+
+    const before = await connection.getBalance(testUser.publicKey, "confirmed");
+
+    await expectErrorCode(
+      () =>
+        program.methods
+          .buyWgb(new BN(amount))
+          .accountsPartial({
+            protocolState: protocolStatePda,
+            buyer: testUser.publicKey,
+            buyerTokenAccount: testUserTokenAccount,
+            treasury: treasuryAta,
+            solReceiver: payer.publicKey,
+            wgbMint: wgbMint,
+            systemProgram: SystemProgram.programId,
+            tokenProgram: TOKEN_2022_PROGRAM_ID,
+            userProfile: null,
+          } as any)
+          .signers([testUser])
+          .rpc({ commitment: "confirmed" }),
+      "InsufficientTreasury"
+    );
+
+    const after = await connection.getBalance(testUser.publicKey, "confirmed");
+    expect(after).to.eq(before);
   });
 
   it("burn_wgb succeeds when user_profile is omitted", async () => {
@@ -234,7 +520,7 @@ describe("programs-wgb-protocol step3 optional profile", () => {
     );
 
     await program.methods
-      .burnWgb(new BN(0), requestId)
+      .burnWgb(new BN(1), requestId, 0, new BN(0))
       .accountsPartial({
         protocolState: protocolStatePda,
         user: testUser.publicKey,
@@ -249,10 +535,890 @@ describe("programs-wgb-protocol step3 optional profile", () => {
       .rpc({ commitment: "confirmed" });
   });
 
-  it("buy_wgb fails with InvalidUserProfileAccount when a wrong profile is provided", async () => {
-    try {
+  it("claim_redemption_batch takes MAX_BATCH requests and rejects one more", async () => {
+    const MAX_BATCH = 10; // Mirrors the program constant
+    const fulfiller = Keypair.generate();
+    await fundFromPayer(fulfiller.publicKey, 0.05);
+
+    await stockTreasury(MAX_BATCH);
+    await program.methods
+      .buyWgb(new BN(MAX_BATCH))
+      .accountsPartial({
+        protocolState: protocolStatePda,
+        buyer: testUser.publicKey,
+        buyerTokenAccount: testUserTokenAccount,
+        treasury: treasuryAta,
+        solReceiver: payer.publicKey,
+        systemProgram: SystemProgram.programId,
+        tokenProgram: TOKEN_2022_PROGRAM_ID,
+        userProfile: null,
+      } as any)
+      .signers([testUser])
+      .rpc({ commitment: "confirmed" });
+
+    const requests: PublicKey[] = [];
+    const baseId = Date.now() + 100;
+    for (let i = 0; i < MAX_BATCH; i++) {
+      const requestId = new BN(baseId + i);
+      const [redemptionRequestPda] = PublicKey.findProgramAddressSync(
+        [Buffer.from("redemption"), testUser.publicKey.toBuffer(), requestId.toArrayLike(Buffer, "le", 8)],
+        program.programId
+      );
       await program.methods
-        .buyWgb(new BN(0))
+        .burnWgb(new BN(1), requestId, 0, new BN(0))
+        .accountsPartial({
+          protocolState: protocolStatePda,
+          user: testUser.publicKey,
+          userTokenAccount: testUserTokenAccount,
+          wgbMint: wgbMint,
+          redemptionRequest: redemptionRequestPda,
+          systemProgram: SystemProgram.programId,
+          tokenProgram: TOKEN_2022_PROGRAM_ID,
+          userProfile: null,
+        } as any)
+        .signers([testUser])
+        .rpc({ commitment: "confirmed" });
+      requests.push(redemptionRequestPda);
+    }
+
+    const claim = (accounts: PublicKey[]) =>
+      program.methods
+        .claimRedemptionBatch()
+        .accountsPartial({ protocolState: protocolStatePda, fulfiller: fulfiller.publicKey })
+        .remainingAccounts(accounts.map((pubkey) => ({ pubkey, isSigner: false, isWritable: true })))
+        .signers([fulfiller])
+        .rpc({ commitment: "confirmed" });
+
+    await expectErrorCode(() => claim([...requests, requests[0]]), "BatchTooLarge");
+
+    await claim(requests);
+    for (const pda of requests) {
+      const req = await program.account.redemptionRequest.fetch(pda);
+      expect(req.status).to.eq(1);
+      expect(req.fulfiller.toBase58()).to.eq(fulfiller.publicKey.toBase58());
+    }
+  });
+
+  it("burn_wgb escrows a priority fee that raise_priority_fee adds to and cancel refunds", async () => {
+    await stockTreasury(1);
+    await program.methods
+      .buyWgb(new BN(1))
+      .accountsPartial({
+        protocolState: protocolStatePda,
+        buyer: testUser.publicKey,
+        buyerTokenAccount: testUserTokenAccount,
+        treasury: treasuryAta,
+        solReceiver: payer.publicKey,
+        systemProgram: SystemProgram.programId,
+        tokenProgram: TOKEN_2022_PROGRAM_ID,
+        userProfile: null,
+      } as any)
+      .signers([testUser])
+      .rpc({ commitment: "confirmed" });
+
+    const requestId = new BN(Date.now() + 200);
+    const [redemptionRequestPda] = PublicKey.findProgramAddressSync(
+      [Buffer.from("redemption"), testUser.publicKey.toBuffer(), requestId.toArrayLike(Buffer, "le", 8)],
+      program.programId
+    );
+    await program.methods
+      .burnWgb(new BN(1), requestId, 0, new BN(10_000))
+      .accountsPartial({
+        protocolState: protocolStatePda,
+        user: testUser.publicKey,
+        userTokenAccount: testUserTokenAccount,
+        wgbMint: wgbMint,
+        redemptionRequest: redemptionRequestPda,
+        systemProgram: SystemProgram.programId,
+        tokenProgram: TOKEN_2022_PROGRAM_ID,
+        userProfile: null,
+      } as any)
+      .signers([testUser])
+      .rpc({ commitment: "confirmed" });
+
+    await program.methods
+      .raisePriorityFee(new BN(5_000))
+      .accountsPartial({
+        protocolState: protocolStatePda,
+        redemptionRequest: redemptionRequestPda,
+        user: testUser.publicKey,
+        systemProgram: SystemProgram.programId,
+      })
+      .signers([testUser])
+      .rpc({ commitment: "confirmed" });
+
+    const info = await connection.getAccountInfo(redemptionRequestPda, "confirmed");
+    const rentFloor = await connection.getMinimumBalanceForRentExemption(info!.data.length);
+    expect(info!.lamports - rentFloor).to.eq(15_000);
+
+    await program.methods
+      .cancelOwnRedemption()
+      .accountsPartial({
+        protocolState: protocolStatePda,
+        redemptionRequest: redemptionRequestPda,
+        wgbMint: wgbMint,
+        userTokenAccount: testUserTokenAccount,
+        user: testUser.publicKey,
+        tokenProgram: TOKEN_2022_PROGRAM_ID,
+      } as any)
+      .signers([testUser])
+      .rpc({ commitment: "confirmed" });
+
+    const after = await connection.getAccountInfo(redemptionRequestPda, "confirmed");
+    expect(after!.lamports).to.eq(rentFloor);
+  });
+
+  it("burn_with_serial binds a proven serial once and release_serial frees it after cancel", async () => {
+    await stockTreasury(1);
+    await program.methods
+      .buyWgb(new BN(1))
+      .accountsPartial({
+        protocolState: protocolStatePda,
+        buyer: testUser.publicKey,
+        buyerTokenAccount: testUserTokenAccount,
+        treasury: treasuryAta,
+        solReceiver: payer.publicKey,
+        systemProgram: SystemProgram.programId,
+        tokenProgram: TOKEN_2022_PROGRAM_ID,
+        userProfile: null,
+      } as any)
+      .signers([testUser])
+      .rpc({ commitment: "confirmed" });
+
+    // A two-leaf reserve tree (sorted pairs, as in services/api merkle.ts)
+    const serial = "BAR-0001";
+    const leaf = createHash("sha256").update(serial).digest();
+    const sibling = createHash("sha256").update("BAR-0002").digest();
+    const root = createHash("sha256")
+      .update(Buffer.concat(Buffer.compare(leaf, sibling) <= 0 ? [leaf, sibling] : [sibling, leaf]))
+      .digest();
+    const state = await program.account.protocolState.fetch(protocolStatePda);
+    await program.methods
+      .updateMerkleRoot(Array.from(root), state.provenReserves)
+      .accountsPartial({ protocolState: protocolStatePda, operator: payer.publicKey })
+      .rpc({ commitment: "confirmed" });
+    const serialLeaf = Array.from(leaf);
+    const [redeemedSerialPda] = PublicKey.findProgramAddressSync(
+      [Buffer.from("redeemed_serial"), Buffer.from(serialLeaf)],
+      program.programId
+    );
+    const burnWithSerial = (requestId: BN, proof: number[][] = [Array.from(sibling)], rawSerial = serial) => {
+      const [redemptionRequestPda] = PublicKey.findProgramAddressSync(
+        [Buffer.from("redemption"), testUser.publicKey.toBuffer(), requestId.toArrayLike(Buffer, "le", 8)],
+        program.programId
+      );
+      return {
+        redemptionRequestPda,
+        send: () =>
+          program.methods
+            .burnWithSerial(new BN(1), requestId, serialLeaf, rawSerial, proof)
+            .accountsPartial({
+              protocolState: protocolStatePda,
+              user: testUser.publicKey,
+              userTokenAccount: testUserTokenAccount,
+              wgbMint: wgbMint,
+              redemptionRequest: redemptionRequestPda,
+              redeemedSerial: redeemedSerialPda,
+              systemProgram: SystemProgram.programId,
+              tokenProgram: TOKEN_2022_PROGRAM_ID,
+              userProfile: null,
+            } as any)
+            .signers([testUser])
+            .rpc({ commitment: "confirmed" }),
+      };
+    };
+
+    // Neither an empty path nor a serial that doesn't hash to the leaf is accepted
+    await expectErrorCode(() => burnWithSerial(new BN(Date.now() + 298), []).send(), "InvalidMerkleProof");
+    await expectErrorCode(
+      () => burnWithSerial(new BN(Date.now() + 299), undefined, "BAR-0002").send(),
+      "InvalidSerial"
+    );
+
+    const first = burnWithSerial(new BN(Date.now() + 300));
+    await first.send();
+    const marker = await program.account.redeemedSerial.fetch(redeemedSerialPda);
+    expect(marker.user.toBase58()).to.eq(testUser.publicKey.toBase58());
+    const req = await program.account.redemptionRequest.fetch(first.redemptionRequestPda);
+    expect(req.serialsRecorded.toNumber()).to.eq(1);
+
+    await expectErrorCode(() => burnWithSerial(new BN(Date.now() + 301)).send(), "SerialAlreadyAssigned");
+
+    await program.methods
+      .cancelOwnRedemption()
+      .accountsPartial({
+        protocolState: protocolStatePda,
+        redemptionRequest: first.redemptionRequestPda,
+        wgbMint: wgbMint,
+        userTokenAccount: testUserTokenAccount,
+        user: testUser.publicKey,
+        tokenProgram: TOKEN_2022_PROGRAM_ID,
+      } as any)
+      .signers([testUser])
+      .rpc({ commitment: "confirmed" });
+    await program.methods
+      .releaseSerial()
+      .accountsPartial({
+        protocolState: protocolStatePda,
+        redemptionRequest: first.redemptionRequestPda,
+        redeemedSerial: redeemedSerialPda,
+        user: testUser.publicKey,
+        signer: payer.publicKey,
+      })
+      .rpc({ commitment: "confirmed" });
+    expect(await program.account.redeemedSerial.fetchNullable(redeemedSerialPda)).to.eq(null);
+  });
+
+  it("admin_set_redemption_status will not reopen a cancelled order for a second refund", async () => {
+    await stockTreasury(1);
+    await program.methods
+      .buyWgb(new BN(1))
+      .accountsPartial({
+        protocolState: protocolStatePda,
+        buyer: testUser.publicKey,
+        buyerTokenAccount: testUserTokenAccount,
+        treasury: treasuryAta,
+        solReceiver: payer.publicKey,
+        systemProgram: SystemProgram.programId,
+        tokenProgram: TOKEN_2022_PROGRAM_ID,
+        userProfile: null,
+      } as any)
+      .signers([testUser])
+      .rpc({ commitment: "confirmed" });
+
+    const requestId = new BN(Date.now() + 400);
+    const [redemptionRequestPda] = PublicKey.findProgramAddressSync(
+      [Buffer.from("redemption"), testUser.publicKey.toBuffer(), requestId.toArrayLike(Buffer, "le", 8)],
+      program.programId
+    );
+    const [fulfillerProfilePda] = PublicKey.findProgramAddressSync(
+      [Buffer.from("user_profile"), PublicKey.default.toBuffer()],
+      program.programId
+    );
+    const [redemptionStatsPda] = PublicKey.findProgramAddressSync(
+      [Buffer.from("redemption_stats")],
+      program.programId
+    );
+    await program.methods
+      .burnWgb(new BN(1), requestId, 0, new BN(0))
+      .accountsPartial({
+        protocolState: protocolStatePda,
+        user: testUser.publicKey,
+        userTokenAccount: testUserTokenAccount,
+        wgbMint: wgbMint,
+        redemptionRequest: redemptionRequestPda,
+        systemProgram: SystemProgram.programId,
+        tokenProgram: TOKEN_2022_PROGRAM_ID,
+        userProfile: null,
+      } as any)
+      .signers([testUser])
+      .rpc({ commitment: "confirmed" });
+
+    const cancel = () =>
+      program.methods
+        .cancelRedemption()
+        .accountsPartial({
+          protocolState: protocolStatePda,
+          redemptionRequest: redemptionRequestPda,
+          wgbMint: wgbMint,
+          userTokenAccount: testUserTokenAccount,
+          tokenProgram: TOKEN_2022_PROGRAM_ID,
+          fulfillerProfile: fulfillerProfilePda,
+          authority: payer.publicKey,
+          user: testUser.publicKey,
+          redemptionStats: redemptionStatsPda,
+        } as any)
+        .rpc({ commitment: "confirmed" });
+    await cancel();
+    const balanceAfterRefund = (await connection.getTokenAccountBalance(testUserTokenAccount, "confirmed")).value.amount;
+
+    await expectErrorCode(
+      () =>
+        program.methods
+          .adminSetRedemptionStatus(0, 1)
+          .accountsPartial({
+            protocolState: protocolStatePda,
+            redemptionRequest: redemptionRequestPda,
+            fulfillerProfile: fulfillerProfilePda,
+            authority: payer.publicKey,
+          })
+          .rpc({ commitment: "confirmed" }),
+      "InvalidRedemptionStatus"
+    );
+    await expectErrorCode(cancel, "InvalidRedemptionStatus");
+
+    const req = await program.account.redemptionRequest.fetch(redemptionRequestPda);
+    expect(req.status).to.eq(4);
+    const balance = (await connection.getTokenAccountBalance(testUserTokenAccount, "confirmed")).value.amount;
+    expect(balance).to.eq(balanceAfterRefund);
+  });
+
+  it("adjust_reserves applies a delta and will not shrink reserves below supply", async () => {
+    const adjust = (delta: number) =>
+      program.methods
+        .adjustReserves(new BN(delta), Array.from(Buffer.alloc(32, 9)))
+        .accountsPartial({ protocolState: protocolStatePda, operator: payer.publicKey })
+        .rpc({ commitment: "confirmed" });
+
+    const before = await program.account.protocolState.fetch(protocolStatePda);
+    await adjust(5);
+    const raised = await program.account.protocolState.fetch(protocolStatePda);
+    expect(raised.provenReserves.toString()).to.eq(before.provenReserves.addn(5).toString());
+
+    await adjust(-1_000_000_000);
+    const clamped = await program.account.protocolState.fetch(protocolStatePda);
+    expect(clamped.provenReserves.toString()).to.eq(BN.min(raised.totalSupply, raised.provenReserves).toString());
+
+    const shortfall = before.provenReserves.sub(clamped.provenReserves);
+    if (shortfall.gtn(0)) await adjust(shortfall.toNumber());
+  });
+
+  it("award_points fails with MathOverflow instead of clamping points", async () => {
+    const user = Keypair.generate();
+    await fundFromPayer(user.publicKey, 0.05);
+    const userProfilePda = await createUserProfile(user);
+    const u64Max = new BN("18446744073709551615");
+
+    const award = (amount: BN, nonce: number) =>
+      program.methods
+        .awardPoints(amount, new BN(nonce))
+        .accountsPartial({
+          protocolState: protocolStatePda,
+          userProfile: userProfilePda,
+          user: user.publicKey,
+          operator: payer.publicKey,
+        })
+        .rpc({ commitment: "confirmed" });
+
+    await award(u64Max, 1);
+
+    try {
+      await award(new BN(1), 2);
+      expect.fail("Expected award_points to overflow");
+    } catch (err) {
+      const maybeCode = (err as any)?.error?.errorCode?.code;
+      expect(maybeCode === "MathOverflow" || String(err).includes("MathOverflow")).to.eq(true);
+    }
+
+    const profile = await program.account.userProfile.fetch(userProfilePda);
+    expect(profile.points.eq(u64Max)).to.eq(true);
+  });
+
+  it("coverage_bps stays equal to reserves / supply across mint and burn", async () => {
+    const expectCoverageInvariant = async () => {
+      const state = await program.account.protocolState.fetch(protocolStatePda);
+      const expected = state.totalSupply.isZero()
+        ? 65535
+        : BN.min(state.provenReserves.muln(10_000).div(state.totalSupply), new BN(65535)).toNumber();
+      expect(state.coverageBps).to.eq(expected);
+    };
+
+    await program.methods
+      .refreshCoverage()
+      .accountsPartial({ protocolState: protocolStatePda })
+      .rpc({ commitment: "confirmed" });
+    await expectCoverageInvariant();
+
+    // stockTreasury moves reserves (merkle update) and supply (mint)
+    await stockTreasury(1);
+    await expectCoverageInvariant();
+
+    const requestId = new BN(Date.now() + 3);
+    const [redemptionRequestPda] = PublicKey.findProgramAddressSync(
+      [Buffer.from("redemption"), testUser.publicKey.toBuffer(), requestId.toArrayLike(Buffer, "le", 8)],
+      program.programId
+    );
+    await program.methods
+      .burnWgb(new BN(1), requestId, 0, new BN(0))
+      .accountsPartial({
+        protocolState: protocolStatePda,
+        user: testUser.publicKey,
+        userTokenAccount: testUserTokenAccount,
+        wgbMint: wgbMint,
+        redemptionRequest: redemptionRequestPda,
+        systemProgram: SystemProgram.programId,
+        tokenProgram: TOKEN_2022_PROGRAM_ID,
+        userProfile: null,
+      } as any)
+      .signers([testUser])
+      .rpc({ commitment: "confirmed" });
+    await expectCoverageInvariant();
+  });
+
+  it("ensure_initialized fails with AlreadyInitialized on an existing state", async () => {
+    try {
+      await program.methods
+        .ensureInitialized()
+        .accountsPartial({ protocolState: protocolStatePda })
+        .rpc({ commitment: "confirmed" });
+      expect.fail("Expected ensure_initialized to fail");
+    } catch (err) {
+      const maybeCode = (err as any)?.error?.errorCode?.code;
+      expect(maybeCode === "AlreadyInitialized" || String(err).includes("AlreadyInitialized")).to.eq(true);
+    }
+  });
+
+  it("buy_wgb fails with ZeroAmount for an empty purchase", async () => {
+    try {
+      await program.methods
+        .buyWgb(new BN(0))
+        .accountsPartial({
+          protocolState: protocolStatePda,
+          buyer: testUser.publicKey,
+          buyerTokenAccount: testUserTokenAccount,
+          treasury: treasuryAta,
+          solReceiver: payer.publicKey,
+          systemProgram: SystemProgram.programId,
+          tokenProgram: TOKEN_2022_PROGRAM_ID,
+          userProfile: null,
+        } as any)
+        .signers([testUser])
+        .rpc({ commitment: "confirmed" });
+
+      expect.fail("Expected buy_wgb to reject a zero amount");
+    } catch (err) {
+      const maybeCode = (err as any)?.error?.errorCode?.code;
+      expect(maybeCode === "ZeroAmount" || String(err).includes("ZeroAmount")).to.eq(true);
+    }
+  });
+
+  it("burn_wgb fails with ZeroAmount for an empty redemption", async () => {
+    const requestId = new BN(Date.now() + 2);
+    const [redemptionRequestPda] = PublicKey.findProgramAddressSync(
+      [Buffer.from("redemption"), testUser.publicKey.toBuffer(), requestId.toArrayLike(Buffer, "le", 8)],
+      program.programId
+    );
+
+    await expectErrorCode(
+      () =>
+        program.methods
+          .burnWgb(new BN(0), requestId, 0, new BN(0))
+          .accountsPartial({
+            protocolState: protocolStatePda,
+            user: testUser.publicKey,
+            userTokenAccount: testUserTokenAccount,
+            wgbMint: wgbMint,
+            redemptionRequest: redemptionRequestPda,
+            systemProgram: SystemProgram.programId,
+            tokenProgram: TOKEN_2022_PROGRAM_ID,
+            userProfile: null,
+          } as any)
+          .signers([testUser])
+          .rpc({ commitment: "confirmed" }),
+      "ZeroAmount"
+    );
+  });
+
+  it("burn_wgb fails with NonWholeRedemption when rounding rejects partial bars", async () => {
+    const setRounding = (mode: number, barUnit: number) =>
+      program.methods
+        .setRedemptionRounding(mode, new BN(barUnit))
+        .accountsPartial({ protocolState: protocolStatePda, authority: payer.publicKey })
+        .rpc({ commitment: "confirmed" });
+
+    const requestId = new BN(Date.now() + 4);
+    const [redemptionRequestPda] = PublicKey.findProgramAddressSync(
+      [Buffer.from("redemption"), testUser.publicKey.toBuffer(), requestId.toArrayLike(Buffer, "le", 8)],
+      program.programId
+    );
+
+    await setRounding(2, 2); // REDEMPTION_ROUND_REJECT, 2-token bars
+    try {
+      await expectErrorCode(
+        () =>
+          program.methods
+            .burnWgb(new BN(1), requestId, 0, new BN(0))
+            .accountsPartial({
+              protocolState: protocolStatePda,
+              user: testUser.publicKey,
+              userTokenAccount: testUserTokenAccount,
+              wgbMint: wgbMint,
+              redemptionRequest: redemptionRequestPda,
+              systemProgram: SystemProgram.programId,
+              tokenProgram: TOKEN_2022_PROGRAM_ID,
+              userProfile: null,
+            } as any)
+            .signers([testUser])
+            .rpc({ commitment: "confirmed" }),
+        "NonWholeRedemption"
+      );
+    } finally {
+      await setRounding(0, 0);
+    }
+  });
+
+  it("try_auto_unpause fails with AutoUnpauseNotDue before the scheduled end", async () => {
+    const until = new BN(Math.floor(Date.now() / 1000) + 3600);
+    await program.methods
+      .setPausedUntil(until)
+      .accountsPartial({ protocolState: protocolStatePda, authority: payer.publicKey })
+      .rpc({ commitment: "confirmed" });
+    try {
+      await expectErrorCode(
+        () =>
+          program.methods
+            .tryAutoUnpause()
+            .accountsPartial({ protocolState: protocolStatePda, signer: testUser.publicKey })
+            .signers([testUser])
+            .rpc({ commitment: "confirmed" }),
+        "AutoUnpauseNotDue"
+      );
+      const state = await program.account.protocolState.fetch(protocolStatePda);
+      expect(state.isPaused).to.eq(true);
+      expect(state.pausedUntil.toString()).to.eq(until.toString());
+    } finally {
+      await program.methods
+        .setPaused(false)
+        .accountsPartial({ protocolState: protocolStatePda, authority: payer.publicKey })
+        .rpc({ commitment: "confirmed" });
+    }
+  });
+
+  it("set_time_windows rejects out-of-range or inconsistent windows", async () => {
+    const setWindows = (quoteLock: number, shipToConfirm: number, holdback: number) =>
+      program.methods
+        .setTimeWindows({
+          quoteLockSecs: new BN(quoteLock),
+          redemption: {
+            pendingExpirySecs: new BN(0),
+            claimToShipSecs: new BN(0),
+            shipToConfirmSecs: new BN(shipToConfirm),
+          },
+          rewardHoldbackSecs: new BN(holdback),
+        })
+        .accountsPartial({ protocolState: protocolStatePda, authority: payer.publicKey })
+        .rpc({ commitment: "confirmed" });
+
+    await expectErrorCode(() => setWindows(601, 0, 0), "InvalidConfig"); // Above MAX_QUOTE_LOCK_SECS
+    await expectErrorCode(() => setWindows(60, 86_400, 3_600), "InvalidConfig"); // Holdback < auto-confirm
+  });
+
+  it("a held fulfiller reward above u16::MAX is released in full and can no longer be disputed", async () => {
+    const reward = 100_000; // Would not fit the request's u16 held_reward
+    const setPointsConfig = (fulfillerReward: number) =>
+      program.methods
+        .setPointsConfig(new BN(1), new BN(2), new BN(fulfillerReward), new BN(0))
+        .accountsPartial({ protocolState: protocolStatePda, authority: payer.publicKey })
+        .rpc({ commitment: "confirmed" });
+    const setHoldback = (secs: number) =>
+      program.methods
+        .setRewardHoldbackSecs(new BN(secs))
+        .accountsPartial({ protocolState: protocolStatePda, authority: payer.publicKey })
+        .rpc({ commitment: "confirmed" });
+
+    const fulfiller = Keypair.generate();
+    await fundFromPayer(fulfiller.publicKey, 0.05);
+    const fulfillerProfilePda = await createUserProfile(fulfiller);
+
+    await stockTreasury(1);
+    await program.methods
+      .buyWgb(new BN(1))
+      .accountsPartial({
+        protocolState: protocolStatePda,
+        buyer: testUser.publicKey,
+        buyerTokenAccount: testUserTokenAccount,
+        treasury: treasuryAta,
+        solReceiver: payer.publicKey,
+        systemProgram: SystemProgram.programId,
+        tokenProgram: TOKEN_2022_PROGRAM_ID,
+        userProfile: null,
+      } as any)
+      .signers([testUser])
+      .rpc({ commitment: "confirmed" });
+
+    const requestId = new BN(Date.now() + 400);
+    const [redemptionRequestPda] = PublicKey.findProgramAddressSync(
+      [Buffer.from("redemption"), testUser.publicKey.toBuffer(), requestId.toArrayLike(Buffer, "le", 8)],
+      program.programId
+    );
+    const [heldRewardPda] = PublicKey.findProgramAddressSync(
+      [Buffer.from("held_reward"), redemptionRequestPda.toBuffer()],
+      program.programId
+    );
+    await program.methods
+      .burnWgb(new BN(1), requestId, 0, new BN(0))
+      .accountsPartial({
+        protocolState: protocolStatePda,
+        user: testUser.publicKey,
+        userTokenAccount: testUserTokenAccount,
+        wgbMint: wgbMint,
+        redemptionRequest: redemptionRequestPda,
+        systemProgram: SystemProgram.programId,
+        tokenProgram: TOKEN_2022_PROGRAM_ID,
+        userProfile: null,
+      } as any)
+      .signers([testUser])
+      .rpc({ commitment: "confirmed" });
+
+    await program.methods
+      .claimRedemption()
+      .accountsPartial({
+        protocolState: protocolStatePda,
+        redemptionRequest: redemptionRequestPda,
+        fulfiller: fulfiller.publicKey,
+        fulfillerProfile: fulfillerProfilePda,
+      })
+      .signers([fulfiller])
+      .rpc({ commitment: "confirmed" });
+    await program.methods
+      .markShipped(Array.from(Buffer.alloc(32)))
+      .accountsPartial({ redemptionRequest: redemptionRequestPda, fulfiller: fulfiller.publicKey })
+      .signers([fulfiller])
+      .rpc({ commitment: "confirmed" });
+
+    await setPointsConfig(reward);
+    await setHoldback(2);
+    try {
+      await program.methods
+        .confirmDelivery(Array.from(Buffer.alloc(32)))
+        .accountsPartial({
+          protocolState: protocolStatePda,
+          redemptionRequest: redemptionRequestPda,
+          fulfillerProfile: fulfillerProfilePda,
+          signer: payer.publicKey,
+          heldReward: heldRewardPda,
+          fulfiller: fulfiller.publicKey,
+        } as any)
+        .rpc({ commitment: "confirmed" });
+
+      const held = await program.account.heldReward.fetch(heldRewardPda);
+      expect(held.amount.toNumber()).to.eq(reward);
+      const confirmed = await program.account.redemptionRequest.fetch(redemptionRequestPda);
+      expect(confirmed.heldReward).to.eq(65_535);
+
+      const release = () =>
+        program.methods
+          .releaseFulfillerRewards()
+          .accountsPartial({
+            protocolState: protocolStatePda,
+            redemptionRequest: redemptionRequestPda,
+            fulfillerProfile: fulfillerProfilePda,
+            heldReward: heldRewardPda,
+          })
+          .rpc({ commitment: "confirmed" });
+      await expectErrorCode(release, "RewardHoldbackActive");
+
+      await new Promise((resolve) => setTimeout(resolve, 3_500));
+      const before = await program.account.userProfile.fetch(fulfillerProfilePda);
+      await release();
+      const after = await program.account.userProfile.fetch(fulfillerProfilePda);
+      expect(after.points.sub(before.points).toNumber()).to.eq(reward);
+      expect((await program.account.heldReward.fetch(heldRewardPda)).amount.toNumber()).to.eq(0);
+
+      // Nothing is held any more, so the redeemer can no longer reopen the order
+      await expectErrorCode(
+        () =>
+          program.methods
+            .disputeDelivery()
+            .accountsPartial({
+              protocolState: protocolStatePda,
+              redemptionRequest: redemptionRequestPda,
+              user: testUser.publicKey,
+              heldReward: heldRewardPda,
+            } as any)
+            .signers([testUser])
+            .rpc({ commitment: "confirmed" }),
+        "InvalidRedemptionStatus"
+      );
+    } finally {
+      await setHoldback(0);
+      await setPointsConfig(5);
+    }
+  });
+
+  it("resolve_challenge fails with WithdrawalThrottled when the bond payout exceeds the cap", async () => {
+    const setThrottle = (windowSecs: number, solLimit: number) =>
+      program.methods
+        .setWithdrawalThrottle(new BN(windowSecs), new BN(solLimit), new BN(0), 10_000)
+        .accountsPartial({
+          protocolState: protocolStatePda,
+          withdrawalThrottle: withdrawalThrottlePda,
+          authority: payer.publicKey,
+        })
+        .rpc({ commitment: "confirmed" });
+    const [proofChallengePda] = PublicKey.findProgramAddressSync(
+      [Buffer.from("proof_challenge")],
+      program.programId
+    );
+    const resolve = () =>
+      program.methods
+        .resolveChallenge(false)
+        .accountsPartial({
+          protocolState: protocolStatePda,
+          proofChallenge: proofChallengePda,
+          challenger: payer.publicKey,
+          solReceiver: payer.publicKey,
+          withdrawalThrottle: withdrawalThrottlePda,
+          authority: payer.publicKey,
+        })
+        .rpc({ commitment: "confirmed" });
+
+    await program.methods
+      .challengeProof(new BN(LAMPORTS_PER_SOL / 100))
+      .accountsPartial({ protocolState: protocolStatePda, challenger: payer.publicKey })
+      .rpc({ commitment: "confirmed" });
+
+    // The challenged attestation can't be replaced while the challenge is open
+    await expectErrorCode(
+      () =>
+        program.methods
+          .updateMerkleRoot(Array.from(Buffer.alloc(32, 8)), new BN(0))
+          .accountsPartial({ protocolState: protocolStatePda, operator: payer.publicKey })
+          .rpc({ commitment: "confirmed" }),
+      "ProofUnderChallenge"
+    );
+
+    await setThrottle(3_600, 1_000);
+    try {
+      await expectErrorCode(resolve, "WithdrawalThrottled");
+    } finally {
+      await setThrottle(0, 0); // window 0 turns the throttle off
+      await resolve();
+    }
+    expect(await connection.getAccountInfo(proofChallengePda)).to.eq(null);
+  });
+
+  it("mint_wgb fails with ZeroAmount", async () => {
+    await expectErrorCode(
+      () =>
+        program.methods
+          .mintWgb(new BN(0))
+          .accountsPartial({
+            protocolState: protocolStatePda,
+            wgbMint: wgbMint,
+            treasury: treasuryAta,
+            tokenProgram: TOKEN_2022_PROGRAM_ID,
+            operator: payer.publicKey,
+          } as any)
+          .rpc({ commitment: "confirmed" }),
+      "ZeroAmount"
+    );
+  });
+
+  it("record_yield_distribution fails with ZeroAmount", async () => {
+    await expectErrorCode(
+      () =>
+        program.methods
+          .recordYieldDistribution(new BN(0))
+          .accountsPartial({ protocolState: protocolStatePda, operator: payer.publicKey })
+          .rpc({ commitment: "confirmed" }),
+      "ZeroAmount"
+    );
+  });
+
+  it("award_points fails with ZeroAmount", async () => {
+    await expectErrorCode(
+      () =>
+        program.methods
+          .awardPoints(new BN(0), new BN(1))
+          .accountsPartial({
+            protocolState: protocolStatePda,
+            userProfile: wrongProfilePda,
+            user: wrongProfileUser.publicKey,
+            operator: payer.publicKey,
+          })
+          .rpc({ commitment: "confirmed" }),
+      "ZeroAmount"
+    );
+  });
+
+  it("award_points fails with SelfTarget when the operator awards itself", async () => {
+    const [operatorProfilePda] = PublicKey.findProgramAddressSync(
+      [Buffer.from("user_profile"), payer.publicKey.toBuffer()],
+      program.programId
+    );
+    if (!(await connection.getAccountInfo(operatorProfilePda))) {
+      await createUserProfile(payer);
+    }
+
+    await expectErrorCode(
+      () =>
+        program.methods
+          .awardPoints(new BN(1), new BN(Date.now()))
+          .accountsPartial({
+            protocolState: protocolStatePda,
+            userProfile: operatorProfilePda,
+            user: payer.publicKey,
+            operator: payer.publicKey,
+          })
+          .rpc({ commitment: "confirmed" }),
+      "SelfTarget"
+    );
+  });
+
+  it("transfer_points fails with ZeroAmount", async () => {
+    const sender = Keypair.generate();
+    await fundFromPayer(sender.publicKey, 0.05);
+    const senderProfilePda = await createUserProfile(sender);
+
+    await expectErrorCode(
+      () =>
+        program.methods
+          .transferPoints(new BN(0))
+          .accountsPartial({
+            protocolState: protocolStatePda,
+            senderProfile: senderProfilePda,
+            recipientProfile: wrongProfilePda,
+            recipient: wrongProfileUser.publicKey,
+            sender: sender.publicKey,
+          })
+          .signers([sender])
+          .rpc({ commitment: "confirmed" }),
+      "ZeroAmount"
+    );
+  });
+
+  it("migrate_profile moves standing to a new wallet and refuses populated targets", async () => {
+    const profilePda = (user: PublicKey) =>
+      PublicKey.findProgramAddressSync([Buffer.from("user_profile"), user.toBuffer()], program.programId)[0];
+    const award = (user: Keypair, amount: number) =>
+      program.methods
+        .awardPoints(new BN(amount), new BN(1))
+        .accountsPartial({
+          protocolState: protocolStatePda,
+          userProfile: profilePda(user.publicKey),
+          user: user.publicKey,
+          operator: payer.publicKey,
+        })
+        .rpc({ commitment: "confirmed" });
+    const migrate = (from: Keypair, to: Keypair) =>
+      program.methods
+        .migrateProfile()
+        .accountsPartial({
+          oldProfile: profilePda(from.publicKey),
+          newProfile: profilePda(to.publicKey),
+          user: from.publicKey,
+          newUser: to.publicKey,
+          systemProgram: SystemProgram.programId,
+        })
+        .signers([from, to])
+        .rpc({ commitment: "confirmed" });
+
+    const oldWallet = Keypair.generate();
+    const newWallet = Keypair.generate();
+    const occupied = Keypair.generate();
+    await fundFromPayer(oldWallet.publicKey, 0.05);
+    await fundFromPayer(occupied.publicKey, 0.05);
+    await createUserProfile(oldWallet);
+    await createUserProfile(occupied);
+    await award(oldWallet, 7);
+    await award(occupied, 1);
+
+    await migrate(oldWallet, newWallet);
+    const migrated = await program.account.userProfile.fetch(profilePda(newWallet.publicKey));
+    expect(migrated.user.toBase58()).to.eq(newWallet.publicKey.toBase58());
+    expect(migrated.points.toNumber()).to.eq(7);
+    expect(await program.account.userProfile.fetchNullable(profilePda(oldWallet.publicKey))).to.eq(null);
+
+    await createUserProfile(oldWallet);
+    await award(oldWallet, 3);
+    await expectErrorCode(() => migrate(oldWallet, occupied), "TargetProfileExists");
+  });
+
+  it("buy_wgb fails with InvalidUserProfileAccount when a wrong profile is provided", async () => {
+    try {
+      await program.methods
+        .buyWgb(new BN(1))
         .accountsPartial({
           protocolState: protocolStatePda,
           buyer: testUser.publicKey,
@@ -293,7 +1459,7 @@ describe("programs-wgb-protocol step3 optional profile", () => {
 
     try {
       await program.methods
-        .burnWgb(new BN(0), requestId)
+        .burnWgb(new BN(1), requestId, 0, new BN(0))
         .accountsPartial({
           protocolState: protocolStatePda,
           user: testUser.publicKey,
@@ -142,6 +142,8 @@ async function main() {
   const program = new Program<WgbProtocol>(idlJson as any, provider);
   const programId = new PublicKey(idlJson.address);
   const [protocolStatePda] = PublicKey.findProgramAddressSync([Buffer.from("protocol_state")], programId);
+  const [proofHistoryPda] = PublicKey.findProgramAddressSync([Buffer.from("proof_history")], programId);
+  const [withdrawalThrottlePda] = PublicKey.findProgramAddressSync([Buffer.from("withdrawal_throttle")], programId);
 
   const pre = await fetchProtocolStateSnapshot(connection, protocolStatePda);
 
@@ -198,6 +200,8 @@ async function main() {
     .accountsPartial({
       protocolState: protocolStatePda,
       operator: operator.publicKey,
+      proofHistory: proofHistoryPda,
+      withdrawalThrottle: withdrawalThrottlePda,
     })
     .rpc();
 
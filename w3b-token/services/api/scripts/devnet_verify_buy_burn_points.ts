@@ -262,7 +262,7 @@ async function main() {
       programId
     );
     const sig = await (program.methods as any)
-      .burnWgb(new anchor.BN(args.amount), new anchor.BN(requestId.toString()))
+      .burnWgb(new anchor.BN(args.amount), new anchor.BN(requestId.toString()), 0, new anchor.BN(0))
       .accountsPartial({
         protocolState: protocolStatePda,
         user: userNoProfile.publicKey,
@@ -355,7 +355,7 @@ async function main() {
     );
 
     const burnSig = await (program.methods as any)
-      .burnWgb(new anchor.BN(args.amount), new anchor.BN(requestId.toString()))
+      .burnWgb(new anchor.BN(args.amount), new anchor.BN(requestId.toString()), 0, new anchor.BN(0))
       .accountsPartial({
         protocolState: protocolStatePda,
         user: userWithProfile.publicKey,
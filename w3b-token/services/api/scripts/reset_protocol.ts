@@ -366,7 +366,7 @@ async function freshTokenSetup(programIdStr: string) {
     };
 
     const initTx = await (program.methods as any)
-      .initializeV2()
+      .initializeV2(0) // expected_decimals — must match the mint above
       .accountsPartial(accounts as any)
       .rpc();
 
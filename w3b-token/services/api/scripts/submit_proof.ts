@@ -361,6 +361,8 @@ async function main() {
 
   // Find PDA
   const [protocolStatePda] = PublicKey.findProgramAddressSync([Buffer.from("protocol_state")], programId);
+  const [proofHistoryPda] = PublicKey.findProgramAddressSync([Buffer.from("proof_history")], programId);
+  const [withdrawalThrottlePda] = PublicKey.findProgramAddressSync([Buffer.from("withdrawal_throttle")], programId);
   console.log(`   Protocol State: ${protocolStatePda.toBase58()}`);
 
   // Submit proofs
@@ -417,6 +419,8 @@ async function main() {
         .accountsPartial({
           protocolState: protocolStatePda,
           operator: keypair.publicKey,
+          proofHistory: proofHistoryPda,
+          withdrawalThrottle: withdrawalThrottlePda,
         })
         .rpc();
 